@@ -0,0 +1,46 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    // Only works when PWD is set and the root path exists
+    #[test_with::all(env(PWD), any(file(/nothing), path(/)))]
+    #[test]
+    fn test_works_with_all_conditions() {
+        assert!(true);
+    }
+
+    #[test_with::all(env(PWD), any(env(NOTHING), file(/nothing)))]
+    #[test]
+    fn test_ignored_without_any_condition() {
+        panic!("should be ignored")
+    }
+
+    // Only works when one of the conditions is met
+    #[test_with::any(env(NOTHING), not(file(/nothing)))]
+    #[test]
+    fn test_works_with_any_condition() {
+        assert!(true);
+    }
+
+    // Only works when PWD is set
+    #[test_with::when(env(PWD))]
+    #[test]
+    fn test_works_when_condition_met() {
+        assert!(true);
+    }
+
+    #[test_with::unless(env(PWD))]
+    #[test]
+    fn test_ignored_unless_condition_not_met() {
+        panic!("should be ignored")
+    }
+}
+
+#[test_with::any(env(NOTHING), all(path(/), file(/nothing)))]
+#[cfg(test)]
+mod ignore_mod {
+    #[test]
+    fn test_ignored() {
+        panic!("should be ignored")
+    }
+}
@@ -0,0 +1,16 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    #[test_with::config("examples/fixtures/tests.toml": integration.enabled)]
+    #[test]
+    fn test_works_with_config() {
+        assert!(true);
+    }
+
+    #[test_with::config("examples/fixtures/tests.toml": integration.slow)]
+    #[test]
+    fn test_ignored_with_config() {
+        panic!("should be ignored")
+    }
+}
@@ -25,12 +25,72 @@ mod tests {
         panic!("should be ignored")
     }
 
+    #[test_with::env(CARGO_*)]
+    #[test]
+    fn env_test_works_with_prefix() {
+        assert!(true);
+    }
+
+    #[test_with::env(NOT_SAYING_*)]
+    #[test]
+    fn test_ignored_with_prefix() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::env(PWD, NOT_SAYING, NOTHING, at_least = 1)]
+    #[test]
+    fn env_test_works_with_enough_vars() {
+        assert!(true);
+    }
+
+    #[test_with::env(PWD, NOT_SAYING, NOTHING, at_least = 2)]
+    #[test]
+    fn test_ignored_without_enough_vars() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::env(PWD, nonempty)]
+    #[test]
+    fn env_test_works_with_nonempty_var() {
+        assert!(true);
+    }
+
+    #[test_with::env(NOTHING in [staging, prod])]
+    #[test]
+    fn test_ignored_with_value_set() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::env_file("examples/fixtures/.env.test": DB_URL, API_KEY)]
+    #[test]
+    fn test_works_with_env_file() {
+        assert!(true);
+    }
+
+    #[test_with::env_file("examples/fixtures/.env.test": DB_URL, NOT_DEFINED)]
+    #[test]
+    fn test_ignored_with_env_file() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::env_true(TEST_WITH_NOT_EXIST_TOGGLE)]
+    #[test]
+    fn test_ignored_with_env_true() {
+        panic!("should be ignored")
+    }
+
     #[test_with::no_env(GITHUB_ACTIONS)]
     #[test]
     fn test_ignore_in_github_action() {
         println!("should be ignored in GITHUB_ACTION");
     }
 
+    #[test_with::skip_if_ci]
+    #[test]
+    fn test_ignore_in_ci() {
+        println!("should be ignored in CI");
+    }
+
     #[test_with::env(
         IT_SOME_LONG_ENV_VAR_NAME_TEST_URL,
         IT_SOME_LONG_ENV_VAR_NAME_TEST_AUTH_TOKEN
@@ -74,6 +134,15 @@ pub mod ignore_pub_test_mod {
     }
 }
 
+#[test_with::env(PWD)]
+#[cfg(test)]
+mod private_test_mod {
+    #[test]
+    fn test_works() {
+        assert!(true);
+    }
+}
+
 #[test_with::env(NOTHING)]
 #[cfg(test)]
 mod ignore_private_test_mod {
@@ -82,3 +151,115 @@ mod ignore_private_test_mod {
         panic!("should be ignored")
     }
 }
+
+#[test_with::env(NOTHING)]
+mod ignore_mod_with_helpers {
+    use std::path::Path;
+
+    const HOSTNAME_FILE: &str = "/etc/hostname";
+
+    fn hostname_exists() -> bool {
+        Path::new(HOSTNAME_FILE).exists()
+    }
+
+    #[test]
+    fn test_ignored() {
+        assert!(hostname_exists());
+        panic!("should be ignored")
+    }
+}
+
+// `allow_non_test` is taken out of the condition on the module as well
+#[test_with::env(PWD, allow_non_test)]
+mod allow_non_test_mod {
+    #[test]
+    fn test_works() {
+        assert!(true);
+    }
+}
+
+// `#[test]` only works on the free functions, so the test cases sharing the methods of a fixture are
+// grouped in a module rather than written as the methods of an impl block
+#[test_with::env(PWD)]
+mod grouped_mod_with_fixture {
+    struct Fixture {
+        pwd: String,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Fixture {
+                pwd: std::env::var("PWD").unwrap_or_default(),
+            }
+        }
+
+        fn is_absolute(&self) -> bool {
+            std::path::Path::new(&self.pwd).is_absolute()
+        }
+    }
+
+    #[test]
+    fn test_works() {
+        assert!(Fixture::new().is_absolute());
+    }
+}
+
+// Only works with a display, ex: `DISPLAY` or `WAYLAND_DISPLAY` is set on Linux
+#[test_with::display]
+#[test]
+fn display_test_works() {
+    assert!(true);
+}
+
+// No machine has so many monitors
+#[test_with::monitors(1000)]
+#[test]
+fn monitors_test_ignored() {
+    panic!("should be ignored")
+}
+
+// The unit does not exist
+#[test_with::systemd_active(nothing.service)]
+#[test]
+fn systemd_test_ignored() {
+    panic!("should be ignored")
+}
+
+#[test_with::command("cargo --version")]
+#[test]
+fn command_test_works() {
+    assert!(true);
+}
+
+// The command exits with non-zero
+#[test_with::command("cargo --no-such-flag")]
+#[test]
+fn command_test_ignored() {
+    panic!("should be ignored")
+}
+
+#[test_with::command_output("cargo --version", contains = "cargo")]
+#[test]
+fn command_output_test_works() {
+    assert!(true);
+}
+
+// The output does not contain the text
+#[test_with::command_output("cargo --version", contains = "nothing")]
+#[test]
+fn command_output_test_ignored() {
+    panic!("should be ignored")
+}
+
+// The fixture pins the major version 1 of cargo
+#[test_with::file_version("examples/fixtures/.cargo-version" == cargo)]
+#[test]
+fn file_version_test_works() {
+    assert!(true);
+}
+
+#[test_with::file_version("examples/fixtures/.cargo-version" > cargo)]
+#[test]
+fn file_version_test_ignored() {
+    panic!("should be ignored")
+}
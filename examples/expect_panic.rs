@@ -0,0 +1,24 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    #[test_with::expect_panic_if(os(linux, macos))]
+    #[test]
+    fn test_panic_on_unix() {
+        if cfg!(any(target_os = "linux", target_os = "macos")) {
+            panic!("should panic on unix")
+        }
+    }
+
+    #[test_with::expect_panic_if(env(NOTHING))]
+    #[test]
+    fn test_works_without_panic() {
+        assert!(true);
+    }
+
+    #[test_with::expect_panic_if(any(os(windows), not(env(NOTHING))))]
+    #[test]
+    fn test_panic_with_combined_condition() {
+        panic!("should panic")
+    }
+}
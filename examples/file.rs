@@ -9,6 +9,13 @@ mod tests {
         assert!(true);
     }
 
+    // the quoted path with spaces is kept
+    #[test_with::file("examples/fixtures/with space.txt")]
+    #[test]
+    fn test_works_with_spaced_path() {
+        assert!(true);
+    }
+
     // nothing file does not exist
     #[test_with::file(/etc/nothing)]
     #[test]
@@ -65,6 +72,27 @@ mod tests {
         assert!(true);
     }
 
+    // etc is a directory
+    #[test_with::dir(/etc)]
+    #[test]
+    fn test_works_for_dir() {
+        assert!(true);
+    }
+
+    // hostname exists, but not a directory
+    #[test_with::dir(/etc/hostname)]
+    #[test]
+    fn test_ignored_for_dir() {
+        panic!("should be ignored")
+    }
+
+    // etc is not empty
+    #[test_with::dir_not_empty(/etc)]
+    #[test]
+    fn test_works_for_not_empty_dir() {
+        assert!(true);
+    }
+
     // nothing does not exist
     #[test_with::file(/etc, /nothing)]
     #[test]
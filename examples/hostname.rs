@@ -0,0 +1,39 @@
+fn main() {}
+
+#[cfg(test)]
+mod hostname_tests {
+    // `*` matches any hostname
+    #[test_with::hostname(*)]
+    #[test]
+    fn test_works() {
+        assert!(true);
+    }
+
+    #[test_with::hostname(not-exist-host-01, not-exist-host-02)]
+    #[test]
+    fn test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::hostname(NOT-EXIST-*)]
+    #[test]
+    fn test_ignored_with_wildcard() {
+        panic!("should be ignored")
+    }
+}
+
+#[test_with::hostname(*)]
+pub mod workable_hostname_mod {
+    #[test]
+    fn test_works() {
+        assert!(true);
+    }
+}
+
+#[test_with::hostname(not-exist-host-*)]
+mod ignore_private_hostname_mod {
+    #[test]
+    fn test_ignored() {
+        panic!("should be ignored")
+    }
+}
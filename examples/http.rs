@@ -15,6 +15,18 @@ mod http_tests {
     fn test_ignored() {
         panic!("should be ignored")
     }
+
+    #[test_with::http(httpbin.org, max_ms=10000)]
+    #[test]
+    fn test_works_in_time() {
+        assert!(true);
+    }
+
+    #[test_with::http(not.exist.com || httpbin.org)]
+    #[test]
+    fn test_works_with_any_mirror() {
+        assert!(true);
+    }
 }
 
 #[test_with::http(httpbin.org)]
@@ -74,6 +86,12 @@ mod https_tests {
     fn test_ignored() {
         panic!("should be ignored")
     }
+
+    #[test_with::https(www.rust-lang.org, danger_accept_invalid_certs)]
+    #[test]
+    fn test_works_without_cert_check() {
+        assert!(true);
+    }
 }
 
 #[test_with::https(httpbin.org)]
@@ -14,6 +14,12 @@ mod icmp_tests {
         assert!(true);
     }
 
+    #[test_with::icmp(127.0.0.1, method=unprivileged)]
+    #[test]
+    fn test_works_without_raw_socket() {
+        assert!(true);
+    }
+
     #[test_with::icmp(193.194.195.196)]
     #[test]
     fn test_ignored() {
@@ -1,7 +1,12 @@
+test_with::throttles!();
+test_with::serial_groups!();
+
 fn main() {}
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     // `LOCK` is file based lock to prevent test1 an test2 run at the same time
     #[test_with::lock(LOCK)]
     fn test_1() {
@@ -27,4 +32,100 @@ mod tests {
     fn test_4() {
         assert!(true);
     }
+
+    // `POLL_LOCK` is file based lock to prevent test5 an test6 run at the same time, and the lock
+    // is polled from every 10 milliseconds.
+    #[test_with::lock(POLL_LOCK, 3, poll_ms = 10)]
+    fn test_5() {
+        assert!(true);
+    }
+
+    // `POLL_LOCK` is file based lock to prevent test5 an test6 run at the same time, and the lock
+    // is polled from every 10 milliseconds.
+    #[test_with::lock(POLL_LOCK, 3, poll_ms = 10)]
+    fn test_6() {
+        assert!(true);
+    }
+
+    // `FLOCK` is an OS advisory lock to prevent test7 an test8 run at the same time, and the lock
+    // is released by the OS even if the test process is killed.
+    #[test_with::lock(FLOCK, backend=flock)]
+    fn test_7() {
+        assert!(true);
+    }
+
+    // `FLOCK` is an OS advisory lock to prevent test7 an test8 run at the same time, and the lock
+    // is released by the OS even if the test process is killed.
+    #[test_with::lock(FLOCK, backend=flock)]
+    fn test_8() {
+        assert!(true);
+    }
+
+    // `JOB_${CI_JOB_ID}` is file based lock with the environment variable expanded when compiling,
+    // such that the parallel CI jobs on the same runner will not collide.
+    #[test_with::lock(JOB_${CI_JOB_ID})]
+    fn test_9() {
+        assert!(true);
+    }
+
+    // no more than 2 of the 3 heavy test cases run at the same time
+    static HEAVY_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    fn run_heavy() {
+        let running = HEAVY_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        HEAVY_RUNNING.fetch_sub(1, Ordering::SeqCst);
+        assert!(
+            running <= 2,
+            "{} heavy test cases run at the same time",
+            running
+        );
+    }
+
+    #[test_with::throttle(2)]
+    fn test_heavy_1() {
+        run_heavy();
+    }
+
+    #[test_with::throttle(2)]
+    fn test_heavy_2() {
+        run_heavy();
+    }
+
+    #[test_with::throttle(2)]
+    fn test_heavy_3() {
+        run_heavy();
+    }
+
+    // the test cases with a different limit are counted separately, so only one of the 2 exclusive
+    // test cases runs at the same time, regardless of the heavy ones
+    static EXCLUSIVE_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+    fn run_exclusive() {
+        let running = EXCLUSIVE_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        EXCLUSIVE_RUNNING.fetch_sub(1, Ordering::SeqCst);
+        assert_eq!(running, 1, "the exclusive test cases run at the same time");
+    }
+
+    #[test_with::throttle(1)]
+    fn test_exclusive_1() {
+        run_exclusive();
+    }
+
+    #[test_with::throttle(1)]
+    fn test_exclusive_2() {
+        run_exclusive();
+    }
+
+    // `test_serial_1` and `test_serial_2` run one by one in the test process without lock files
+    #[test_with::serial_group(MEMORY)]
+    fn test_serial_1() {
+        assert!(true);
+    }
+
+    #[test_with::serial_group(MEMORY)]
+    fn test_serial_2() {
+        assert!(true);
+    }
 }
@@ -0,0 +1,30 @@
+use proptest::prelude::*;
+
+fn main() {}
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+// `proptest!` keeps the attributes on the generated test function, so the condition can be
+// placed above `#[test]` in the block
+proptest! {
+    #[test_with::env(RUN_PROPTESTS)]
+    #[test]
+    fn add_is_commutative(a in 0..1000u32, b in 0..1000u32) {
+        prop_assert_eq!(add(a, b), add(b, a));
+    }
+}
+
+// the test functions in the `proptest!` block of the module are ignored together
+#[test_with::env(RUN_PROPTESTS)]
+mod ignore_proptest_mod {
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn add_zero_is_identity(a in 0..1000u32) {
+            prop_assert_eq!(super::add(a, 0), a);
+        }
+    }
+}
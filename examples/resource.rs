@@ -8,12 +8,42 @@ mod tests {
         panic!("should be ignored")
     }
 
+    #[test_with::mem(999GiB)]
+    #[test]
+    fn mem_test_ignored_with_binary_unit() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::free_mem(999GB)]
+    #[test]
+    fn free_mem_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::available_mem(999GB)]
+    #[test]
+    fn available_mem_test_ignored() {
+        panic!("should be ignored")
+    }
+
     #[test_with::swap(999GB)]
     #[test]
     fn swap_test_ignored() {
         panic!("should be ignored")
     }
 
+    #[test_with::max_swap(999GB)]
+    #[test]
+    fn max_swap_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::free_swap_percent(0)]
+    #[test]
+    fn free_swap_percent_test_works() {
+        assert!(true);
+    }
+
     #[test_with::cpu_core(32)]
     #[test]
     fn cpu_core_test_ignored() {
@@ -25,4 +55,56 @@ mod tests {
     fn physical_cpu_core_test_ignored() {
         panic!("should be ignored")
     }
+
+    #[test_with::smt]
+    #[test]
+    fn smt_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::parallelism(1)]
+    #[test]
+    fn parallelism_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::parallelism(4096)]
+    #[test]
+    fn parallelism_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::kernel(>= 999.0)]
+    #[test]
+    fn kernel_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::min_open_files(1)]
+    #[test]
+    fn open_files_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::min_open_files(1048576000)]
+    #[test]
+    fn open_files_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::min_entropy(1048576000)]
+    #[test]
+    fn entropy_test_ignored() {
+        panic!("should be ignored")
+    }
+}
+
+// the module is gated on the physical cores, so it is still ignored on the machine with 16
+// physical cores and 32 logical cores
+#[test_with::phy_core(32)]
+mod physical_cpu_core_mod {
+    #[test]
+    fn physical_cpu_core_mod_test_ignored() {
+        panic!("should be ignored")
+    }
 }
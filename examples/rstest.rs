@@ -13,6 +13,15 @@ fn fibonacci_test(#[case] input: u32, #[case] expected: u32) {
     assert_eq!(expected, fibonacci(input))
 }
 
+// the condition is fulfilled, and `#[rstest]` generates the test cases without another `#[test]`
+#[test_with::env(PATH)]
+#[rstest]
+#[case(5, 5)]
+#[case(6, 8)]
+fn fibonacci_works(#[case] input: u32, #[case] expected: u32) {
+    assert_eq!(expected, fibonacci(input))
+}
+
 fn fibonacci(input: u32) -> u32 {
     match input {
         0 => 0,
@@ -20,3 +29,19 @@ fn fibonacci(input: u32) -> u32 {
         n => fibonacci(n - 2) + fibonacci(n - 1),
     }
 }
+
+#[test_with::env(NOTHING)]
+mod ignore_rstest_mod {
+    #[rstest::rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    fn fibonacci_test(#[case] input: u32, #[case] expected: u32) {
+        assert_eq!(expected, super::fibonacci(input))
+    }
+
+    #[test]
+    #[should_panic]
+    fn fibonacci_should_panic() {
+        assert_eq!(0, super::fibonacci(1))
+    }
+}
@@ -0,0 +1,22 @@
+test_with::runner!(panicking_hooks);
+
+#[test_with::module]
+mod panicking_hooks {
+    fn before_each() {
+        panic!("before_each needs the database")
+    }
+
+    // The hooks only run when the conditions are met, so the panic of `before_each` does not
+    // turn the ignored test case into a failure
+    #[test_with::runtime_env(NOTHING)]
+    fn test_ignored_without_hooks() {
+        panic!("should be ignored")
+    }
+
+    // The panic of `before_each` is reported as the failure of the test case
+    #[test_with::runtime_should_panic(expected = "before_each")]
+    #[test_with::runtime_env(PWD)]
+    fn before_each_panic_fails_the_test() {
+        assert!(true);
+    }
+}
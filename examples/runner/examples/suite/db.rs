@@ -0,0 +1,14 @@
+// The attribute macro can not be on `mod db;`, so the module of `#[test_with::module]` is declared
+// inline in this file, and passed to the runner as `db::db`.
+#[test_with::module]
+pub mod db {
+    #[test_with::runtime_env(DATABASE_URL)]
+    fn test_query() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_tcp(127.0.0.1:5432)]
+    fn test_connect() {
+        assert!(true);
+    }
+}
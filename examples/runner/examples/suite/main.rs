@@ -0,0 +1,11 @@
+mod db;
+
+test_with::runner!(db::db, local);
+
+#[test_with::module]
+mod local {
+    #[test_with::runtime_env(PWD)]
+    fn test_works() {
+        assert!(true);
+    }
+}
@@ -1,4 +1,4 @@
-test_with::runner!(env, file, path, net, user, exe, resource, custom_mod, timezone);
+test_with::runner!(env, file, path, dir, net, user, exe, resource, custom_mod, timezone, hooks, flaky, panic, lock1, lock2, toolchain, features, machine, fallible_env);
 
 #[test_with::module]
 mod env {
@@ -20,6 +20,33 @@ mod env {
         panic!("should be ignored")
     }
 
+    // Passes because the test case is ignored
+    #[test_with::assert_ignored]
+    #[test_with::runtime_env(NOTHING)]
+    fn test_asserted_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_all(env(PWD), any(env(NOTHING), path(/)))]
+    fn combined_env_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_any(env(NOTHING), not(env(PWD)))]
+    fn test_ignored_without_any_condition() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_when(env(PWD))]
+    fn when_env_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_unless(env(PWD))]
+    fn test_ignored_unless_env() {
+        panic!("should be ignored")
+    }
+
     // Will rase error when using non-runtime macro
     //
     // #[test_with::env(PWD, SAYING)]
@@ -37,10 +64,65 @@ mod env {
         panic!("should be ignored")
     }
 
+    #[test_with::runtime_env(CARGO_*)]
+    fn env_test_works_with_prefix() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_env(NOT_SAYING_*)]
+    fn test_ignored_with_prefix() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_env(PWD, NOT_SAYING, NOTHING, at_least=1)]
+    fn env_test_works_with_enough_vars() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_env(PWD, NOT_SAYING, NOTHING, at_least=2)]
+    fn test_ignored_without_enough_vars() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_env(PWD, nonempty)]
+    fn env_test_works_with_nonempty_var() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_env(NOTHING in [staging, prod])]
+    fn test_ignored_with_value_set() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_env_file("../fixtures/.env.test": DB_URL, API_KEY)]
+    fn env_file_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_config("../fixtures/tests.toml": integration.enabled)]
+    fn config_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_config("../fixtures/tests.toml": integration.slow)]
+    fn config_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_env_true(TEST_WITH_NOT_EXIST_TOGGLE)]
+    fn env_true_test_ignored() {
+        panic!("should be ignored")
+    }
+
     #[test_with::runtime_no_env(GITHUB_ACTIONS)]
     fn test_ignore_in_github_action() {
         panic!("should be ignored in github action")
     }
+
+    #[test_with::runtime_skip_if_ci]
+    fn test_ignore_in_ci() {
+        panic!("should be ignored in CI")
+    }
 }
 
 #[test_with::module]
@@ -49,6 +131,10 @@ mod file {
     fn file_test_works() {
         assert!(true);
     }
+    #[test_with::runtime_file("../fixtures/with space.txt")]
+    fn file_test_works_with_spaced_path() {
+        assert!(true);
+    }
 }
 
 #[test_with::module]
@@ -59,16 +145,46 @@ mod path {
     }
 }
 
+#[test_with::module]
+mod dir {
+    #[test_with::runtime_dir(/etc/hostname)]
+    fn test_not_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_dir_not_empty(/etc)]
+    fn test_works_with_entries() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_mounted(/)]
+    fn test_works_with_mount_point() {
+        assert!(true);
+    }
+}
+
 #[test_with::module]
 mod net {
     #[test_with::runtime_http(httpbin.org)]
     fn http_test_works() {
         assert!(true);
     }
+    #[test_with::runtime_http(httpbin.org, max_ms=10000)]
+    fn http_test_works_in_time() {
+        assert!(true);
+    }
+    #[test_with::runtime_http(not.exist.com || httpbin.org)]
+    fn http_test_works_with_any_mirror() {
+        assert!(true);
+    }
     #[test_with::runtime_https(httpbin.org)]
     fn https_test_works() {
         assert!(true);
     }
+    #[test_with::runtime_https(httpbin.org, danger_accept_invalid_certs)]
+    fn https_test_works_without_cert_check() {
+        assert!(true);
+    }
     #[test_with::runtime_icmp(193.194.195.196)]
     fn test_ignored_with_non_existing_host() {
         panic!("should be ignored with non existing host")
@@ -77,6 +193,42 @@ mod net {
     fn test_works_with_domain_name_server() {
         assert!(true);
     }
+    #[test_with::runtime_tcp(8.8.8.8:53, retries=3)]
+    fn test_works_with_retries() {
+        assert!(true);
+    }
+    #[test_with::runtime_tcp(8.8.8.8:53-55)]
+    fn test_works_with_port_range() {
+        assert!(true);
+    }
+    #[test_with::runtime_no_tcp(193.194.195.196:5432)]
+    fn test_works_when_socket_offline() {
+        assert!(true);
+    }
+    #[test_with::runtime_any(tcp(193.194.195.196:5432), http(httpbin.org))]
+    fn any_test_works_with_http() {
+        assert!(true);
+    }
+    #[test_with::runtime_all(tcp(8.8.8.8:53, retries=3), http(193.194.195.196))]
+    fn test_ignored_without_http() {
+        panic!("should be ignored without the http service")
+    }
+    #[test_with::runtime_no_tcp(8.8.8.8:53)]
+    fn test_ignored_when_socket_online() {
+        panic!("should be ignored")
+    }
+    #[test_with::runtime_no_http(not.exist.com)]
+    fn test_works_when_http_offline() {
+        assert!(true);
+    }
+    #[test_with::runtime_http(httpbin.org, cache=30)]
+    fn http_test_works_with_cache() {
+        assert!(true);
+    }
+    #[test_with::runtime_http(httpbin.org, cache=30)]
+    fn http_test_works_with_cached_probe() {
+        assert!(true);
+    }
 }
 
 #[test_with::module]
@@ -85,6 +237,10 @@ mod user {
     fn test_ignored_by_normal_user() {
         panic!("should be ignored")
     }
+    #[test_with::runtime_effective_root()]
+    fn test_ignored_by_effective_normal_user() {
+        panic!("should be ignored")
+    }
     #[test_with::runtime_group(avengers)]
     fn test_ignored_by_normal_person() {
         panic!("should be ignored")
@@ -93,6 +249,10 @@ mod user {
     fn test_ignored_by_normal_man() {
         panic!("should be ignored")
     }
+    #[test_with::runtime_capability(CAP_SYS_MODULE)]
+    fn test_ignored_without_capability() {
+        panic!("should be ignored")
+    }
 }
 
 #[test_with::module]
@@ -102,6 +262,18 @@ mod exe {
     fn test_executable_with_path() {
         assert!(true);
     }
+
+    // `sh` newer than 999.0 does not exist
+    #[test_with::runtime_executable(sh > 999.0)]
+    fn test_ignored_executable_version() {
+        panic!("should be ignored")
+    }
+
+    // The shared library can not be loaded
+    #[test_with::runtime_library(libnothing.so.1)]
+    fn test_ignored_without_library() {
+        panic!("should be ignored")
+    }
 }
 
 #[test_with::module]
@@ -118,6 +290,12 @@ mod resource {
         panic!("should be ignored")
     }
 
+    // Only works with enough open files limit
+    #[test_with::runtime_min_open_files(1048576000)]
+    fn test_ignored_open_files_not_enough() {
+        panic!("should be ignored")
+    }
+
     // Only works with enough memory size
     #[test_with::runtime_mem(100GB)]
     fn test_ignored_mem_not_enough() {
@@ -142,23 +320,89 @@ mod resource {
         panic!("should be ignored")
     }
 
+    // Only works with swap size not more than 999GB
+    #[test_with::runtime_max_swap(999GB)]
+    fn max_swap_test_works() {
+        assert!(true);
+    }
+
     // Only works with enough free swap size
     #[test_with::runtime_free_swap(100GB)]
     fn test_ignored_free_swap_not_enough() {
         panic!("should be ignored")
     }
+
+    // Only works with enough available swap size
+    #[test_with::runtime_available_swap(100GB)]
+    fn test_ignored_available_swap_not_enough() {
+        panic!("should be ignored")
+    }
+
+    // Only works with SMT enabled
+    #[test_with::runtime_smt]
+    fn smt_test_works() {
+        assert!(true);
+    }
+
+    // Only works when 4096 threads can run in parallel
+    #[test_with::runtime_parallelism(4096)]
+    fn test_ignored_parallelism_not_enough() {
+        panic!("should be ignored")
+    }
+
+    // Only works on the kernel 999.0 or newer
+    #[test_with::runtime_kernel(>= 999.0)]
+    fn test_ignored_kernel_too_old() {
+        panic!("should be ignored")
+    }
+
+    // Only works with the huge entropy
+    #[test_with::runtime_min_entropy(1048576000)]
+    fn test_ignored_entropy_too_low() {
+        panic!("should be ignored")
+    }
+
+    // Any free swap percentage is enough
+    #[test_with::runtime_free_swap_percent(0)]
+    fn free_swap_percent_test_works() {
+        assert!(true);
+    }
 }
 
 fn something_happened() -> Option<String> {
     Some("because something happened".to_string())
 }
 
+fn env_is_set(var: String) -> (bool, String) {
+    match std::env::var(&var) {
+        Ok(_) => (true, String::new()),
+        Err(_) => (false, format!("because variable {} not found", var)),
+    }
+}
+
 #[test_with::module]
 mod custom_mod {
     #[test_with::runtime_ignore_if(something_happened)]
     fn test_ignored() {
         assert!(false);
     }
+
+    #[test_with::runtime_custom(crate::env_is_set, "PWD")]
+    fn custom_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_custom(crate::env_is_set, NOTHING)]
+    fn custom_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    // Passes because the test case is ignored by the custom checker
+    #[test_with::assert_ignored]
+    #[test_with::runtime_custom(crate::env_is_set, NOTHING)]
+    fn custom_test_asserted_ignored() {
+        panic!("should be ignored")
+    }
 }
 
 #[test_with::module]
@@ -171,4 +415,190 @@ mod timezone {
     fn timezone_test_ignored() {
         assert!(false);
     }
+
+    #[test_with::runtime_no_dst()]
+    fn no_dst_test_works() {
+        assert!(true);
+    }
+}
+
+#[test_with::module]
+mod hooks {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static PREPARED: AtomicUsize = AtomicUsize::new(0);
+
+    fn before_each() {
+        PREPARED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn after_each() {
+        PREPARED.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    #[test_with::runtime_env(PWD)]
+    fn hooks_test_works() {
+        assert!(PREPARED.load(Ordering::SeqCst) > 0);
+    }
+}
+
+#[test_with::module]
+mod flaky {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[test_with::runtime_retry(3)]
+    fn retry_test_works() {
+        assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) > 0);
+    }
+}
+
+#[test_with::module]
+mod panic {
+    #[test_with::runtime_should_panic]
+    fn should_panic_test_works() {
+        panic!("boom");
+    }
+
+    #[test_with::runtime_should_panic(expected = "boom")]
+    #[test_with::runtime_env(PWD)]
+    fn should_panic_with_expected_test_works() {
+        panic!("big boom");
+    }
+}
+
+#[test_with::module]
+mod lock1 {
+    #[test_with::runtime_lock(RUNTIME_LOCK)]
+    fn lock_test_works() {
+        assert!(true);
+    }
+}
+
+#[test_with::module]
+mod lock2 {
+    #[test_with::runtime_lock(RUNTIME_LOCK, 3)]
+    #[test_with::runtime_env(PWD)]
+    fn lock_with_env_test_works() {
+        assert!(true);
+    }
+}
+
+#[test_with::module]
+mod toolchain {
+    #[test_with::runtime_min_rust(1.77)]
+    fn min_rust_test_works() {
+        assert!(true);
+    }
+    #[test_with::runtime_min_rust(999.0)]
+    fn min_rust_test_ignored() {
+        panic!("should be ignored")
+    }
+    #[test_with::runtime_debug]
+    fn debug_test_works() {
+        debug_assert!(true);
+    }
+    #[test_with::runtime_release]
+    fn release_test_works() {
+        assert!(!cfg!(debug_assertions));
+    }
+    #[test_with::runtime_no_debugger]
+    fn no_debugger_test_works() {
+        assert!(true);
+    }
+    #[test_with::runtime_under_debugger]
+    fn under_debugger_test_ignored() {
+        panic!("should be ignored")
+    }
+}
+
+#[test_with::module]
+mod features {
+    #[test_with::runtime_feature("enabled-feature")]
+    fn feature_test_works() {
+        assert!(true);
+    }
+    #[test_with::runtime_feature("disabled-feature")]
+    fn feature_test_ignored() {
+        panic!("should be ignored")
+    }
+}
+
+#[test_with::module]
+mod machine {
+    #[test_with::tag(machine)]
+    #[test_with::runtime_hostname(*)]
+    fn hostname_test_works() {
+        assert!(true);
+    }
+    #[test_with::runtime_hostname(not-exist-host-*)]
+    fn hostname_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    // Only works with a display
+    #[test_with::runtime_display]
+    fn display_test_works() {
+        assert!(true);
+    }
+
+    // No machine has so many monitors
+    #[test_with::runtime_monitors(1000)]
+    fn monitors_test_ignored() {
+        panic!("should be ignored")
+    }
+
+    // The unit does not exist
+    #[test_with::runtime_systemd_active(nothing.service)]
+    fn test_ignored_without_active_unit() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_command("cargo --version")]
+    fn command_test_works() {
+        assert!(true);
+    }
+
+    // The command exits with non-zero
+    #[test_with::runtime_command("cargo --no-such-flag")]
+    fn test_ignored_with_failed_command() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::runtime_command_output("cargo --version", contains = "cargo")]
+    fn command_output_test_works() {
+        assert!(true);
+    }
+
+    // The output does not contain the text
+    #[test_with::runtime_command_output("cargo --version", contains = "nothing")]
+    fn test_ignored_without_command_output() {
+        panic!("should be ignored")
+    }
+
+    // The fixture pins the major version 1 of cargo
+    #[test_with::runtime_file_version("../fixtures/.cargo-version" == cargo)]
+    fn file_version_test_works() {
+        assert!(true);
+    }
+
+    #[test_with::runtime_file_version("../fixtures/.cargo-version" > cargo)]
+    fn test_ignored_with_other_version() {
+        panic!("should be ignored")
+    }
+}
+
+#[test_with::module]
+mod fallible_env {
+    pub struct TestEnv;
+
+    fn try_default() -> Option<TestEnv> {
+        None
+    }
+
+    #[test_with::runtime_env(PWD)]
+    fn test_ignored_without_test_env() {
+        panic!("should be ignored")
+    }
 }
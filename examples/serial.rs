@@ -16,3 +16,17 @@ async fn my_test_1() {
 async fn my_test_2() {
     assert!(false);
 }
+
+#[serial]
+#[test_with::with_env(TEST_WITH_LOG=debug, TEST_WITH_TZ="UTC+1")]
+#[test]
+fn my_test_with_env() {
+    assert_eq!(std::env::var("TEST_WITH_LOG").unwrap(), "debug");
+    assert_eq!(std::env::var("TEST_WITH_TZ").unwrap(), "UTC+1");
+}
+
+#[serial]
+#[test]
+fn my_test_after_env_restored() {
+    assert!(std::env::var("TEST_WITH_LOG").is_err());
+}
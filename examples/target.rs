@@ -0,0 +1,34 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    #[test_with::target_family(unix, windows)]
+    #[test]
+    fn test_works_on_native() {
+        assert!(true);
+    }
+
+    #[test_with::target_family(wasm)]
+    #[test]
+    fn test_ignored_on_native() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::not_wasm]
+    #[test]
+    fn test_with_threads() {
+        std::thread::spawn(|| {}).join().unwrap();
+    }
+
+    #[test_with::debug]
+    #[test]
+    fn test_works_in_debug() {
+        debug_assert!(true);
+    }
+
+    #[test_with::release]
+    #[test]
+    fn test_works_in_release() {
+        assert!(!cfg!(debug_assertions));
+    }
+}
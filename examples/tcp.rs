@@ -13,6 +13,36 @@ mod tcp_tests {
     fn test_ignored() {
         panic!("should be ignored")
     }
+
+    #[test_with::tcp(8.8.8.8:53, retries=3)]
+    #[test]
+    fn test_works_with_retries() {
+        assert!(true);
+    }
+
+    #[test_with::tcp(193.194.195.196, retries=2)]
+    #[test]
+    fn test_ignored_with_retries() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::tcp(8.8.8.8:53, family=v4)]
+    #[test]
+    fn test_works_over_ipv4() {
+        assert!(true);
+    }
+
+    #[test_with::tcp(8.8.8.8:53, family=v6)]
+    #[test]
+    fn test_ignored_without_ipv6_address() {
+        panic!("should be ignored")
+    }
+
+    #[test_with::tcp(8.8.8.8:53-55)]
+    #[test]
+    fn test_works_with_port_range() {
+        assert!(true);
+    }
 }
 
 #[test_with::tcp(8.8.8.8:53)]
@@ -13,6 +13,14 @@ mod timezone_tests {
     fn test_ignored() {
         panic!("should be ignored")
     }
+
+    // UTC has no daylight saving time
+    #[test_with::timezone(0)]
+    #[test_with::no_dst()]
+    #[test]
+    fn test_works_without_dst() {
+        assert!(true);
+    }
 }
 
 #[test_with::timezone(0)]
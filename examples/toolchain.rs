@@ -0,0 +1,18 @@
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    // The compiler is not older than 1.77, which is the MSRV of test-with
+    #[test_with::min_rust(1.77)]
+    #[test]
+    fn test_works() {
+        assert!(true);
+    }
+
+    // The compiler is older than 999.0
+    #[test_with::min_rust(999.0)]
+    #[test]
+    fn test_ignored() {
+        panic!("should be ignored")
+    }
+}
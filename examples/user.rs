@@ -9,6 +9,12 @@ mod tests {
         panic!("should be ignored")
     }
 
+    #[test_with::effective_root()]
+    #[test]
+    fn test_ignored_by_effective_user() {
+        panic!("should be ignored")
+    }
+
     #[test_with::group(avengers)]
     #[test]
     fn test_ignored2() {
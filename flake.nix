@@ -14,7 +14,7 @@
         pkgs = import nixpkgs {
           inherit system overlays;
         };
-        rust = pkgs.rust-bin.stable."1.77.0".default;
+        rust = pkgs.rust-bin.stable."1.82.0".default;
         dr = dependency-refresh.defaultPackage.${system};
 
         publishScript = pkgs.writeShellScriptBin "crate-publish" ''
@@ -39,6 +39,8 @@
           cargo run --no-default-features --features=resource --example=resource
           cargo run --no-default-features --features=executable --example=executable
           cargo run --no-default-features --features=timezone --example=timezone
+          cargo run --no-default-features --features=hostname --example=hostname
+          cargo run --no-default-features --features=config --example=config
           cargo install cargo-hack
           cargo hack test --examples
 
@@ -48,6 +50,7 @@
           cargo run --example mock
           cargo run --example mock2
           cargo run --example mix
+          cargo run --example hooks
         '';
       in
       with pkgs;
@@ -0,0 +1,83 @@
+use proc_macro_error2::abort_call_site;
+
+#[cfg(target_os = "linux")]
+use caps::{CapSet, Capability};
+
+/// Parse the capability name, ex: `CAP_NET_RAW`, the `CAP_` prefix is optional and the name is
+/// case insensitive.
+#[cfg(target_os = "linux")]
+pub(crate) fn parse_capability(attr_str: &str) -> Option<Capability> {
+    let name = attr_str.trim_matches('"').to_uppercase();
+    if name.starts_with("CAP_") {
+        name.parse().ok()
+    } else {
+        format!("CAP_{}", name).parse().ok()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn capability_or_abort(attr_str: &str) -> Capability {
+    match parse_capability(attr_str) {
+        Some(cap) => cap,
+        None => abort_call_site!("unknown capability `{}`, ex: CAP_NET_RAW", attr_str),
+    }
+}
+
+/// The bit of the capability in the capability sets, which is used to check the `CapEff` field of
+/// `/proc/self/status` when the test case running.
+#[cfg(all(feature = "runtime", target_os = "linux"))]
+pub(crate) fn capability_index(attr_str: &str) -> (u8, String) {
+    let cap = capability_or_abort(attr_str);
+    (cap.index(), cap.to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn check_capability_condition(attr_str: String) -> (bool, String) {
+    let cap = capability_or_abort(&attr_str);
+    (
+        caps::has_cap(None, CapSet::Effective, cap).unwrap_or(false),
+        format!("because missing capability {}", cap),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_capability_condition(attr_str: String) -> (bool, String) {
+    if attr_str.is_empty() {
+        abort_call_site!("capability is required, ex: CAP_NET_RAW");
+    }
+    (
+        false,
+        "because capabilities are only supported on Linux".into(),
+    )
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::parse_capability;
+    use caps::Capability;
+
+    #[test]
+    fn parse_capability_names() {
+        //* Given
+        let names = [
+            "CAP_NET_RAW",
+            "net_raw",
+            "\"CAP_NET_ADMIN\"",
+            "CAP_NOT_EXIST",
+        ];
+
+        //* When
+        let parsed: Vec<_> = names.iter().map(|n| parse_capability(n)).collect();
+
+        //* Then
+        assert_eq!(
+            parsed,
+            vec![
+                Some(Capability::CAP_NET_RAW),
+                Some(Capability::CAP_NET_RAW),
+                Some(Capability::CAP_NET_ADMIN),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use proc_macro_error2::abort_call_site;
+
+/// The files of the memory limit of cgroup v2 and v1, which are the limit of the container when
+/// the cgroup namespace is used.  The limit of v2 is `max` and the limit of v1 is a huge number
+/// when it is unlimited.
+const CGROUP_MEMORY_LIMIT_FILES: [&str; 2] = [
+    "/sys/fs/cgroup/memory.max",
+    "/sys/fs/cgroup/memory/memory.limit_in_bytes",
+];
+
+/// Where the total memory comes from, the cgroup limit is preferred when it is lower than the
+/// memory of the host, and `source=host` forces the memory of the host.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MemorySource {
+    Cgroup,
+    Host,
+}
+
+/// Take out the `source=host` or `source=cgroup` option from the attribute string of memory
+/// conditions.
+pub(crate) fn split_memory_source_option(attr_str: &str) -> (&str, MemorySource) {
+    match attr_str.split_once(',') {
+        Some((size, option)) => match option
+            .trim()
+            .strip_prefix("source")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+            .map(str::trim)
+        {
+            Some("cgroup") => (size.trim(), MemorySource::Cgroup),
+            Some("host") => (size.trim(), MemorySource::Host),
+            _ => {
+                abort_call_site!("the option of memory should be `source=cgroup` or `source=host`")
+            }
+        },
+        None => (attr_str.trim(), MemorySource::Cgroup),
+    }
+}
+
+pub(crate) fn parse_cgroup_memory_limit(content: &str) -> Option<u64> {
+    content.trim().parse::<u64>().ok()
+}
+
+/// The total memory for the memory conditions, the cgroup limit is used when it is lower than the
+/// memory of the host.
+pub(crate) fn total_memory(host_memory: u64, source: MemorySource) -> u64 {
+    let cgroup_limit = match source {
+        MemorySource::Cgroup => CGROUP_MEMORY_LIMIT_FILES.iter().find_map(|file| {
+            std::fs::read_to_string(file)
+                .ok()
+                .and_then(|content| parse_cgroup_memory_limit(&content))
+        }),
+        MemorySource::Host => None,
+    };
+    cgroup_limit.map_or(host_memory, |limit| limit.min(host_memory))
+}
+
+/// The generated code of `total_memory`, the memory of the host is in `host_memory`.
+#[cfg(feature = "runtime")]
+pub(crate) fn total_memory_tokens(source: MemorySource) -> proc_macro2::TokenStream {
+    let limit_files = CGROUP_MEMORY_LIMIT_FILES;
+    match source {
+        MemorySource::Cgroup => quote::quote! {
+            [#(#limit_files),*]
+                .iter()
+                .find_map(|file| {
+                    std::fs::read_to_string(file)
+                        .ok()
+                        .and_then(|content| content.trim().parse::<u64>().ok())
+                })
+                .map_or(host_memory, |limit| limit.min(host_memory))
+        },
+        MemorySource::Host => quote::quote! { host_memory },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cgroup_memory_limit, split_memory_source_option, MemorySource};
+
+    #[test]
+    fn parse_memory_source_and_limit() {
+        //* Given
+        let attrs = ["2GB", "2GB, source=host", "512MiB,source=cgroup"];
+
+        //* When
+        let splitted: Vec<_> = attrs
+            .iter()
+            .map(|attr| split_memory_source_option(attr))
+            .collect();
+
+        //* Then
+        assert_eq!(
+            splitted,
+            vec![
+                ("2GB", MemorySource::Cgroup),
+                ("2GB", MemorySource::Host),
+                ("512MiB", MemorySource::Cgroup),
+            ]
+        );
+        assert_eq!(parse_cgroup_memory_limit("536870912\n"), Some(536870912));
+        assert_eq!(parse_cgroup_memory_limit("max\n"), None);
+    }
+}
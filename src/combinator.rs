@@ -0,0 +1,380 @@
+use std::fmt;
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use proc_macro_error2::abort_call_site;
+
+#[cfg(feature = "runtime")]
+use crate::condition::runtime_condition;
+use crate::condition::{condition_check, split_top_level};
+use crate::utils::tokens_to_string;
+
+/// The condition tree of the combinators, ex: `all(env(DB_URL), any(tcp(db:5432), http(db:8080)))`
+#[derive(Debug, PartialEq)]
+pub(crate) enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+    Check { name: String, args: String },
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, conditions) = match self {
+            Condition::All(conditions) => ("all", conditions.iter().collect::<Vec<_>>()),
+            Condition::Any(conditions) => ("any", conditions.iter().collect()),
+            Condition::Not(condition) => ("not", vec![condition.as_ref()]),
+            Condition::Check { name, args } if args.is_empty() => return write!(f, "{}", name),
+            Condition::Check { name, args } => return write!(f, "{}({})", name, args),
+        };
+        let conditions: Vec<String> = conditions.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}({})", name, conditions.join(", "))
+    }
+}
+
+/// Parse the conditions separated by the commas, ex: `env(DB_URL), any(tcp(db:5432))`
+pub(crate) fn parse_conditions(tokens: TokenStream) -> Vec<Condition> {
+    let conditions: Vec<Condition> = split_top_level(tokens)
+        .into_iter()
+        .map(parse_condition)
+        .collect();
+    if conditions.is_empty() {
+        abort_call_site!(
+            "at least one condition is required, ex: `all(env(DB_URL), tcp(db:5432))`"
+        );
+    }
+    conditions
+}
+
+fn parse_condition(tokens: TokenStream) -> Condition {
+    let mut tokens = tokens.into_iter();
+    let (name, args) = match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(name)), Some(TokenTree::Group(args)), None)
+            if args.delimiter() == Delimiter::Parenthesis =>
+        {
+            (name.to_string(), args.stream())
+        }
+        (Some(TokenTree::Ident(name)), None, None) => (name.to_string(), TokenStream::new()),
+        _ => abort_call_site!(
+            "the condition should be like `env(DB_URL)`, or combined with `all`, `any` and `not`"
+        ),
+    };
+    match name.as_str() {
+        "all" => Condition::All(parse_conditions(args)),
+        "any" => Condition::Any(parse_conditions(args)),
+        "not" => {
+            let mut conditions = parse_conditions(args);
+            if conditions.len() != 1 {
+                abort_call_site!("`not` needs exactly one condition");
+            }
+            Condition::Not(Box::new(conditions.remove(0)))
+        }
+        _ => Condition::Check {
+            name,
+            args: tokens_to_string(args),
+        },
+    }
+}
+
+/// The reason of the unmet condition in lines, the first line is the reason of the condition, and
+/// the following lines are the indented reasons of the unmet sub-conditions.
+fn reason_tree(summary: &str, unmet: Vec<(String, Vec<String>)>) -> Vec<String> {
+    let mut lines = vec![summary.to_string()];
+    for (label, reasons) in unmet {
+        lines.push(format!("- {}: {}", label, reasons[0]));
+        lines.extend(reasons[1..].iter().map(|line| format!("  {}", line)));
+    }
+    lines
+}
+
+impl Condition {
+    /// Check the condition when compiling, the reasons are returned when it is not met
+    pub(crate) fn check(&self) -> (bool, Vec<String>) {
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                let results: Vec<(String, (bool, Vec<String>))> = conditions
+                    .iter()
+                    .map(|condition| (condition.to_string(), condition.check()))
+                    .collect();
+                let (met, summary) = match self {
+                    Condition::All(_) => (
+                        results.iter().all(|(_, (met, _))| *met),
+                        "not all of the conditions are met:",
+                    ),
+                    _ => (
+                        results.iter().any(|(_, (met, _))| *met),
+                        "none of the conditions is met:",
+                    ),
+                };
+                let unmet = results
+                    .into_iter()
+                    .filter(|(_, (met, _))| !met)
+                    .map(|(label, (_, reasons))| (label, reasons))
+                    .collect();
+                (met, reason_tree(summary, unmet))
+            }
+            Condition::Not(condition) => {
+                (!condition.check().0, vec![format!("{} is met", condition)])
+            }
+            Condition::Check { name, args } => match condition_check(name) {
+                Some(check) => {
+                    let (met, msg) = check(args.clone());
+                    let msg = msg.trim().trim_start_matches("because ");
+                    (
+                        met,
+                        vec![msg.split_whitespace().collect::<Vec<_>>().join(" ")],
+                    )
+                }
+                None => abort_call_site!("`{}` is not a supported condition", name),
+            },
+        }
+    }
+
+    /// The generated check of the condition, which is evaluated as `(bool, Vec<String>)` in the
+    /// same shape as `check`.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn check_tokens(&self) -> TokenStream {
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                let labels: Vec<String> = conditions.iter().map(|c| c.to_string()).collect();
+                let checks: Vec<TokenStream> =
+                    conditions.iter().map(|c| c.check_tokens()).collect();
+                let (met, summary) = match self {
+                    Condition::All(_) => (
+                        quote::quote! { results.iter().all(|(_, (met, _))| *met) },
+                        "not all of the conditions are met:",
+                    ),
+                    _ => (
+                        quote::quote! { results.iter().any(|(_, (met, _))| *met) },
+                        "none of the conditions is met:",
+                    ),
+                };
+                quote::quote! {
+                    {
+                        let results: Vec<(&str, (bool, Vec<String>))> = vec![#((#labels, #checks)),*];
+                        let mut reasons = vec![#summary.to_string()];
+                        for (label, (_, unmet_reasons)) in results.iter().filter(|(_, (met, _))| !*met) {
+                            reasons.push(format!("- {}: {}", label, unmet_reasons[0]));
+                            reasons.extend(unmet_reasons[1..].iter().map(|line| format!("  {}", line)));
+                        }
+                        (#met, reasons)
+                    }
+                }
+            }
+            Condition::Not(condition) => {
+                let check = condition.check_tokens();
+                let reason = format!("{} is met", condition);
+                quote::quote! { (!#check.0, vec![#reason.to_string()]) }
+            }
+            Condition::Check { name, args } => runtime_check_tokens(name, args),
+        }
+    }
+}
+
+/// The generated check of the sub-condition, which is the check function generated by its runtime
+/// macro for an empty test case, so the options of the runtime macro, ex: `retries=`, also work in
+/// the combinators.  The ignore reason of the check function becomes the reason of the unmet
+/// sub-condition, and the other failures, ex: a partially set group of variables, fail the test.
+#[cfg(feature = "runtime")]
+fn runtime_check_tokens(name: &str, args: &str) -> TokenStream {
+    let (runtime_macro, expect_met) = match (name, runtime_condition(name)) {
+        ("ci", _) => (crate::runtime_skip_if_ci as _, false),
+        (_, Some(runtime_macro)) => (runtime_macro, true),
+        (_, None) => abort_call_site!("`{}` is not supported in the runtime combinators", name),
+    };
+    let attr: proc_macro::TokenStream = match args.parse() {
+        Ok(attr) => attr,
+        Err(_) => abort_call_site!("the condition `{}({})` can not be parsed", name, args),
+    };
+    let check: TokenStream = runtime_macro(attr, quote::quote! { fn condition() {} }.into()).into();
+    let met = quote::quote! {
+        match _check_condition() {
+            Ok(()) => (true, vec![]),
+            Err(failed) => match failed.message().and_then(_strip_ignore_prefix) {
+                Some(reason) => {
+                    let reason = reason.trim().trim_start_matches("because ");
+                    (false, vec![reason.split_whitespace().collect::<Vec<_>>().join(" ")])
+                }
+                None => return Err(failed),
+            },
+        }
+    };
+    if expect_met {
+        quote::quote! {{ #check #met }}
+    } else {
+        quote::quote! {{
+            #check
+            (!#met.0, vec!["not running in CI".to_string()])
+        }}
+    }
+}
+
+fn parse_attr_str(attr_str: &str) -> TokenStream {
+    match attr_str.parse() {
+        Ok(tokens) => tokens,
+        Err(_) => abort_call_site!("the conditions can not be parsed"),
+    }
+}
+
+/// The conditions of `when`, which is the readable alias of `all`
+pub(crate) fn when_condition(tokens: TokenStream) -> Condition {
+    Condition::All(parse_conditions(tokens))
+}
+
+/// The conditions of `unless`, which is met when none of the conditions is met
+pub(crate) fn unless_condition(tokens: TokenStream) -> Condition {
+    let mut conditions = parse_conditions(tokens);
+    let condition = if conditions.len() == 1 {
+        conditions.remove(0)
+    } else {
+        Condition::Any(conditions)
+    };
+    Condition::Not(Box::new(condition))
+}
+
+fn check_condition(condition: Condition) -> (bool, String) {
+    let (met, reasons) = condition.check();
+    (met, format!("because {}", reasons.join("\n")))
+}
+
+pub(crate) fn check_all_condition(attr_str: String) -> (bool, String) {
+    check_condition(Condition::All(parse_conditions(parse_attr_str(&attr_str))))
+}
+
+pub(crate) fn check_any_condition(attr_str: String) -> (bool, String) {
+    check_condition(Condition::Any(parse_conditions(parse_attr_str(&attr_str))))
+}
+
+pub(crate) fn check_when_condition(attr_str: String) -> (bool, String) {
+    check_condition(when_condition(parse_attr_str(&attr_str)))
+}
+
+pub(crate) fn check_unless_condition(attr_str: String) -> (bool, String) {
+    check_condition(unless_condition(parse_attr_str(&attr_str)))
+}
+
+/// Transform the function into the testcase of the runner, which is ignored with the reasons when
+/// the condition is not met.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_condition_macro(
+    condition: Condition,
+    input: syn::ItemFn,
+) -> proc_macro::TokenStream {
+    let check = condition.check_tokens();
+    let syn::ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let (met, reasons): (bool, Vec<String>) = #check;
+            if met {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because {}", reasons.join("\n"))))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_conditions, unless_condition, when_condition, Condition};
+
+    #[test]
+    fn parse_nested_conditions() {
+        //* Given
+        let tokens = quote::quote!(env(DB_URL), any(tcp(db:5432), not(display)));
+
+        //* When
+        let conditions = parse_conditions(tokens);
+
+        //* Then
+        assert_eq!(
+            conditions,
+            vec![
+                Condition::Check {
+                    name: "env".into(),
+                    args: "DB_URL".into()
+                },
+                Condition::Any(vec![
+                    Condition::Check {
+                        name: "tcp".into(),
+                        args: "db:5432".into()
+                    },
+                    Condition::Not(Box::new(Condition::Check {
+                        name: "display".into(),
+                        args: String::new()
+                    })),
+                ]),
+            ]
+        );
+        assert_eq!(
+            Condition::All(conditions).to_string(),
+            "all(env(DB_URL), any(tcp(db:5432), not(display)))"
+        );
+    }
+
+    #[test]
+    fn check_nested_conditions() {
+        //* Given
+        let met = Condition::All(parse_conditions(quote::quote!(
+            env(PATH),
+            any(env(NOTHING), not(env(NOTHING)))
+        )));
+        let unmet = Condition::Any(parse_conditions(quote::quote!(
+            env(NOTHING),
+            all(path(/), file(/nothing))
+        )));
+
+        //* When
+        let met = met.check();
+        let unmet = unmet.check();
+
+        //* Then
+        assert!(met.0);
+        assert_eq!(
+            unmet,
+            (
+                false,
+                vec![
+                    "none of the conditions is met:".to_string(),
+                    "- env(NOTHING): variable NOTHING not found".to_string(),
+                    "- all(path(/), file(/nothing)): not all of the conditions are met:"
+                        .to_string(),
+                    "  - file(/nothing): file not found: /nothing".to_string(),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn check_when_and_unless_conditions() {
+        //* Given
+        let when = when_condition(quote::quote!(env(PATH)));
+        let unless = unless_condition(quote::quote!(env(NOTHING), env(PATH)));
+
+        //* When
+        let when = when.check();
+        let unless = unless.check();
+
+        //* Then
+        assert!(when.0);
+        assert_eq!(
+            unless,
+            (
+                false,
+                vec!["any(env(NOTHING), env(PATH)) is met".to_string()]
+            )
+        );
+        assert_eq!(unless_condition(quote::quote!(ci)).to_string(), "not(ci)");
+    }
+}
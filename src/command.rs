@@ -0,0 +1,422 @@
+use std::io::Read;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use proc_macro_error2::abort_call_site;
+
+/// The seconds to wait for the command without the `timeout=SECS` option.
+pub(crate) const DEFAULT_TIMEOUT: u64 = 5;
+
+/// The bytes of the output kept to be searched, the rest of the output is discarded.
+pub(crate) const MAX_OUTPUT: u64 = 1024 * 1024;
+
+/// The command of the command conditions, ex: `"pg_isready -q", timeout=3`
+pub(crate) struct CommandSpec {
+    /// The command line as written in the attribute
+    pub(crate) line: String,
+    /// The program and the arguments to run, the command line is passed to `sh -c` (`cmd /C` on
+    /// Windows) with the `shell` option, otherwise it is split into words and run directly.
+    pub(crate) argv: Vec<String>,
+    pub(crate) timeout: u64,
+    /// The text expected in the output of the command, the output is only captured with it.
+    pub(crate) contains: Option<String>,
+}
+
+/// Parse the attribute of the command condition, the first item is the quoted command line, and
+/// the following items are the `shell` and `timeout=SECS` options.
+pub(crate) fn parse_command(attr_str: &str) -> CommandSpec {
+    let spec = parse_command_spec(attr_str);
+    if spec.contains.is_some() {
+        abort_call_site!(
+            "the `contains` option is for `command_output`, ex: `#[test_with::command_output(\"nvidia-smi -L\", contains=\"A100\")]`"
+        );
+    }
+    spec
+}
+
+/// Parse the attribute of the command output condition, which is the same as the command
+/// condition with the required `contains="TEXT"` option.
+pub(crate) fn parse_command_output(attr_str: &str) -> CommandSpec {
+    let spec = parse_command_spec(attr_str);
+    if spec.contains.is_none() {
+        abort_call_site!(
+            "the expected output is required, ex: `#[test_with::command_output(\"nvidia-smi -L\", contains=\"A100\")]`"
+        );
+    }
+    spec
+}
+
+fn parse_command_spec(attr_str: &str) -> CommandSpec {
+    let (line, mut options) = match split_literal(attr_str.trim()) {
+        Some((line, options)) if !line.trim().is_empty() => (line, options),
+        _ => abort_call_site!(
+            "the command should be a string literal, ex: `#[test_with::command(\"pg_isready -q\")]`"
+        ),
+    };
+    let mut shell = false;
+    let mut timeout = DEFAULT_TIMEOUT;
+    let mut contains = None;
+    while !options.is_empty() {
+        if let Some(value) = options
+            .strip_prefix("contains")
+            .and_then(|value| value.trim_start().strip_prefix('='))
+        {
+            match split_literal(value.trim_start()) {
+                Some((text, rest)) if !text.is_empty() => {
+                    contains = Some(text);
+                    options = rest;
+                }
+                _ => abort_call_site!("`contains` should be a non-empty string literal"),
+            }
+            continue;
+        }
+        let (option, rest) = options.split_once(',').unwrap_or((options, ""));
+        options = rest.trim_start();
+        let option: String = option.split_whitespace().collect();
+        if option == "shell" {
+            shell = true;
+        } else if let Some(secs) = option.strip_prefix("timeout=") {
+            timeout = match secs.parse::<u64>() {
+                Ok(secs) if secs > 0 => secs,
+                _ => abort_call_site!("`timeout` should be a positive number of seconds"),
+            };
+        } else {
+            abort_call_site!(
+                "unknown option `{}` of the command condition, the options are `shell` and `timeout=SECS`",
+                option
+            );
+        }
+    }
+    let argv = if shell {
+        if cfg!(windows) {
+            vec!["cmd".into(), "/C".into(), line.clone()]
+        } else {
+            vec!["sh".into(), "-c".into(), line.clone()]
+        }
+    } else {
+        split_words(&line).unwrap_or_else(|e| abort_call_site!("{} in `{}`", e, line))
+    };
+    CommandSpec {
+        line,
+        argv,
+        timeout,
+        contains,
+    }
+}
+
+/// Split the leading string literal, ex: `"pg_isready -q"` or `r#"echo "a""#`, from the items
+/// after it, and return the value of the literal.
+fn split_literal(attr_str: &str) -> Option<(String, &str)> {
+    let end = if let Some(raw) = attr_str.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let close = format!("\"{}", "#".repeat(hashes));
+        let body = raw[hashes..].strip_prefix('"')?;
+        attr_str.len() - body.len() + body.find(&close)? + close.len()
+    } else {
+        let mut escaped = false;
+        attr_str.strip_prefix('"')?;
+        1 + attr_str[1..].char_indices().find_map(|(i, c)| match c {
+            _ if escaped => {
+                escaped = false;
+                None
+            }
+            '\\' => {
+                escaped = true;
+                None
+            }
+            '"' => Some(i + 1),
+            _ => None,
+        })?
+    };
+    let value = syn::parse_str::<syn::LitStr>(&attr_str[..end])
+        .ok()?
+        .value();
+    let rest = attr_str[end..].trim_start();
+    match rest.strip_prefix(',') {
+        Some(rest) => Some((value, rest.trim_start())),
+        None if rest.is_empty() => Some((value, rest)),
+        None => None,
+    }
+}
+
+/// Split the command line into words without a shell.  The words are separated by whitespace, the
+/// text in single quotes is kept as it is, and in double quotes or outside quotes a backslash
+/// escapes the next character.  There is no expansion of variables, globs, pipes or redirections.
+pub(crate) fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unclosed single quote".into()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("unclosed double quote".into()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unclosed double quote".into()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => return Err("trailing backslash".into()),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Run the command and wait until it exits or the timeout, the stdout is captured when the output
+/// is expected, and the error is the reason to ignore.
+pub(crate) fn run_command(spec: &CommandSpec) -> Result<(ExitStatus, String), String> {
+    let mut child = match Command::new(&spec.argv[0])
+        .args(&spec.argv[1..])
+        .stdin(Stdio::null())
+        .stdout(if spec.contains.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return Err(format!("`{}` could not be run: {}", spec.line, e)),
+    };
+    let deadline = Instant::now() + Duration::from_secs(spec.timeout);
+    let output = child.stdout.take().map(|mut stdout| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = vec![];
+            let _ = (&mut stdout).take(MAX_OUTPUT).read_to_end(&mut output);
+            let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+            let _ = sender.send(output);
+        });
+        receiver
+    });
+    let timed_out = || format!("`{}` did not finish in {}s", spec.line, spec.timeout);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+            Err(e) => return Err(format!("`{}` could not be waited: {}", spec.line, e)),
+        }
+    };
+    let output = match output {
+        Some(receiver) => receiver
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .map_err(|_| timed_out())?,
+        None => vec![],
+    };
+    Ok((status, String::from_utf8_lossy(&output).into_owned()))
+}
+
+pub(crate) fn check_command_condition(attr_str: String) -> (bool, String) {
+    let spec = parse_command(&attr_str);
+    match run_command(&spec) {
+        Ok((status, _)) if status.success() => (true, String::new()),
+        Ok((status, _)) => (
+            false,
+            format!("because `{}` failed with {}", spec.line, status),
+        ),
+        Err(reason) => (false, format!("because {}", reason)),
+    }
+}
+
+pub(crate) fn check_command_output_condition(attr_str: String) -> (bool, String) {
+    let spec = parse_command_output(&attr_str);
+    let contains = spec.contains.as_deref().unwrap_or_default();
+    match run_command(&spec) {
+        Ok((_, output)) if output.contains(contains) => (true, String::new()),
+        Ok(_) => (
+            false,
+            format!(
+                "because the output of `{}` does not contain {:?}",
+                spec.line, contains
+            ),
+        ),
+        Err(reason) => (false, format!("because {}", reason)),
+    }
+}
+
+/// The generated code to run the command, which evaluates to the exit status and the captured
+/// output, or the reason to ignore.
+#[cfg(feature = "runtime")]
+fn run_command_tokens(spec: &CommandSpec) -> proc_macro2::TokenStream {
+    let CommandSpec {
+        line,
+        argv,
+        timeout,
+        contains,
+    } = spec;
+    let program = &argv[0];
+    let args = &argv[1..];
+    let argc = args.len();
+    let stdout = if contains.is_some() {
+        quote::quote! { std::process::Stdio::piped() }
+    } else {
+        quote::quote! { std::process::Stdio::null() }
+    };
+    quote::quote! {
+        (|| -> Result<(std::process::ExitStatus, String), String> {
+            use std::io::Read;
+            let mut child = std::process::Command::new(#program)
+                .args([#(#args),*] as [&str; #argc])
+                .stdin(std::process::Stdio::null())
+                .stdout(#stdout)
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .map_err(|e| format!("`{}` could not be run: {}", #line, e))?;
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(#timeout);
+            let output = child.stdout.take().map(|mut stdout| {
+                let (sender, receiver) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    let mut output = vec![];
+                    let _ = (&mut stdout).take(#MAX_OUTPUT).read_to_end(&mut output);
+                    let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+                    let _ = sender.send(output);
+                });
+                receiver
+            });
+            let timed_out = || format!("`{}` did not finish in {}s", #line, #timeout);
+            let status = loop {
+                match child.try_wait() {
+                    Ok(Some(status)) => break status,
+                    Ok(None) if std::time::Instant::now() < deadline => {
+                        std::thread::sleep(std::time::Duration::from_millis(10))
+                    }
+                    Ok(None) => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(timed_out());
+                    }
+                    Err(e) => return Err(format!("`{}` could not be waited: {}", #line, e)),
+                }
+            };
+            let output = match output {
+                Some(receiver) => receiver
+                    .recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+                    .map_err(|_| timed_out())?,
+                None => vec![],
+            };
+            Ok((status, String::from_utf8_lossy(&output).into_owned()))
+        })()
+    }
+}
+
+/// The generated code of the runtime command condition, which evaluates to the reason to ignore
+/// when the command does not succeed.
+#[cfg(feature = "runtime")]
+pub(crate) fn command_check_tokens(spec: &CommandSpec) -> proc_macro2::TokenStream {
+    let line = &spec.line;
+    let run = run_command_tokens(spec);
+    quote::quote! {
+        match #run {
+            Ok((status, _)) if status.success() => None,
+            Ok((status, _)) => Some(format!("because `{}` failed with {}", #line, status)),
+            Err(reason) => Some(format!("because {}", reason)),
+        }
+    }
+}
+
+/// The generated code of the runtime command output condition, which evaluates to the reason to
+/// ignore when the output does not contain the expected text.
+#[cfg(feature = "runtime")]
+pub(crate) fn command_output_check_tokens(spec: &CommandSpec) -> proc_macro2::TokenStream {
+    let line = &spec.line;
+    let contains = spec.contains.as_deref().unwrap_or_default();
+    let run = run_command_tokens(spec);
+    quote::quote! {
+        match #run {
+            Ok((_, output)) if output.contains(#contains) => None,
+            Ok(_) => Some(format!("because the output of `{}` does not contain {:?}", #line, #contains)),
+            Err(reason) => Some(format!("because {}", reason)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_command_condition, check_command_output_condition, split_words};
+
+    #[test]
+    fn split_command_words() {
+        //* Given
+        let line = r#"psql -c 'select 1' "a \"b\"" c\ d"#;
+
+        //* When
+        let words = split_words(line).unwrap();
+
+        //* Then
+        assert_eq!(words, vec!["psql", "-c", "select 1", "a \"b\"", "c d"]);
+        assert!(split_words("echo 'unclosed").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_command_exit_status() {
+        //* Given
+        let succeeds = r#""true""#.to_string();
+        let fails = r#""sh -c 'exit 3'""#.to_string();
+
+        //* When
+        let (succeeded, _) = check_command_condition(succeeds);
+        let (failed, ignore_msg) = check_command_condition(fails);
+
+        //* Then
+        assert!(succeeded);
+        assert!(!failed);
+        assert_eq!(
+            ignore_msg,
+            "because `sh -c 'exit 3'` failed with exit status: 3"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_command_output_contains() {
+        //* Given
+        let listed = r#""echo GPU 0: A100, GPU 1: T4", contains="A100""#.to_string();
+        let missing = r#"r"echo 'GPU 0: T4'", contains = "A100", timeout=1"#.to_string();
+
+        //* When
+        let (found, _) = check_command_output_condition(listed);
+        let (not_found, ignore_msg) = check_command_output_condition(missing);
+
+        //* Then
+        assert!(found);
+        assert!(!not_found);
+        assert_eq!(
+            ignore_msg,
+            "because the output of `echo 'GPU 0: T4'` does not contain \"A100\""
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, TokenTree};
+use proc_macro_error2::abort_call_site;
+use quote::quote;
+use syn::ItemFn;
+
+use crate::utils::{check_before_attrs, has_test_attr, tokens_to_string};
+
+/// The compile-time check of the condition named in the sub-condition, ex: `env` of `env(PWD)`
+pub(crate) fn condition_check(name: &str) -> Option<fn(String) -> (bool, String)> {
+    let check: fn(String) -> (bool, String) = match name {
+        "env" => crate::check_env_condition,
+        "no_env" => crate::check_no_env_condition,
+        "env_true" => crate::check_env_true_condition,
+        "env_file" => crate::check_env_file_condition,
+        "ci" => crate::check_ci_condition,
+        #[cfg(feature = "config")]
+        "config" => crate::config::check_config_condition,
+        "file" => crate::check_file_condition,
+        "path" => crate::check_path_condition,
+        "dir" => crate::check_dir_condition,
+        "dir_not_empty" => crate::check_dir_not_empty_condition,
+        "mounted" => crate::mount::check_mount_condition,
+        "min_rust" => crate::toolchain::check_min_rust_condition,
+        #[cfg(feature = "http")]
+        "http" => crate::check_http_condition,
+        #[cfg(feature = "http")]
+        "https" => crate::check_https_condition,
+        #[cfg(feature = "icmp")]
+        "icmp" => crate::check_icmp_condition,
+        "tcp" => crate::check_tcp_condition,
+        "listening" => crate::socket::check_listening_condition,
+        "display" => crate::display::check_display_condition,
+        "monitors" => crate::monitors::check_monitors_condition,
+        "systemd_active" => crate::systemd::check_systemd_condition,
+        "command" => crate::command::check_command_condition,
+        "command_output" => crate::command::check_command_output_condition,
+        "file_version" => crate::file_version::check_file_version_condition,
+        #[cfg(all(feature = "user", not(target_os = "windows")))]
+        "root" => crate::check_root_condition,
+        #[cfg(all(feature = "user", not(target_os = "windows")))]
+        "effective_root" => crate::check_effective_root_condition,
+        #[cfg(all(feature = "user", not(target_os = "windows")))]
+        "group" => crate::check_group_condition,
+        #[cfg(all(feature = "user", not(target_os = "windows")))]
+        "user" => crate::check_user_condition,
+        #[cfg(feature = "capability")]
+        "capability" => crate::capability::check_capability_condition,
+        #[cfg(feature = "resource")]
+        "mem" => crate::check_mem_condition,
+        #[cfg(feature = "resource")]
+        "free_mem" => crate::check_free_mem_condition,
+        #[cfg(feature = "resource")]
+        "available_mem" => crate::check_available_mem_condition,
+        #[cfg(feature = "resource")]
+        "swap" => crate::check_swap_condition,
+        #[cfg(feature = "resource")]
+        "max_swap" => crate::check_max_swap_condition,
+        #[cfg(feature = "resource")]
+        "cpu_core" => crate::check_cpu_core_condition,
+        #[cfg(feature = "resource")]
+        "phy_core" => crate::check_phy_core_condition,
+        #[cfg(feature = "resource")]
+        "smt" => crate::check_smt_condition,
+        #[cfg(feature = "resource")]
+        "parallelism" => crate::check_parallelism_condition,
+        #[cfg(feature = "resource")]
+        "min_open_files" => crate::ulimit::check_ulimit_condition,
+        #[cfg(feature = "resource")]
+        "kernel" => crate::kernel::check_kernel_condition,
+        #[cfg(feature = "resource")]
+        "min_entropy" => crate::entropy::check_entropy_condition,
+        #[cfg(feature = "executable")]
+        "executable" => crate::check_executable_condition,
+        #[cfg(feature = "library")]
+        "library" => crate::library::check_library_condition,
+        #[cfg(feature = "hostname")]
+        "hostname" => crate::hostname::check_hostname_condition,
+        #[cfg(feature = "timezone")]
+        "timezone" => crate::check_tz_condition,
+        _ => return None,
+    };
+    Some(check)
+}
+
+/// The runtime macro of the condition named in the sub-condition, ex: `runtime_env` of `env(PWD)`,
+/// the check generated by the macro is reused by the runtime combinators.  `ci` has no runtime
+/// macro of its own, and is checked as the opposite of `runtime_skip_if_ci`.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_condition(name: &str) -> Option<fn(TokenStream, TokenStream) -> TokenStream> {
+    let runtime_macro: fn(TokenStream, TokenStream) -> TokenStream = match name {
+        "env" => crate::runtime_env,
+        "no_env" => crate::runtime_no_env,
+        "env_true" => crate::runtime_env_true,
+        "env_file" => crate::runtime_env_file,
+        #[cfg(feature = "config")]
+        "config" => crate::runtime_config,
+        "file" => crate::runtime_file,
+        "path" => crate::runtime_path,
+        "dir" => crate::runtime_dir,
+        "dir_not_empty" => crate::runtime_dir_not_empty,
+        "mounted" => crate::runtime_mounted,
+        "min_rust" => crate::runtime_min_rust,
+        #[cfg(feature = "http")]
+        "http" => crate::runtime_http,
+        #[cfg(feature = "http")]
+        "https" => crate::runtime_https,
+        #[cfg(feature = "icmp")]
+        "icmp" => crate::runtime_icmp,
+        "tcp" => crate::runtime_tcp,
+        "listening" => crate::runtime_listening,
+        "display" => crate::runtime_display,
+        "monitors" => crate::runtime_monitors,
+        "systemd_active" => crate::runtime_systemd_active,
+        "command" => crate::runtime_command,
+        "command_output" => crate::runtime_command_output,
+        "file_version" => crate::runtime_file_version,
+        #[cfg(feature = "user")]
+        "root" => crate::runtime_root,
+        #[cfg(feature = "user")]
+        "effective_root" => crate::runtime_effective_root,
+        #[cfg(feature = "user")]
+        "group" => crate::runtime_group,
+        #[cfg(feature = "user")]
+        "user" => crate::runtime_user,
+        #[cfg(feature = "capability")]
+        "capability" => crate::runtime_capability,
+        #[cfg(feature = "resource")]
+        "mem" => crate::runtime_mem,
+        #[cfg(feature = "resource")]
+        "free_mem" => crate::runtime_free_mem,
+        #[cfg(feature = "resource")]
+        "available_mem" => crate::runtime_available_mem,
+        #[cfg(feature = "resource")]
+        "swap" => crate::runtime_swap,
+        #[cfg(feature = "resource")]
+        "max_swap" => crate::runtime_max_swap,
+        #[cfg(feature = "resource")]
+        "cpu_core" => crate::runtime_cpu_core,
+        #[cfg(feature = "resource")]
+        "phy_core" => crate::runtime_phy_cpu_core,
+        #[cfg(feature = "resource")]
+        "smt" => crate::runtime_smt,
+        #[cfg(feature = "resource")]
+        "parallelism" => crate::runtime_parallelism,
+        #[cfg(feature = "resource")]
+        "min_open_files" => crate::runtime_min_open_files,
+        #[cfg(feature = "resource")]
+        "kernel" => crate::runtime_kernel,
+        #[cfg(feature = "resource")]
+        "min_entropy" => crate::runtime_min_entropy,
+        #[cfg(feature = "executable")]
+        "executable" => crate::runtime_executable,
+        #[cfg(feature = "library")]
+        "library" => crate::runtime_library,
+        #[cfg(feature = "hostname")]
+        "hostname" => crate::runtime_hostname,
+        #[cfg(feature = "timezone")]
+        "timezone" => crate::runtime_timezone,
+        _ => return None,
+    };
+    Some(runtime_macro)
+}
+
+// split the tokens with the commas which are not in any group
+pub(crate) fn split_top_level(tokens: proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+    let mut items = vec![proc_macro2::TokenStream::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == ',' => items.push(Default::default()),
+            _ => items.last_mut().expect("at least one item").extend([token]),
+        }
+    }
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Transform the sub-condition into a `cfg` predicate, ex: `any(os(windows), env(CI))`.
+/// The `os` is checked by the target of the test case, so it still works when cross compiling, and
+/// the other conditions are checked when compiling, then become `all()` for true or `any()` for
+/// false.  The sub-conditions can be combined with `all`, `any` and `not`.
+pub(crate) fn condition_to_cfg(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut tokens = tokens.into_iter();
+    let (name, args) = match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(name)), Some(TokenTree::Group(args)), None)
+            if args.delimiter() == Delimiter::Parenthesis =>
+        {
+            (name.to_string(), args.stream())
+        }
+        (Some(TokenTree::Ident(name)), None, None) => {
+            (name.to_string(), proc_macro2::TokenStream::new())
+        }
+        _ => abort_call_site!(
+            "the sub-condition should be like `os(windows)`, `env(CI)`, or combined with `all`, `any` and `not`"
+        ),
+    };
+    match name.as_str() {
+        "all" => {
+            let predicates = split_top_level(args).into_iter().map(condition_to_cfg);
+            quote! { all(#(#predicates),*) }
+        }
+        "any" => {
+            let predicates = split_top_level(args).into_iter().map(condition_to_cfg);
+            quote! { any(#(#predicates),*) }
+        }
+        "not" => match split_top_level(args).as_slice() {
+            [condition] => {
+                let predicate = condition_to_cfg(condition.clone());
+                quote! { not(#predicate) }
+            }
+            _ => abort_call_site!("`not` needs exactly one sub-condition"),
+        },
+        "os" => {
+            let os_names: Vec<String> = split_top_level(args)
+                .into_iter()
+                .map(|os_name| tokens_to_string(os_name).trim_matches('"').to_string())
+                .collect();
+            if os_names.is_empty() {
+                abort_call_site!("`os` needs the operating systems, ex: `os(windows, macos)`");
+            }
+            quote! { any(#(target_os = #os_names),*) }
+        }
+        _ => match condition_check(&name) {
+            Some(check) if check(tokens_to_string(args)).0 => quote! { all() },
+            Some(_) => quote! { any() },
+            None => abort_call_site!("`{}` is not a supported sub-condition", name),
+        },
+    }
+}
+
+pub(crate) fn expect_panic_if_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let predicate = condition_to_cfg(attr.into());
+
+    check_before_attrs(&attrs);
+    let test_attr = if has_test_attr(&attrs) {
+        quote! {}
+    } else {
+        quote! { #[test] }
+    };
+
+    quote! {
+        #(#attrs)*
+        #test_attr
+        #[cfg_attr(#predicate, should_panic)]
+        #vis #sig #block
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::condition_to_cfg;
+
+    #[test]
+    fn combine_sub_conditions() {
+        //* Given
+        let condition = quote::quote!(any(os(windows, macos), not(env(PATH)), all()));
+
+        //* When
+        let predicate = condition_to_cfg(condition);
+
+        //* Then
+        assert_eq!(
+            predicate.to_string(),
+            quote::quote!(any(
+                any(target_os = "windows", target_os = "macos"),
+                not(all()),
+                all()
+            ))
+            .to_string()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use proc_macro_error2::abort_call_site;
+use serde_json::Value;
+
+/// Split the attribute of the config conditions, ex: `"tests.toml": integration.enabled`, into the
+/// config file and the dotted key.
+pub(crate) fn split_config_attr(attr_str: &str) -> (String, String) {
+    match attr_str.rsplit_once(':') {
+        Some((file, key)) if !key.trim().is_empty() => (
+            file.trim().trim_matches('"').to_string(),
+            key.trim().trim_matches('"').to_string(),
+        ),
+        _ => abort_call_site!(
+            "the config condition should be used as `config(\"tests.toml\": integration.enabled)`"
+        ),
+    }
+}
+
+/// Parse the config file as JSON if the file name ends with `.json`, otherwise as TOML.
+pub(crate) fn parse_config(file: &str, content: &str) -> Result<Value, String> {
+    if file.ends_with(".json") {
+        serde_json::from_str(content).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+}
+
+/// The value of the dotted key, ex: `integration.enabled`
+pub(crate) fn lookup<'a>(config: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(config, |value, part| value.as_object()?.get(part))
+}
+
+/// The `false`, zero, empty string, `"false"`, `"0"`, empty array and empty table are not truthy.
+pub(crate) fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().map(|n| n != 0.0).unwrap_or(true),
+        Value::String(s) => !s.is_empty() && s != "false" && s != "0",
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+pub(crate) fn check_config_condition(attr_str: String) -> (bool, String) {
+    let (file, key) = split_config_attr(&attr_str);
+    let content = match std::fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(_) => return (false, format!("because config file {} not found", file)),
+    };
+    let config = match parse_config(&file, &content) {
+        Ok(config) => config,
+        Err(e) => {
+            return (
+                false,
+                format!("because config file {} can not be parsed: {}", file, e),
+            )
+        }
+    };
+    match lookup(&config, &key) {
+        Some(value) if is_truthy(value) => (true, String::new()),
+        Some(_) => (false, format!("because {} key {} is false", file, key)),
+        None => (false, format!("because {} key {} is missing", file, key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_truthy, lookup, parse_config};
+
+    #[test]
+    fn lookup_truthy_keys() {
+        //* Given
+        let toml = parse_config(
+            "tests.toml",
+            "[integration]\nenabled = true\nretries = 0\nname = \"db\"\n",
+        )
+        .unwrap();
+        let json = parse_config("tests.json", r#"{"integration": {"enabled": "false"}}"#).unwrap();
+
+        //* When
+        let values: Vec<_> = [
+            lookup(&toml, "integration.enabled"),
+            lookup(&toml, "integration.retries"),
+            lookup(&toml, "integration.name"),
+            lookup(&json, "integration.enabled"),
+        ]
+        .iter()
+        .map(|value| value.map(is_truthy))
+        .collect();
+
+        //* Then
+        assert_eq!(
+            values,
+            vec![Some(true), Some(false), Some(true), Some(false)]
+        );
+        assert!(lookup(&toml, "integration.missing").is_none());
+    }
+}
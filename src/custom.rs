@@ -0,0 +1,90 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use proc_macro_error2::abort_call_site;
+use quote::quote;
+use syn::ItemFn;
+
+use crate::utils::tokens_to_string;
+
+/// Split the attribute of the custom condition, ex: `gpu::check_cuda, ">=12"`, into the path of the
+/// checker and the argument passed to it.  The argument is the value of the string literal, or the
+/// tokens written as the other conditions, and it is empty when there is no argument.
+pub(crate) fn parse_custom(attr: proc_macro2::TokenStream) -> (syn::Path, String) {
+    let mut tokens = attr.into_iter();
+    let path: proc_macro2::TokenStream = tokens
+        .by_ref()
+        .take_while(|token| !matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+        .collect();
+    let path = match syn::parse2::<syn::Path>(path) {
+        Ok(path) => path,
+        Err(_) => abort_call_site!(
+            "the custom condition should be like `custom(path::to::checker)` or `custom(path::to::checker, \"argument\")`"
+        ),
+    };
+    let args: proc_macro2::TokenStream = tokens.collect();
+    let args = match syn::parse2::<syn::LitStr>(args.clone()) {
+        Ok(lit) => lit.value(),
+        Err(_) => tokens_to_string(args),
+    };
+    (path, args)
+}
+
+pub(crate) fn runtime_custom_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let (checker, args) = parse_custom(attr.into());
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let (met, reason): (bool, String) = #checker(#args.to_string());
+            if met {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("{}", reason)))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_custom;
+
+    #[test]
+    fn parse_custom_checkers() {
+        //* Given
+        let attrs = [
+            quote::quote!(gpu::check_cuda),
+            quote::quote!(gpu::check_cuda, ">=12"),
+            quote::quote!(crate::check_service, db:5432),
+        ];
+
+        //* When
+        let parsed: Vec<(String, String)> = attrs
+            .into_iter()
+            .map(parse_custom)
+            .map(|(path, args)| (quote::quote!(#path).to_string().replace(' ', ""), args))
+            .collect();
+
+        //* Then
+        assert_eq!(
+            parsed,
+            vec![
+                ("gpu::check_cuda".to_string(), String::new()),
+                ("gpu::check_cuda".to_string(), ">=12".to_string()),
+                ("crate::check_service".to_string(), "db:5432".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::ItemFn;
+
+/// The generated code detecting the debugger of the test binary, which is `true` when a tracer is
+/// attached.  The tracer is the `TracerPid` of `/proc/self/status` on Linux and Android, and it is
+/// asked with `IsDebuggerPresent` on Windows.  The debugger is not detected on the other operating
+/// systems, ex: macOS.
+fn debugger_tokens() -> proc_macro2::TokenStream {
+    quote! {{
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let traced = std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("TracerPid:"))
+                    .map(|pid| pid.trim() != "0")
+            })
+            .unwrap_or(false);
+        #[cfg(windows)]
+        let traced = {
+            unsafe extern "system" {
+                fn IsDebuggerPresent() -> i32;
+            }
+            unsafe { IsDebuggerPresent() != 0 }
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+        let traced = false;
+        traced
+    }}
+}
+
+fn ignore_msg(under_debugger: bool) -> &'static str {
+    if under_debugger {
+        "because the test is not running under a debugger"
+    } else {
+        "because the test is running under a debugger"
+    }
+}
+
+pub(crate) fn runtime_debugger_macro(under_debugger: bool, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let detect = debugger_tokens();
+    let ignore_msg = ignore_msg(under_debugger);
+
+    quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if #detect == #under_debugger {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(#ignore_msg)))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
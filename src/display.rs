@@ -0,0 +1,27 @@
+/// The environment variables of the X11 and Wayland display servers
+const DISPLAY_ENV_VARS: [&str; 2] = ["DISPLAY", "WAYLAND_DISPLAY"];
+
+/// The display is always available in the GUI sessions of macOS and Windows, and it is available
+/// on the other systems when the X11 or Wayland display is set.
+pub(crate) fn has_display() -> bool {
+    cfg!(any(target_os = "macos", target_os = "windows"))
+        || DISPLAY_ENV_VARS
+            .iter()
+            .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+pub(crate) fn check_display_condition(_attr_str: String) -> (bool, String) {
+    (has_display(), "because no display available".to_string())
+}
+
+/// The generated check of the display, the target of the example is checked in the generated code
+#[cfg(feature = "runtime")]
+pub(crate) fn display_check_tokens() -> proc_macro2::TokenStream {
+    let display_env_vars = DISPLAY_ENV_VARS;
+    quote::quote! {
+        cfg!(any(target_os = "macos", target_os = "windows"))
+            || [#(#display_env_vars),*]
+                .iter()
+                .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+    }
+}
@@ -0,0 +1,60 @@
+use proc_macro_error2::abort_call_site;
+
+/// The available entropy of the kernel in bits
+#[cfg(target_os = "linux")]
+pub(crate) const ENTROPY_AVAIL_FILE: &str = "/proc/sys/kernel/random/entropy_avail";
+
+pub(crate) fn parse_entropy_requirement(attr_str: &str) -> u64 {
+    match attr_str.trim().parse::<u64>() {
+        Ok(bits) => bits,
+        Err(_) => abort_call_site!("the entropy should be the bits, ex: `min_entropy(256)`"),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn check_entropy_condition(attr_str: String) -> (bool, String) {
+    let required = parse_entropy_requirement(&attr_str);
+    match std::fs::read_to_string(ENTROPY_AVAIL_FILE)
+        .ok()
+        .and_then(|content| content.trim().parse::<u64>().ok())
+    {
+        Some(entropy) if entropy >= required => (true, String::new()),
+        Some(entropy) => (
+            false,
+            format!(
+                "because the available entropy {} is less than {}",
+                entropy, required
+            ),
+        ),
+        None => (
+            false,
+            "because the available entropy is unknown".to_string(),
+        ),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_entropy_condition(attr_str: String) -> (bool, String) {
+    parse_entropy_requirement(&attr_str);
+    (
+        false,
+        "because the available entropy is only checked on Linux".into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_entropy_requirement;
+
+    #[test]
+    fn parse_entropy_bits() {
+        //* Given
+        let attr_str = " 256 ";
+
+        //* When
+        let required = parse_entropy_requirement(attr_str);
+
+        //* Then
+        assert_eq!(required, 256);
+    }
+}
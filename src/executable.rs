@@ -0,0 +1,100 @@
+use proc_macro_error2::abort_call_site;
+use quote::quote;
+
+use crate::toolchain::{find_version_tokens, parse_version, COMPARATORS};
+
+/// Split the executable into the name and the optional version requirement, ex: `ffmpeg>=6.0`
+/// becomes `ffmpeg` with `>=` and `6.0`.
+pub(crate) fn split_version_requirement(item: &str) -> (&str, Option<(&'static str, &str)>) {
+    let (name, rest) = match item.find(['<', '>', '=']) {
+        Some(pos) => item.split_at(pos),
+        None => return (item.trim_matches('"'), None),
+    };
+    let comparator = match COMPARATORS.into_iter().find(|c| rest.starts_with(c)) {
+        Some(comparator) => comparator,
+        None => abort_call_site!(
+            "the comparator of the executable version should be one of >=, <=, ==, >, <"
+        ),
+    };
+    let version = rest[comparator.len()..].trim().trim_matches('"');
+    if name.trim().is_empty() || parse_version(version).is_none() {
+        abort_call_site!("the executable version should be like `ffmpeg >= 6.0`");
+    }
+    (name.trim().trim_matches('"'), Some((comparator, version)))
+}
+
+/// The generated check of one executable, the missing executable is pushed into
+/// `missing_executables`, and the executable not in the required version is pushed into
+/// `unmet_versions`.  The version is the first dotted number in the output of `--version`, ex:
+/// `6.0` of `ffmpeg version 6.0-static`.
+pub(crate) fn executable_check_tokens(item: &str) -> proc_macro2::TokenStream {
+    let (name, requirement) = split_version_requirement(item);
+    let (comparator, version) = match requirement {
+        Some(requirement) => requirement,
+        None => {
+            return quote! {
+                if libtest_with::which::which(#name).is_err() {
+                    missing_executables.push(#name);
+                }
+            }
+        }
+    };
+    let (major, minor, patch) = parse_version(version).expect("version is checked");
+    let op: proc_macro2::TokenStream = comparator.parse().expect("comparator is a valid token");
+    let find_version = find_version_tokens(quote! { text });
+    quote! {
+        match libtest_with::which::which(#name) {
+            Err(_) => missing_executables.push(#name),
+            Ok(path) => {
+                let version = std::process::Command::new(path)
+                    .arg("--version")
+                    .output()
+                    .ok()
+                    .and_then(|output| {
+                        let text = format!(
+                            "{}{}",
+                            String::from_utf8_lossy(&output.stdout),
+                            String::from_utf8_lossy(&output.stderr)
+                        );
+                        #find_version
+                    });
+                match version {
+                    Some(version) if version #op (#major, #minor, #patch) => (),
+                    Some((major, minor, patch)) => unmet_versions.push(format!(
+                        "{} {}.{}.{} is not {} {}",
+                        #name, major, minor, patch, #comparator, #version
+                    )),
+                    None => unmet_versions.push(format!("the version of {} is unknown", #name)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_version_requirement;
+
+    #[test]
+    fn split_executable_versions() {
+        //* Given
+        let items = ["ffmpeg>=6.0", "python3==3.11.4", "git<3", "\"/bin/sh\""];
+
+        //* When
+        let splitted: Vec<_> = items
+            .iter()
+            .map(|item| split_version_requirement(item))
+            .collect();
+
+        //* Then
+        assert_eq!(
+            splitted,
+            vec![
+                ("ffmpeg", Some((">=", "6.0"))),
+                ("python3", Some(("==", "3.11.4"))),
+                ("git", Some(("<", "3"))),
+                ("/bin/sh", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use proc_macro_error2::abort_call_site;
+
+/// Parse the feature names of `runtime_feature`, the names can be quoted or not, ex:
+/// `"my-feature"` or `my_feature`.
+pub(crate) fn parse_feature_names(attr_str: &str) -> Vec<String> {
+    let names: Vec<String> = attr_str
+        .split(',')
+        .map(|name| name.trim().trim_matches('"').to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        abort_call_site!(
+            "feature name is required, ex: `#[test_with::runtime_feature(\"my-feature\")]`"
+        );
+    }
+    for name in names.iter() {
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '+' || c == '.')
+        {
+            abort_call_site!("`{}` is not a valid feature name", name);
+        }
+    }
+    names
+}
+
+/// The check statements of `runtime_feature`, the `cfg!` is expanded in the crate building the
+/// example, so the features of that crate are checked rather than the features of this crate.
+pub(crate) fn feature_check_tokens(
+    names: &[String],
+    ident: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    quote::quote! {
+        let mut missing_features: Vec<&str> = vec![];
+        #(
+            if !cfg!(feature = #names) {
+                missing_features.push(#names);
+            }
+        )*
+        match missing_features.len() {
+            0 => {
+                #ident();
+                Ok(())
+            },
+            1 => Err(_ignore_reason(format_args!(
+                "because feature {} is not enabled",
+                missing_features[0]
+            ))),
+            _ => Err(_ignore_reason(format_args!(
+                "because following features are not enabled:\n{}\n",
+                missing_features.join(", ")
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_feature_names;
+
+    #[test]
+    fn parse_quoted_and_bare_feature_names() {
+        //* Given
+        let attr_str = "\"my-feature\",my_feature";
+
+        //* When
+        let names = parse_feature_names(attr_str);
+
+        //* Then
+        assert_eq!(names, vec!["my-feature", "my_feature"]);
+    }
+}
@@ -0,0 +1,205 @@
+use proc_macro_error2::abort_call_site;
+
+use crate::toolchain::{compare_versions, find_version, parse_version, COMPARATORS};
+
+/// Split the attribute of the file version condition, ex: `".python-version" == python`, into the
+/// file pinning the version, the comparator and the executable.
+pub(crate) fn split_file_version_attr(attr_str: &str) -> (String, &'static str, String) {
+    let attr_str = attr_str.trim();
+    let path_end = match attr_str.strip_prefix('"') {
+        Some(quoted) => quoted.find('"').map(|end| end + 2).unwrap_or(0),
+        None => 0,
+    };
+    let (file, rest) = match attr_str[path_end..].find(['<', '>', '=']) {
+        Some(pos) => attr_str.split_at(path_end + pos),
+        None => ("", ""),
+    };
+    let comparator = COMPARATORS.into_iter().find(|c| rest.starts_with(c));
+    let exe = rest[comparator.map(str::len).unwrap_or(0)..].trim();
+    match comparator {
+        Some(comparator) if !file.trim().is_empty() && !exe.is_empty() => (
+            file.trim().trim_matches('"').to_string(),
+            comparator,
+            exe.trim_matches('"').to_string(),
+        ),
+        _ => abort_call_site!(
+            "the file version condition should be like `file_version(\".python-version\" == python)`, and the comparator should be one of >=, <=, ==, >, <"
+        ),
+    }
+}
+
+/// The version pinned in the file, which is the first line not empty nor a comment, without the
+/// prefix before the digits, ex: `18` of `v18` or `3.11` of `python-3.11`.  The version is returned
+/// with the text as written in the file.
+pub(crate) fn pinned_version(content: &str) -> Option<(&str, (u64, u64, u64))> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let word = line.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let end = word
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(word.len());
+    let text = word[..end].trim_end_matches('.');
+    Some((text, parse_version(text)?))
+}
+
+/// Drop the parts of the version not written in the pinned version, so `3.11` in the file is
+/// compared with `3.11` of `3.11.4`.
+pub(crate) fn truncate_version(version: (u64, u64, u64), pinned: &str) -> (u64, u64, u64) {
+    let (major, minor, patch) = version;
+    match pinned.split('.').count() {
+        1 => (major, 0, 0),
+        2 => (major, minor, 0),
+        _ => (major, minor, patch),
+    }
+}
+
+/// The version of the executable, which is the first dotted number in the output of `--version`,
+/// the error is the reason to ignore.
+fn executable_version(exe: &str) -> Result<(u64, u64, u64), String> {
+    let output = std::process::Command::new(exe)
+        .arg("--version")
+        .output()
+        .map_err(|_| format!("executable {} not found", exe))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    find_version(&text).ok_or_else(|| format!("the version of {} is unknown", exe))
+}
+
+pub(crate) fn check_file_version_condition(attr_str: String) -> (bool, String) {
+    let (file, comparator, exe) = split_file_version_attr(&attr_str);
+    let content = match std::fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(_) => return (false, format!("because {} not found", file)),
+    };
+    let (pinned, pinned_version) = match pinned_version(&content) {
+        Some(pinned) => pinned,
+        None => return (false, format!("because the version in {} is unknown", file)),
+    };
+    let version = match executable_version(&exe) {
+        Ok(version) => version,
+        Err(reason) => return (false, format!("because {}", reason)),
+    };
+    if compare_versions(
+        pinned_version,
+        comparator,
+        truncate_version(version, pinned),
+    ) {
+        (true, String::new())
+    } else {
+        let (major, minor, patch) = version;
+        (
+            false,
+            format!(
+                "because {} in {} is not {} {} {}.{}.{}",
+                pinned, file, comparator, exe, major, minor, patch
+            ),
+        )
+    }
+}
+
+/// The generated code of the runtime file version condition, which evaluates to the reason to
+/// ignore when the version of the executable does not match the version in the file.
+#[cfg(feature = "runtime")]
+pub(crate) fn file_version_check_tokens(attr_str: &str) -> proc_macro2::TokenStream {
+    let (file, comparator, exe) = split_file_version_attr(attr_str);
+    let op: proc_macro2::TokenStream = comparator.parse().expect("comparator is a valid token");
+    let find_version = crate::toolchain::find_version_tokens(quote::quote! { text });
+    quote::quote! {
+        (|| -> Option<String> {
+            let content = match std::fs::read_to_string(#file) {
+                Ok(content) => content,
+                Err(_) => return Some(format!("because {} not found", #file)),
+            };
+            let pinned = content
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let word = line.trim_start_matches(|c: char| !c.is_ascii_digit());
+                    let end = word
+                        .find(|c: char| !c.is_ascii_digit() && c != '.')
+                        .unwrap_or(word.len());
+                    word[..end].trim_end_matches('.')
+                })
+                .unwrap_or_default();
+            let mut parts = pinned.split('.').map(str::parse::<u64>);
+            let pinned_version = match (
+                parts.next(),
+                parts.next().unwrap_or(Ok(0)),
+                parts.next().unwrap_or(Ok(0)),
+                parts.next(),
+            ) {
+                (Some(Ok(major)), Ok(minor), Ok(patch), None) => (major, minor, patch),
+                _ => return Some(format!("because the version in {} is unknown", #file)),
+            };
+            let output = match std::process::Command::new(#exe).arg("--version").output() {
+                Ok(output) => output,
+                Err(_) => return Some(format!("because executable {} not found", #exe)),
+            };
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let (major, minor, patch) = match #find_version {
+                Some(version) => version,
+                None => return Some(format!("because the version of {} is unknown", #exe)),
+            };
+            let version = match pinned.split('.').count() {
+                1 => (major, 0, 0),
+                2 => (major, minor, 0),
+                _ => (major, minor, patch),
+            };
+            if pinned_version #op version {
+                None
+            } else {
+                Some(format!(
+                    "because {} in {} is not {} {} {}.{}.{}",
+                    pinned, #file, #comparator, #exe, major, minor, patch
+                ))
+            }
+        })()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pinned_version, split_file_version_attr, truncate_version};
+
+    #[test]
+    fn parse_pinned_versions() {
+        //* Given
+        let attr_str = "\".python-version\" == python";
+        let contents = [
+            "3.11\n",
+            "v18.17.0",
+            "# comment\n\npython-3.12.1\n",
+            "lts/*",
+        ];
+
+        //* When
+        let attr = split_file_version_attr(attr_str);
+        let pinned: Vec<_> = contents.iter().map(|c| pinned_version(c)).collect();
+
+        //* Then
+        assert_eq!(
+            attr,
+            (".python-version".to_string(), "==", "python".to_string())
+        );
+        assert_eq!(
+            pinned,
+            vec![
+                Some(("3.11", (3, 11, 0))),
+                Some(("18.17.0", (18, 17, 0))),
+                Some(("3.12.1", (3, 12, 1))),
+                None
+            ]
+        );
+        assert_eq!(truncate_version((3, 11, 4), "3.11"), (3, 11, 0));
+    }
+}
@@ -0,0 +1,101 @@
+use proc_macro_error2::abort_call_site;
+
+include!("hostname/wildcard_match.rs");
+
+/// The helpers of `#[test_with::runtime_hostname]` emitted once by `test_with::runner!`, which
+/// read the hostname in the same way as the compile-time check, ex: `/proc/sys/kernel/hostname`
+/// on Linux, rather than depending on the `hostname` command.
+#[cfg(feature = "runtime")]
+pub(crate) fn hostname_helpers_tokens() -> proc_macro2::TokenStream {
+    let wildcard_match: proc_macro2::TokenStream = include_str!("hostname/wildcard_match.rs")
+        .parse()
+        .expect("wildcard_match.rs should be valid tokens");
+    quote::quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        mod _test_with_hostname {
+            #wildcard_match
+
+            pub(crate) fn hostname() -> String {
+                #[cfg(target_os = "linux")]
+                if let Ok(hostname) = std::fs::read_to_string("/proc/sys/kernel/hostname") {
+                    return hostname.trim().to_string();
+                }
+                #[cfg(unix)]
+                {
+                    unsafe extern "C" {
+                        fn gethostname(name: *mut std::ffi::c_char, len: usize) -> i32;
+                    }
+                    let mut name = [0u8; 256];
+                    if unsafe { gethostname(name.as_mut_ptr().cast(), name.len()) } == 0 {
+                        let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+                        return String::from_utf8_lossy(&name[..end]).to_string();
+                    }
+                }
+                #[cfg(windows)]
+                {
+                    unsafe extern "system" {
+                        fn GetComputerNameExW(name_type: i32, name: *mut u16, size: *mut u32) -> i32;
+                    }
+                    let mut name = [0u16; 256];
+                    let mut size = name.len() as u32;
+                    // ComputerNamePhysicalDnsHostname
+                    if unsafe { GetComputerNameExW(5, name.as_mut_ptr(), &mut size) } != 0 {
+                        return String::from_utf16_lossy(&name[..size as usize]);
+                    }
+                }
+                String::new()
+            }
+        }
+    }
+}
+
+/// Parse the hostname patterns, the patterns can be quoted or not, ex: `gpu-01` or `"gpu-*"`.
+pub(crate) fn parse_hostname_patterns(attr_str: &str) -> Vec<String> {
+    let patterns: Vec<String> = attr_str
+        .split(',')
+        .map(|pattern| pattern.trim().trim_matches('"').to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+    if patterns.is_empty() {
+        abort_call_site!("hostname is required, ex: `#[test_with::hostname(gpu-01, gpu-*)]`");
+    }
+    patterns
+}
+
+pub(crate) fn check_hostname_condition(attr_str: String) -> (bool, String) {
+    let patterns = parse_hostname_patterns(&attr_str);
+    let hostname = gethostname::gethostname().to_string_lossy().to_string();
+    (
+        patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern, &hostname)),
+        format!(
+            "because the hostname {} is not one of {}",
+            hostname,
+            patterns.join(", ")
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildcard_match;
+
+    #[test]
+    fn match_hostname_patterns() {
+        //* Given
+        let patterns = [
+            "gpu-01", "GPU-*", "*-01", "g*-*1", "gpu-0", "cpu-*", "gpu-01-*",
+        ];
+
+        //* When
+        let matched: Vec<_> = patterns
+            .iter()
+            .map(|p| wildcard_match(p, "gpu-01"))
+            .collect();
+
+        //* Then
+        assert_eq!(matched, vec![true, true, true, true, false, false, false]);
+    }
+}
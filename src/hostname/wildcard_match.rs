@@ -0,0 +1,28 @@
+// The matcher is also emitted by `test_with::runner!` for `#[test_with::runtime_hostname]`, so it
+// is written once here, and included by `hostname.rs` as the source and as the tokens.
+
+/// Match the hostname with the pattern case-insensitively, and `*` in the pattern matches any
+/// characters, ex: `gpu-*` matches `gpu-01`.
+pub(crate) fn wildcard_match(pattern: &str, hostname: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let hostname = hostname.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if parts.len() == 1 {
+        return pattern == hostname;
+    }
+    if hostname.len() < first.len() + last.len()
+        || !hostname.starts_with(first)
+        || !hostname.ends_with(last)
+    {
+        return false;
+    }
+    let mut rest = &hostname[first.len()..hostname.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
@@ -0,0 +1,85 @@
+use proc_macro_error2::abort_call_site;
+
+#[cfg(any(target_os = "linux", test))]
+use crate::toolchain::compare_versions;
+use crate::toolchain::{parse_version, COMPARATORS};
+
+/// Parse the requirement of the kernel version, ex: `>= 5.10`, into the comparator, the version and
+/// the version text for the ignore message.  The comparator is `>=` if it is absent.
+pub(crate) fn parse_kernel_requirement(attr_str: &str) -> (&'static str, (u64, u64, u64), String) {
+    let attr_str = attr_str.trim();
+    let comparator = COMPARATORS
+        .into_iter()
+        .find(|c| attr_str.starts_with(c))
+        .unwrap_or(">=");
+    let version_str = attr_str
+        .strip_prefix(comparator)
+        .unwrap_or(attr_str)
+        .trim()
+        .trim_matches('"');
+    match parse_version(version_str) {
+        Some(version) => (comparator, version, version_str.to_string()),
+        None => abort_call_site!("the kernel version should be like `>= 5.10`"),
+    }
+}
+
+/// Parse the kernel release, ex: `6.1.0-18-amd64`, the parts after the patch version, ex: the fourth
+/// part of `5.15.146.1-microsoft-standard-WSL2`, are ignored.
+#[cfg(any(target_os = "linux", test))]
+pub(crate) fn parse_kernel_version(kernel: &str) -> Option<(u64, u64, u64)> {
+    let release = kernel.split(['-', '+']).next()?;
+    parse_version(&release.split('.').take(3).collect::<Vec<_>>().join("."))
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn check_kernel_condition(attr_str: String) -> (bool, String) {
+    let (comparator, required, version_str) = parse_kernel_requirement(&attr_str);
+    let kernel = sysinfo::System::kernel_version().unwrap_or_default();
+    match parse_kernel_version(&kernel) {
+        Some(version) if compare_versions(version, comparator, required) => (true, String::new()),
+        Some(_) => (
+            false,
+            format!(
+                "because the kernel version {} is not {} {}",
+                kernel, comparator, version_str
+            ),
+        ),
+        None => (false, "because the kernel version is unknown".to_string()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_kernel_condition(attr_str: String) -> (bool, String) {
+    parse_kernel_requirement(&attr_str);
+    (
+        false,
+        "because the kernel version is only checked on Linux".into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_versions, parse_kernel_requirement, parse_kernel_version};
+
+    #[test]
+    fn compare_kernel_versions() {
+        //* Given
+        let requirements = [">=5.10", "<5.10", "==6.1.0", "4.19"];
+
+        //* When
+        let matched: Vec<_> = requirements
+            .iter()
+            .map(|requirement| {
+                let (comparator, required, _) = parse_kernel_requirement(requirement);
+                compare_versions((6, 1, 0), comparator, required)
+            })
+            .collect();
+
+        //* Then
+        assert_eq!(matched, vec![true, false, true, true]);
+        assert_eq!(
+            parse_kernel_version("5.15.146.1-microsoft-standard-WSL2"),
+            Some((5, 15, 146))
+        );
+    }
+}
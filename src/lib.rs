@@ -1,8 +1,17 @@
-//! `test_with` provides [macro@env], [macro@file], [macro@path], [macro@http], [macro@https],
-//! [macro@icmp], [macro@tcp], [macro@root], [macro@group], [macro@user], [macro@mem], [macro@swap],
-//! [macro@cpu_core], [macro@phy_core], [macro@executable], [macro@timezone] macros to help you run
-//! test case only with the condition is fulfilled.  If the `#[test]` is absent for the test case,
-//! `#[test_with]` will add it to the test case automatically.
+//! `test_with` provides [macro@env], [macro@env_true], [macro@file], [macro@path], [macro@dir],
+//! [macro@dir_not_empty], [macro@http], [macro@https], [macro@icmp], [macro@tcp],
+//! [macro@listening], [macro@root], [macro@effective_root], [macro@capability], [macro@group],
+//! [macro@user], [macro@mem], [macro@free_mem], [macro@available_mem], [macro@swap],
+//! [macro@max_swap], [macro@free_swap_percent], [macro@cpu_core], [macro@phy_core], [macro@smt],
+//! [macro@parallelism], [macro@min_open_files], [macro@kernel], [macro@min_entropy],
+//! [macro@display], [macro@monitors], [macro@systemd_active], [macro@command],
+//! [macro@command_output], [macro@library], [macro@executable], [macro@file_version],
+//! [macro@hostname], [macro@timezone], [macro@env_file], [macro@config], [macro@dst],
+//! [macro@no_dst], [macro@skip_if_ci], [macro@min_rust], [macro@target_family], [macro@not_wasm],
+//! [macro@release], [macro@debug], [macro@all], [macro@any], [macro@when], [macro@unless] macros to
+//! help you run test case only with the condition is fulfilled.  If the `#[test]` is absent for the
+//! test case, `#[test_with]` will add it to the test case automatically, and warn if the function
+//! name does not look like a test case, which can be confirmed with `allow_non_test`.
 //!
 //! This crate help you easier make integrating test case and has a good cargo summary on CI server,
 //! and will not affect on your binary output when you dependent it as dev-dependency as following.
@@ -23,13 +32,28 @@
 //! The `test-with` need be included as normal dependency with `runtime` feature.
 //! And also include the `libtest-with` with corresponding features in `Cargo.toml`
 //! [macro@runner] and [macro@module] are for the basic skeleton of the test runner.
-//! [macro@runtime_env], [macro@runtime_no_env], [macro@runtime_file], [macro@runtime_path],
-//! [macro@runtime_http], [macro@runtime_https], [macro@runtime_icmp], [macro@runtime_tcp],
-//! [macro@runtime_root], [macro@runtime_group], [macro@runtime_user], [macro@runtime_mem],
-//! [macro@runtime_free_mem], [macro@runtime_available_mem], [macro@runtime_swap],
-//! [macro@runtime_free_swap], [macro@runtime_available_swap], [macro@runtime_cpu_core],
-//! [macro@runtime_phy_core], [macro@runtime_executable], [macro@runtime_timezone]
-//! and [macro@runtime_ignore_if] are used to transform a normal function to a testcase.
+//! [macro@runtime_env], [macro@runtime_env_true], [macro@runtime_no_env], [macro@runtime_file],
+//! [macro@runtime_path], [macro@runtime_dir], [macro@runtime_dir_not_empty],
+//! [macro@runtime_mounted], [macro@runtime_http], [macro@runtime_https], [macro@runtime_no_http],
+//! [macro@runtime_icmp], [macro@runtime_tcp], [macro@runtime_no_tcp], [macro@runtime_listening],
+//! [macro@runtime_root], [macro@runtime_effective_root], [macro@runtime_capability],
+//! [macro@runtime_group], [macro@runtime_user], [macro@runtime_mem], [macro@runtime_free_mem],
+//! [macro@runtime_available_mem], [macro@runtime_swap], [macro@runtime_max_swap],
+//! [macro@runtime_free_swap], [macro@runtime_available_swap], [macro@runtime_free_swap_percent],
+//! [macro@runtime_cpu_core], [macro@runtime_phy_core], [macro@runtime_smt],
+//! [macro@runtime_parallelism], [macro@runtime_min_open_files], [macro@runtime_kernel],
+//! [macro@runtime_min_entropy], [macro@runtime_display], [macro@runtime_monitors],
+//! [macro@runtime_systemd_active], [macro@runtime_command], [macro@runtime_command_output],
+//! [macro@runtime_library], [macro@runtime_executable], [macro@runtime_file_version],
+//! [macro@runtime_hostname], [macro@runtime_timezone], [macro@runtime_env_file],
+//! [macro@runtime_config], [macro@runtime_dst], [macro@runtime_no_dst], [macro@runtime_skip_if_ci],
+//! [macro@runtime_min_rust], [macro@runtime_release], [macro@runtime_debug],
+//! [macro@runtime_under_debugger], [macro@runtime_no_debugger], [macro@runtime_feature],
+//! [macro@runtime_retry], [macro@runtime_should_panic], [macro@runtime_lock],
+//! [macro@runtime_ignore_if], [macro@runtime_custom], [macro@runtime_all], [macro@runtime_any],
+//! [macro@runtime_when] and [macro@runtime_unless] are used to transform a normal function to a
+//! testcase, [macro@tag] selects the testcases with `--tag`, and [macro@assert_ignored] asserts the
+//! testcase is ignored.
 //!
 //! ```toml
 //! [dependencies]
@@ -53,7 +77,7 @@ use std::{fs::metadata, path::Path};
 
 #[cfg(feature = "icmp")]
 use std::net::IpAddr;
-use std::net::TcpStream;
+use std::net::{TcpStream, ToSocketAddrs};
 
 use proc_macro::TokenStream;
 use proc_macro_error2::abort_call_site;
@@ -66,8 +90,91 @@ use syn::{Item, ItemStruct, ItemType};
 #[cfg(feature = "executable")]
 use which::which;
 
-use crate::utils::{fn_macro, is_module, lock_macro, mod_macro, sanitize_env_vars_attr};
-
+#[cfg(all(feature = "runtime", feature = "http"))]
+use crate::utils::http_check_tokens;
+#[cfg(feature = "http")]
+use crate::utils::{
+    http_client, http_ignore_msg, probe_http_links, split_max_ms_option, split_tls_options,
+};
+
+#[cfg(all(feature = "runtime", feature = "capability", target_os = "linux"))]
+use crate::capability::capability_index;
+#[cfg(feature = "capability")]
+use crate::capability::check_capability_condition;
+use crate::condition::expect_panic_if_macro;
+#[cfg(feature = "config")]
+use crate::config::check_config_condition;
+#[cfg(all(feature = "runtime", feature = "config"))]
+use crate::config::split_config_attr;
+#[cfg(all(feature = "runtime", feature = "executable"))]
+use crate::executable::executable_check_tokens;
+#[cfg(feature = "runtime")]
+use crate::feature::{feature_check_tokens, parse_feature_names};
+#[cfg(feature = "hostname")]
+use crate::hostname::check_hostname_condition;
+#[cfg(all(feature = "runtime", feature = "hostname"))]
+use crate::hostname::parse_hostname_patterns;
+#[cfg(feature = "resource")]
+use crate::kernel::check_kernel_condition;
+#[cfg(all(feature = "runtime", feature = "resource"))]
+use crate::kernel::parse_kernel_requirement;
+use crate::profile::{debug_macro, release_macro};
+use crate::target::{not_wasm_macro, target_family_macro};
+use crate::toolchain::check_min_rust_condition;
+#[cfg(feature = "resource")]
+use crate::ulimit::check_ulimit_condition;
+#[cfg(all(feature = "runtime", feature = "resource"))]
+use crate::ulimit::parse_open_files_limitation;
+#[cfg(any(feature = "runtime", feature = "icmp"))]
+use crate::utils::IpFamily;
+#[cfg(feature = "runtime")]
+use crate::utils::{
+    attr_to_string, cached_probe_tokens, env_attr_to_string, retry_probe_tokens, split_cache_option,
+};
+use crate::utils::{
+    env_var_exists, env_var_group, env_var_group_exists, env_var_nonempty, env_var_value,
+    expand_port_range, fn_macro, is_module, lock_macro, missing_env_var_msg, mod_macro,
+    retry_probe, sanitize_env_vars_attr, serial_group_macro, serial_groups_tokens,
+    split_at_least_option, split_attr_list, split_env_value_set, split_family_option,
+    split_nonempty_option, split_retries_option, throttle_macro, throttles_tokens, with_env_macro,
+};
+
+#[cfg(feature = "capability")]
+mod capability;
+#[cfg(feature = "resource")]
+mod cgroup;
+mod combinator;
+mod command;
+mod condition;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "runtime")]
+mod custom;
+#[cfg(feature = "runtime")]
+mod debugger;
+mod display;
+#[cfg(feature = "resource")]
+mod entropy;
+#[cfg(all(feature = "runtime", feature = "executable"))]
+mod executable;
+#[cfg(feature = "runtime")]
+mod feature;
+mod file_version;
+#[cfg(feature = "hostname")]
+mod hostname;
+#[cfg(feature = "resource")]
+mod kernel;
+#[cfg(feature = "library")]
+mod library;
+mod monitors;
+mod mount;
+mod profile;
+mod socket;
+mod systemd;
+mod target;
+mod toolchain;
+#[cfg(feature = "resource")]
+mod ulimit;
 mod utils;
 
 /// Run test case when the environment variable is set.
@@ -95,6 +202,48 @@ mod utils;
 ///     fn test_ignored_too() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // Any variable starting with `CARGO_` exists
+///     #[test_with::env(CARGO_*)]
+///     #[test]
+///     fn test_works_with_prefix() {
+///         assert!(true);
+///     }
+///
+///     // NOT_SAYING does not exist, but its alias PWD exists
+///     #[test_with::env(NOT_SAYING | PWD)]
+///     #[test]
+///     fn test_works_with_alias() {
+///         assert!(true);
+///     }
+///
+///     // Neither of the grouped variables exists, a partially set group is a compile error
+///     #[test_with::env(group(NOT_SAYING_KEY, NOT_SAYING_SECRET))]
+///     #[test]
+///     fn test_ignored_with_group() {
+///         panic!("should be ignored")
+///     }
+///
+///     // PWD and HOME exist, NOT_SAYING does not exist
+///     #[test_with::env(PWD, HOME, NOT_SAYING, at_least=2)]
+///     #[test]
+///     fn test_works_with_enough_vars() {
+///         assert!(true);
+///     }
+///
+///     // PWD exists and is not empty
+///     #[test_with::env(PWD, nonempty)]
+///     #[test]
+///     fn test_works_with_nonempty_var() {
+///         assert!(true);
+///     }
+///
+///     // NOT_SAYING does not exist, so it is not one of the allowed values
+///     #[test_with::env(NOT_SAYING in [staging, prod])]
+///     #[test]
+///     fn test_ignored_with_value_set() {
+///         panic!("should be ignored")
+///     }
 /// }
 /// ```
 /// or run all test cases for test module when the environment variable is set.
@@ -128,29 +277,88 @@ pub fn env(attr: TokenStream, stream: TokenStream) -> TokenStream {
 }
 
 fn check_env_condition(attr_str: String) -> (bool, String) {
-    let var_names = sanitize_env_vars_attr(&attr_str);
+    let (attr_str, nonempty) = split_nonempty_option(&attr_str);
+    let (var_names, at_least) = split_at_least_option(&attr_str);
 
-    // Check if the environment variables are set
+    // Check if the environment variables are set, not empty with `nonempty`, and in the allowed
+    // values with `in [..]`
     let mut missing_vars = vec![];
-    for name in var_names {
-        if std::env::var(name).is_err() {
+    let mut empty_vars = vec![];
+    let mut unmatched_vars = vec![];
+    let (var_names, value_sets): (Vec<&str>, Vec<_>) =
+        var_names.into_iter().map(split_env_value_set).unzip();
+    for (name, value_set) in var_names.iter().zip(value_sets) {
+        let (exists, members) = match env_var_group(name) {
+            Some(members) => match env_var_group_exists(&members) {
+                Ok(exists) => (exists, members),
+                Err(msg) => abort_call_site!("{}", msg),
+            },
+            None => (env_var_exists(name), vec![*name]),
+        };
+        if !exists {
             missing_vars.push(name.to_string());
+        } else if nonempty && !members.iter().all(|member| env_var_nonempty(member)) {
+            empty_vars.push(name.to_string());
+        } else if let Some(values) = value_set {
+            let value = env_var_value(name);
+            if !values.contains(&value) {
+                unmatched_vars.push(format!(
+                    "{} is {}, not in [{}]",
+                    name,
+                    value,
+                    values.join(", ")
+                ));
+            }
         }
     }
 
+    if let Some(required) = at_least {
+        let found = var_names.len() - missing_vars.len() - empty_vars.len() - unmatched_vars.len();
+        return if found >= required {
+            (true, String::new())
+        } else {
+            (
+                false,
+                format!(
+                    "because only {} of following variables found, at least {} required:\n{}\n",
+                    found,
+                    required,
+                    var_names.join(", ")
+                ),
+            )
+        };
+    }
+
     // Generate ignore message
-    let ignore_msg = if missing_vars.is_empty() {
-        String::new()
-    } else if missing_vars.len() == 1 {
-        format!("because variable {} not found", missing_vars[0])
-    } else {
+    let ignore_msg = if missing_vars.len() == 1 {
+        missing_env_var_msg(&missing_vars[0])
+    } else if !missing_vars.is_empty() {
         format!(
             "because following variables not found:\n{}\n",
             missing_vars.join(", ")
         )
+    } else if empty_vars.len() == 1 {
+        format!("because variable {} is empty", empty_vars[0])
+    } else if !empty_vars.is_empty() {
+        format!(
+            "because following variables are empty:\n{}\n",
+            empty_vars.join(", ")
+        )
+    } else if unmatched_vars.len() == 1 {
+        format!("because variable {}", unmatched_vars[0])
+    } else if !unmatched_vars.is_empty() {
+        format!(
+            "because following variables are not in the allowed values:\n{}\n",
+            unmatched_vars.join("\n")
+        )
+    } else {
+        String::new()
     };
 
-    (missing_vars.is_empty(), ignore_msg)
+    (
+        missing_vars.is_empty() && empty_vars.is_empty() && unmatched_vars.is_empty(),
+        ignore_msg,
+    )
 }
 
 /// Run test case when the example running and the environment variable is set.
@@ -175,8 +383,10 @@ pub fn runtime_env(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn runtime_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let var_names: Vec<&str> = attr_str.split(',').collect();
+    let (attr_str, nonempty) = split_nonempty_option(&env_attr_to_string(attr));
+    let (var_names, at_least) = split_at_least_option(&attr_str);
+    let (var_names, value_sets): (Vec<&str>, Vec<_>) =
+        var_names.into_iter().map(split_env_value_set).unzip();
     let ItemFn {
         attrs,
         vis,
@@ -188,28 +398,156 @@ pub fn runtime_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
         &format!("_check_{}", ident.to_string()),
         proc_macro2::Span::call_site(),
     );
-    quote::quote! {
-        fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let mut missing_vars = vec![];
-            #(
-                if std::env::var(#var_names).is_err() {
-                    missing_vars.push(#var_names);
+    let missing_msgs: Vec<String> = var_names.iter().map(|n| missing_env_var_msg(n)).collect();
+    // a partially set group fails the test instead of ignoring it
+    let var_checks: Vec<proc_macro2::TokenStream> = var_names
+        .iter()
+        .map(|name| match env_var_group(name) {
+            Some(members) => {
+                let group = members.join(", ");
+                quote::quote! {{
+                    let unset: Vec<&str> = [#(#members),*]
+                        .into_iter()
+                        .filter(|name| !env_var_exists(name))
+                        .collect();
+                    match unset.len() {
+                        0 => true,
+                        n if n == [#(#members),*].len() => false,
+                        _ => {
+                            return Err(format!(
+                                "the variables of group({}) should be all set or all unset, but {} not set",
+                                #group,
+                                unset.join(", ")
+                            )
+                            .into())
+                        }
+                    }
+                }}
+            }
+            None => quote::quote! { env_var_exists(#name) },
+        })
+        .collect();
+    // with `nonempty`, the set variable with the empty value is also reported
+    let empty_checks: Vec<proc_macro2::TokenStream> = var_names
+        .iter()
+        .map(|name| {
+            if !nonempty {
+                return quote::quote! {};
+            }
+            let members = env_var_group(name).unwrap_or_else(|| vec![*name]);
+            quote::quote! {
+                else if ![#(#members),*].into_iter().all(|name| env_var_nonempty(name)) {
+                    empty_vars.push(#name);
                 }
-            )*
-            match missing_vars.len() {
-                0 => {
+            }
+        })
+        .collect();
+    // with `in [..]`, the value of the set variable should be one of the allowed values
+    let value_checks: Vec<proc_macro2::TokenStream> = var_names
+        .iter()
+        .zip(value_sets)
+        .map(|(name, value_set)| {
+            let Some(values) = value_set else {
+                return quote::quote! {};
+            };
+            let allowed = values.join(", ");
+            quote::quote! {
+                else if ![#(#values),*].contains(&env_var_value(#name).as_str()) {
+                    unmatched_vars.push(format!(
+                        "{} is {}, not in [{}]",
+                        #name,
+                        env_var_value(#name),
+                        #allowed
+                    ));
+                }
+            }
+        })
+        .collect();
+    let check_missing_vars = match at_least {
+        Some(required) => {
+            let var_count = var_names.len();
+            let all_vars = var_names.join(", ");
+            quote::quote! {
+                let found = #var_count - missing_vars.len() - empty_vars.len() - unmatched_vars.len();
+                if found >= #required {
+                    #ident();
+                    Ok(())
+                } else {
+                    Err(_ignore_reason(format_args!(
+                        "because only {} of following variables found, at least {} required:\n{}\n",
+                        found, #required, #all_vars
+                    )))
+                }
+            }
+        }
+        None => quote::quote! {
+            match (missing_vars.len(), empty_vars.len(), unmatched_vars.len()) {
+                (0, 0, 0) => {
                     #ident();
                     Ok(())
                 },
-                1 => Err(
-                    format!("{}because variable {} not found",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_vars[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following variables not found:\n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_vars.join(", ")
-                ).into()),
+                (1, _, _) => Err(_ignore_reason(format_args!("{}", missing_vars[0].1))),
+                (0, 1, _) => Err(_ignore_reason(format_args!(
+                    "because variable {} is empty",
+                    empty_vars[0]
+                ))),
+                (0, 0, 1) => Err(_ignore_reason(format_args!(
+                    "because variable {}",
+                    unmatched_vars[0]
+                ))),
+                (0, 0, _) => Err(_ignore_reason(format_args!(
+                    "because following variables are not in the allowed values:\n{}\n",
+                    unmatched_vars.join("\n")
+                ))),
+                (0, _, _) => Err(_ignore_reason(format_args!(
+                    "because following variables are empty:\n{}\n",
+                    empty_vars.join(", ")
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following variables not found:\n{}\n",
+                    missing_vars.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+                ))),
             }
+        },
+    };
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let mut missing_vars = vec![];
+            #[allow(unused_mut)]
+            let mut empty_vars: Vec<&str> = vec![];
+            #[allow(unused_mut)]
+            let mut unmatched_vars: Vec<String> = vec![];
+            let env_var_exists = |name: &str| {
+                name.split('|').map(str::trim).any(|name| match name.strip_suffix('*') {
+                    Some(prefix) => std::env::vars_os()
+                        .any(|(key, _)| key.to_string_lossy().starts_with(prefix)),
+                    None => std::env::var(name).is_ok(),
+                })
+            };
+            #[allow(unused_variables)]
+            let env_var_nonempty = |name: &str| {
+                name.split('|').map(str::trim).any(|name| match name.strip_suffix('*') {
+                    Some(prefix) => std::env::vars_os().any(|(key, value)| {
+                        key.to_string_lossy().starts_with(prefix) && !value.is_empty()
+                    }),
+                    None => std::env::var_os(name).is_some_and(|value| !value.is_empty()),
+                })
+            };
+            #[allow(unused_variables)]
+            let env_var_value = |name: &str| {
+                name.split('|')
+                    .map(str::trim)
+                    .find_map(std::env::var_os)
+                    .map(|value| value.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            };
+            #(
+                let var_exists = #var_checks;
+                if !var_exists {
+                    missing_vars.push((#var_names, #missing_msgs));
+                } #empty_checks #value_checks
+            )*
+            #check_missing_vars
         }
 
         #(#attrs)*
@@ -297,7 +635,7 @@ pub fn runtime_no_env(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
 #[proc_macro_error]
 pub fn runtime_no_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
     let attr_str = attr.to_string().replace(' ', "");
-    let var_names: Vec<&str> = attr_str.split(',').collect();
+    let var_names = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -322,14 +660,14 @@ pub fn runtime_no_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
                     #ident();
                     Ok(())
                 },
-                1 => Err(
-                    format!("{}because variable {} found",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, should_no_exist_vars[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following variables found:\n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, should_no_exist_vars.join(", ")
-                ).into()),
+                1 => Err(_ignore_reason(format_args!(
+                    "because variable {} found",
+                    should_no_exist_vars[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following variables found:\n{}\n",
+                    should_no_exist_vars.join(", ")
+                ))),
             }
         }
 
@@ -339,94 +677,90 @@ pub fn runtime_no_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when the file exist.
+/// Run test case when the environment variables are set as the opt-in toggles, ex: `RUN_SLOW=1`.
+/// The values `1`, `true`, `yes` and `on` are case-insensitive and enable the test case, and any
+/// other value, ex: `0` or `false`, or the unset variable disables it.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // hostname exists
-///     #[test_with::file(/etc/hostname)]
-///     #[test]
-///     fn test_works() {
-///         assert!(true);
-///     }
-///
-///     // nothing file does not exist
-///     #[test_with::file(/etc/nothing)]
-///     #[test]
-///     fn test_ignored() {
-///         panic!("should be ignored")
-///     }
-///
-///     // hostname and hosts exist
-///     #[test_with::file(/etc/hostname, /etc/hosts)]
+///     // Only works with `RUN_SLOW=1` or `RUN_SLOW=true`
+///     #[test_with::env_true(RUN_SLOW)]
 ///     #[test]
-///     fn test_works_too() {
+///     fn test_slow() {
 ///         assert!(true);
 ///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn file(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn env_true(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_file_condition,
+            check_env_true_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_file_condition,
+            check_env_true_condition,
         )
     }
 }
 
-fn check_file_condition(attr_str: String) -> (bool, String) {
-    let files: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_files = vec![];
-    for file in files.iter() {
-        if !Path::new(file.trim_matches('"')).is_file() {
-            missing_files.push(file.to_string());
-        }
-    }
-    let ignore_msg = if missing_files.len() == 1 {
-        format!("because file not found: {}", missing_files[0])
-    } else {
-        format!(
-            "because following files not found: \n{}\n",
-            missing_files.join("\n")
-        )
+/// The values of the environment variable enabling the opt-in toggle, which are case-insensitive
+const TRUE_ENV_VALUES: [&str; 4] = ["1", "true", "yes", "on"];
+
+fn is_true_env_value(value: &str) -> bool {
+    TRUE_ENV_VALUES
+        .iter()
+        .any(|v| value.trim().eq_ignore_ascii_case(v))
+}
+
+fn check_env_true_condition(attr_str: String) -> (bool, String) {
+    let disabled_vars: Vec<&str> = sanitize_env_vars_attr(&attr_str)
+        .filter(|name| !std::env::var(name).is_ok_and(|value| is_true_env_value(&value)))
+        .collect();
+    let ignore_msg = match disabled_vars.len() {
+        0 => String::new(),
+        1 => format!("because variable {} is not true", disabled_vars[0]),
+        _ => format!(
+            "because following variables are not true:\n{}\n",
+            disabled_vars.join(", ")
+        ),
     };
-    (missing_files.is_empty(), ignore_msg)
+    (disabled_vars.is_empty(), ignore_msg)
 }
 
-/// Run test case when the example running and the file exist.
+/// Run test case when the example running and the environment variables are set as the opt-in
+/// toggles, ex: `RUN_SLOW=1`.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(file);
+/// test_with::runner!(env);
 /// #[test_with::module]
-/// mod file {
-///     #[test_with::runtime_file(/etc/hostname)]
-///     fn test_works() {
-///         assert!(true);
+/// mod env {
+/// #[test_with::runtime_env_true(RUN_SLOW)]
+/// fn test_slow() {
+///     assert!(true);
 ///     }
 /// }
 ///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_file(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_env_true(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
 #[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let files: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_env_true(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let var_names = split_attr_list(&attr_str);
+    let true_values = TRUE_ENV_VALUES.iter();
+    let true_values = quote::quote! { [#(#true_values),*] };
     let ItemFn {
         attrs,
         vis,
@@ -434,32 +768,33 @@ pub fn runtime_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let mut missing_files = vec![];
+            let mut disabled_vars = vec![];
             #(
-                if !std::path::Path::new(#files.trim_matches('"')).is_file() {
-                    missing_files.push(#files);
+                let enabled = std::env::var(#var_names).is_ok_and(|value| {
+                    #true_values
+                        .iter()
+                        .any(|v| value.trim().eq_ignore_ascii_case(v))
+                });
+                if !enabled {
+                    disabled_vars.push(#var_names);
                 }
             )*
-
-            match missing_files.len() {
+            match disabled_vars.len() {
                 0 => {
                     #ident();
                     Ok(())
                 },
-                1 => Err(
-                    format!("{}because file not found: {}",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_files[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following files not found: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_files.join(", ")
-                ).into()),
+                1 => Err(_ignore_reason(format_args!(
+                    "because variable {} is not true",
+                    disabled_vars[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following variables are not true:\n{}\n",
+                    disabled_vars.join(", ")
+                ))),
             }
         }
 
@@ -469,94 +804,113 @@ pub fn runtime_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when the path(file or folder) exist.
+/// Ignore test case when it is running in CI.
+/// The common CI markers, ex: `CI`, `GITHUB_ACTIONS`, `GITLAB_CI`, `JENKINS_URL`..., are checked,
+/// and the test case is ignored when any of them is set with a value other than `false` or `0`.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // etc exists
-///     #[test_with::path(/etc)]
+///     // The test will be ignored in CI
+///     #[test_with::skip_if_ci]
 ///     #[test]
-///     fn test_works() {
+///     fn test_ignored_in_ci() {
 ///         assert!(true);
 ///     }
 ///
-///     // nothing does not exist
-///     #[test_with::path(/nothing)]
-///     #[test]
-///     fn test_ignored() {
-///         panic!("should be ignored")
-///     }
-///
-///     // etc and tmp exist
-///     #[test_with::path(/etc, /tmp)]
-///     #[test]
-///     fn test_works_too() {
-///         assert!(true);
+///     // The whole module will be ignored in CI
+///     #[test_with::skip_if_ci]
+///     mod local {
+///         #[test]
+///         fn test_works_locally() {
+///             assert!(true);
+///         }
 ///     }
 /// }
-/// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn path(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn skip_if_ci(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_path_condition,
+            check_skip_if_ci_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_path_condition,
+            check_skip_if_ci_condition,
         )
     }
 }
 
-fn check_path_condition(attr_str: String) -> (bool, String) {
-    let paths: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_paths = vec![];
-    for path in paths.iter() {
-        if metadata(path.trim_matches('"')).is_err() {
-            missing_paths.push(path.to_string());
-        }
+/// The environment variables set by the common CI providers
+const CI_ENV_VARS: [&str; 14] = [
+    "CI",
+    "CONTINUOUS_INTEGRATION",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "TRAVIS",
+    "CIRCLECI",
+    "BUILDKITE",
+    "JENKINS_URL",
+    "TEAMCITY_VERSION",
+    "TF_BUILD",
+    "APPVEYOR",
+    "DRONE",
+    "BITBUCKET_BUILD_NUMBER",
+    "CODEBUILD_BUILD_ID",
+];
+
+/// Find the first CI marker set with a truthy value
+fn ci_env_var<F: Fn(&str) -> Option<String>>(lookup: F) -> Option<&'static str> {
+    CI_ENV_VARS.into_iter().find(|name| {
+        lookup(name).is_some_and(|value| {
+            let value = value.trim();
+            !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false")
+        })
+    })
+}
+
+fn check_skip_if_ci_condition(_attr_str: String) -> (bool, String) {
+    match ci_env_var(|name| std::env::var(name).ok()) {
+        Some(_) => (false, "because running in CI".to_string()),
+        None => (true, String::new()),
     }
-    let ignore_msg = if missing_paths.len() == 1 {
-        format!("because path not found: {}", missing_paths[0])
-    } else {
-        format!(
-            "because following paths not found: \n{}\n",
-            missing_paths.join("\n")
-        )
-    };
-    (missing_paths.is_empty(), ignore_msg)
 }
 
-/// Run test case when the example running and the path(file or folder) exist.
+/// The sub-condition `ci` of the combinators, ex: `#[test_with::unless(ci)]`, which is met when
+/// running in CI
+fn check_ci_condition(_attr_str: String) -> (bool, String) {
+    match ci_env_var(|name| std::env::var(name).ok()) {
+        Some(_) => (true, String::new()),
+        None => (false, "because not running in CI".to_string()),
+    }
+}
+
+/// Ignore test case when the example running in CI.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(path);
+/// test_with::runner!(env);
 /// #[test_with::module]
-/// mod path {
-///     #[test_with::runtime_path(/etc)]
-///     fn test_works() {
-///         assert!(true);
+/// mod env {
+/// #[test_with::runtime_skip_if_ci]
+/// fn test_works() {
+///     assert!(true);
 ///     }
 /// }
 ///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_path(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_skip_if_ci(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
 #[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_path(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let paths: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_skip_if_ci(_attr: TokenStream, stream: TokenStream) -> TokenStream {
     let ItemFn {
         attrs,
         vis,
@@ -564,32 +918,21 @@ pub fn runtime_path(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let ci_env_vars = CI_ENV_VARS;
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let mut missing_paths = vec![];
-            #(
-                if std::fs::metadata(#paths.trim_matches('"')).is_err() {
-                    missing_paths.push(#paths.to_string());
-                }
-            )*
-
-            match missing_paths.len() {
-                0 => {
-                    #ident();
-                    Ok(())
-                },
-                1 => Err(
-                    format!("{}because path not found: {}",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_paths[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following paths not found: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_paths.join(", ")
-                ).into()),
+            let in_ci = [#(#ci_env_vars),*].iter().any(|name| {
+                std::env::var(name).is_ok_and(|value| {
+                    let value = value.trim();
+                    !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false")
+                })
+            });
+            if in_ci {
+                Err(_ignore_reason(format_args!("because running in CI")))
+            } else {
+                #ident();
+                Ok(())
             }
         }
 
@@ -599,20 +942,23 @@ pub fn runtime_path(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when the http service exist.
+/// Run test case when the Rust compiler is not older than the version.
+/// The version of the compiler compiling the test cases is captured by the build script of this
+/// crate with `rustc --version`, because the proc macros and the test cases are compiled by the
+/// same compiler.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // http service exists
-///     #[test_with::http(httpbin.org)]
+///     // The compiler is newer than 1.0
+///     #[test_with::min_rust(1.0)]
 ///     #[test]
 ///     fn test_works() {
 ///         assert!(true);
 ///     }
 ///
-///     // There is no not.exist.com
-///     #[test_with::http(not.exist.com)]
+///     // The compiler is older than 999.0
+///     #[test_with::min_rust(999.0)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
@@ -621,68 +967,96 @@ pub fn runtime_path(attr: TokenStream, stream: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "http")]
-pub fn http(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn min_rust(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_http_condition,
+            check_min_rust_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_http_condition,
+            check_min_rust_condition,
         )
     }
 }
 
-#[cfg(feature = "http")]
-fn check_http_condition(attr_str: String) -> (bool, String) {
-    let links: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_links = vec![];
-    let client = reqwest::blocking::Client::new();
-    for link in links.iter() {
-        if client.head(&format!("http://{}", link)).send().is_err() {
-            missing_links.push(format!("http://{link:}"));
-        }
-    }
-    let ignore_msg = if missing_links.len() == 1 {
-        format!("because {} not response", missing_links[0])
-    } else {
-        format!(
-            "because following links not response: \n{}\n",
-            missing_links.join("\n")
-        )
-    };
-    (missing_links.is_empty(), ignore_msg)
+/// Run test case when the example running and the Rust compiler is not older than the version.
+/// The version of the compiler is captured when compiling the example, the same as [macro@min_rust].
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(toolchain);
+/// #[test_with::module]
+/// mod toolchain {
+/// #[test_with::runtime_min_rust(1.0)]
+/// fn test_works() {
+///     assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_rust(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
 }
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_rust(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let (version_met, ignore_msg) = check_min_rust_condition(attr.to_string().replace(' ', ""));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if #version_met {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("{}", #ignore_msg)))
+            }
+        }
 
-/// Run test case when the example running and the http service exist.
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the example running and the crate building the example enables the features.
+/// The features of the downstream crate can not be read by the proc macro, so the `cfg!` is
+/// expanded in the example and checked there, and the feature names should exist in the
+/// `[features]` of the crate building the example, or the `unexpected_cfgs` lint will warn.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(http);
+/// test_with::runner!(features);
 /// #[test_with::module]
-/// mod http {
-///     #[test_with::runtime_http(httpbin.org)]
-///     fn test_works() {
-///         assert!(true);
+/// mod features {
+/// #[test_with::runtime_feature("my-feature")]
+/// fn test_works() {
+///     assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_http(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_feature(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-
-#[cfg(all(feature = "runtime", feature = "http"))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let links: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_feature(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let feature_names = parse_feature_names(&attr_to_string(attr));
     let ItemFn {
         attrs,
         vis,
@@ -690,34 +1064,11 @@ pub fn runtime_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let check_tokens = feature_check_tokens(&feature_names, &ident);
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-
-            let mut missing_links = vec![];
-            let client = libtest_with::reqwest::blocking::Client::new();
-            #(
-                if client.head(&format!("http://{}", #links)).send().is_err() {
-                    missing_links.push(format!("http://{}", #links));
-                }
-            )*
-            match missing_links.len() {
-                0 => {
-                    #ident();
-                    Ok(())
-                },
-                1 => Err(
-                    format!("{}because {} not response",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_links[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following links not response: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_links.join(", ")
-                ).into()),
-            }
+            #check_tokens
         }
 
         #(#attrs)*
@@ -726,20 +1077,22 @@ pub fn runtime_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when the https service exist.
+/// Run test case when the dotenv file exists and defines the variables.
+/// The variables only need to be defined in the file, they are not necessary to be set in the
+/// environment of the process.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // https server exists
-///     #[test_with::https(www.rust-lang.org)]
+///     // DB_URL and API_KEY are defined in .env.test
+///     #[test_with::env_file(".env.test": DB_URL, API_KEY)]
 ///     #[test]
 ///     fn test_works() {
 ///         assert!(true);
 ///     }
 ///
-///     // There is no not.exist.com
-///     #[test_with::https(not.exist.com)]
+///     // NOT_DEFINED is not defined in .env.test
+///     #[test_with::env_file(".env.test": NOT_DEFINED)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
@@ -748,68 +1101,109 @@ pub fn runtime_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "http")]
-pub fn https(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn env_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_https_condition,
+            check_env_file_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_https_condition,
+            check_env_file_condition,
         )
     }
 }
 
-#[cfg(feature = "http")]
-fn check_https_condition(attr_str: String) -> (bool, String) {
-    let links: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_links = vec![];
-    let client = reqwest::blocking::Client::new();
-    for link in links.iter() {
-        if client.head(&format!("https://{}", link)).send().is_err() {
-            missing_links.push(format!("https://{link:}"));
+// the variable names defined in the content of dotenv file
+fn dotenv_var_names(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            line.split_once('=').map(|(name, _)| name.trim())
+        })
+        .collect()
+}
+
+fn check_env_file_condition(attr_str: String) -> (bool, String) {
+    let (file, var_names) = match attr_str.rsplit_once(':') {
+        Some((file, var_names)) => (file.trim().trim_matches('"'), var_names),
+        None => abort_call_site!(
+            "`#[test_with::env_file]` should be used as `env_file(\"file\": VAR1, VAR2)`"
+        ),
+    };
+    let content = match std::fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(_) => return (false, format!("because env file {} not found", file)),
+    };
+    let defined_vars = dotenv_var_names(&content);
+
+    // Check if the variables are defined in the file
+    let mut missing_vars = vec![];
+    for name in sanitize_env_vars_attr(var_names) {
+        if !defined_vars.contains(&name) {
+            missing_vars.push(name.to_string());
         }
     }
-    let ignore_msg = if missing_links.len() == 1 {
-        format!("because {} not response", missing_links[0])
+
+    // Generate ignore message
+    let ignore_msg = if missing_vars.is_empty() {
+        String::new()
+    } else if missing_vars.len() == 1 {
+        format!(
+            "because variable {} not defined in {}",
+            missing_vars[0], file
+        )
     } else {
         format!(
-            "because following links not response: \n{}\n",
-            missing_links.join("\n")
+            "because following variables not defined in {}:\n{}\n",
+            file,
+            missing_vars.join(", ")
         )
     };
-    (missing_links.is_empty(), ignore_msg)
+
+    (missing_vars.is_empty(), ignore_msg)
 }
 
-/// Run test case when the example running and the http service exist.
+/// Run test case when the example running and the dotenv file defines the variables.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(http);
+/// test_with::runner!(env);
 /// #[test_with::module]
-/// mod http {
-///     #[test_with::runtime_https(httpbin.org)]
-///     fn test_works() {
-///         assert!(true);
+/// mod env {
+/// #[test_with::runtime_env_file(".env.test": DB_URL, API_KEY)]
+/// fn test_works() {
+///     assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_https(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_env_file(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-
-#[cfg(all(feature = "runtime", feature = "http"))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_https(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn runtime_env_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
     let attr_str = attr.to_string().replace(' ', "");
-    let links: Vec<&str> = attr_str.split(',').collect();
+    let (file, var_names) = match attr_str.rsplit_once(':') {
+        Some((file, var_names)) => (
+            file.trim().trim_matches('"'),
+            sanitize_env_vars_attr(var_names).collect::<Vec<_>>(),
+        ),
+        None => abort_call_site!(
+            "`#[test_with::runtime_env_file]` should be used as `runtime_env_file(\"file\": VAR1, VAR2)`"
+        ),
+    };
     let ItemFn {
         attrs,
         vis,
@@ -817,33 +1211,46 @@ pub fn runtime_https(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-
-            let mut missing_links = vec![];
-            let client = libtest_with::reqwest::blocking::Client::new();
+            let content = match std::fs::read_to_string(#file) {
+                Ok(content) => content,
+                Err(_) => return Err(_ignore_reason(format_args!(
+                    "because env file {} not found",
+                    #file
+                ))),
+            };
+            let defined_vars: Vec<&str> = content
+                .lines()
+                .filter_map(|line| {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        return None;
+                    }
+                    let line = line.strip_prefix("export ").unwrap_or(line);
+                    line.split_once('=').map(|(name, _)| name.trim())
+                })
+                .collect();
+            let mut missing_vars = vec![];
             #(
-                if client.head(&format!("https://{}", #links)).send().is_err() {
-                    missing_links.push(format!("https://{}", #links));
+                if !defined_vars.contains(&#var_names) {
+                    missing_vars.push(#var_names);
                 }
             )*
-            match missing_links.len() {
+            match missing_vars.len() {
                 0 => {
                     #ident();
                     Ok(())
                 },
-                1 => Err(
-                    format!("{}because {} not response",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_links[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following links not response: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_links.join(", ")
-                ).into()),
+                1 => Err(_ignore_reason(format_args!(
+                    "because variable {} not defined in {}",
+                    missing_vars[0], #file
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following variables not defined in {}:\n{}\n",
+                    #file, missing_vars.join(", ")
+                ))),
             }
         }
 
@@ -853,97 +1260,66 @@ pub fn runtime_https(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when the server online.
-/// Please make sure the role of test case runner have capability to open socket
-///
+/// Run test case when the key of the config file is truthy, ex: the test toggles are kept in a
+/// config file rather than the environment.  The file is parsed as JSON if the file name ends with
+/// `.json`, otherwise as TOML, and the dotted key is looked up in the nested tables.  The `false`,
+/// zero, empty string, `"false"`, `"0"`, empty array and empty table are not truthy.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // localhost is online
-///     #[test_with::icmp(127.0.0.1)]
+///     // `enabled = true` under `[integration]` of tests.toml
+///     #[test_with::config("tests.toml": integration.enabled)]
 ///     #[test]
-///     fn test_works() {
+///     fn test_integration() {
 ///         assert!(true);
 ///     }
-///
-///     // 193.194.195.196 is offline
-///     #[test_with::icmp(193.194.195.196)]
-///     #[test]
-///     fn test_ignored() {
-///         panic!("should be ignored")
-///     }
 /// }
 /// ```
+#[cfg(feature = "config")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "icmp")]
-pub fn icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn config(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_icmp_condition,
+            check_config_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_icmp_condition,
+            check_config_condition,
         )
     }
 }
 
-#[cfg(feature = "icmp")]
-fn check_icmp_condition(attr_str: String) -> (bool, String) {
-    let ips: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_ips = vec![];
-    for ip in ips.iter() {
-        if let Ok(addr) = ip.parse::<IpAddr>() {
-            if ping::ping(addr, None, None, None, None, None).is_err() {
-                missing_ips.push(ip.to_string());
-            }
-        } else {
-            abort_call_site!("ip address malformat")
-        }
-    }
-    let ignore_msg = if missing_ips.len() == 1 {
-        format!("because ip {} not response", missing_ips[0])
-    } else {
-        format!(
-            "because following ip not response: \n{}\n",
-            missing_ips.join(", ")
-        )
-    };
-    (missing_ips.is_empty(), ignore_msg)
-}
-
-/// Run test case when the example running and the server online.
-/// Please make sure the role of test case runner have capability to open socket
+/// Run test case when the example running and the key of the config file is truthy.
+/// The config file is parsed when the example running, so the crate of the example should depend
+/// on `toml` for the TOML file, or `serde_json` for the JSON file.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(icmp);
+/// test_with::runner!(config);
 /// #[test_with::module]
-/// mod icmp {
-///     // 193.194.195.196 is offline
-///     #[test_with::runtime_icmp(193.194.195.196)]
-///     fn test_ignored_with_non_existing_host() {
-///         panic!("should be ignored with non existing host")
+/// mod config {
+///     #[test_with::runtime_config("tests.toml": integration.enabled)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_icmp(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_config(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-
-#[cfg(all(feature = "runtime", feature = "icmp"))]
+#[cfg(all(feature = "runtime", feature = "config"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let ips: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_config(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let (file, key) = split_config_attr(&attr_to_string(attr));
     let ItemFn {
         attrs,
         vis,
@@ -951,33 +1327,74 @@ pub fn runtime_icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let lookup = if file.ends_with(".json") {
+        quote::quote! {
+            let config: serde_json::Value = match serde_json::from_str(&content) {
+                Ok(config) => config,
+                Err(e) => return Err(_ignore_reason(format_args!(
+                    "because config file {} can not be parsed: {}",
+                    #file, e
+                ))),
+            };
+            let value = #key
+                .split('.')
+                .try_fold(&config, |value, part| value.as_object()?.get(part));
+            let truthy = value.map(|value| match value {
+                serde_json::Value::Null => false,
+                serde_json::Value::Bool(b) => *b,
+                serde_json::Value::Number(n) => n.as_f64().map(|n| n != 0.0).unwrap_or(true),
+                serde_json::Value::String(s) => !s.is_empty() && s != "false" && s != "0",
+                serde_json::Value::Array(a) => !a.is_empty(),
+                serde_json::Value::Object(o) => !o.is_empty(),
+            });
+        }
+    } else {
+        quote::quote! {
+            let config: toml::Value = match toml::from_str(&content) {
+                Ok(config) => config,
+                Err(e) => return Err(_ignore_reason(format_args!(
+                    "because config file {} can not be parsed: {}",
+                    #file, e
+                ))),
+            };
+            let value = #key
+                .split('.')
+                .try_fold(&config, |value, part| value.as_table()?.get(part));
+            let truthy = value.map(|value| match value {
+                toml::Value::Boolean(b) => *b,
+                toml::Value::Integer(n) => *n != 0,
+                toml::Value::Float(n) => *n != 0.0,
+                toml::Value::String(s) => !s.is_empty() && s != "false" && s != "0",
+                toml::Value::Array(a) => !a.is_empty(),
+                toml::Value::Table(t) => !t.is_empty(),
+                toml::Value::Datetime(_) => true,
+            });
+        }
+    };
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-
-            let mut missing_ips = vec![];
-            #(
-                if libtest_with::ping::ping(#ips.parse().expect("ip address is invalid"), None, None, None, None, None).is_err() {
-                    missing_ips.push(#ips);
-                }
-            )*
-            match missing_ips.len() {
-                0 => {
+            let content = match std::fs::read_to_string(#file) {
+                Ok(content) => content,
+                Err(_) => return Err(_ignore_reason(format_args!(
+                    "because config file {} not found",
+                    #file
+                ))),
+            };
+            #lookup
+            match truthy {
+                Some(true) => {
                     #ident();
                     Ok(())
-                }
-                ,
-                1 => Err(
-                    format!("{}because {} not response",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_ips[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following ips not response: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_ips.join(", ")
-                ).into()),
+                },
+                Some(false) => Err(_ignore_reason(format_args!(
+                    "because {} key {} is false",
+                    #file, #key
+                ))),
+                None => Err(_ignore_reason(format_args!(
+                    "because {} key {} is missing",
+                    #file, #key
+                ))),
             }
         }
 
@@ -987,89 +1404,94 @@ pub fn runtime_icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when socket connected
-///
+/// Run test case when the file exist.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Google DNS is online
-///     #[test_with::tcp(8.8.8.8:53)]
+///     // hostname exists
+///     #[test_with::file(/etc/hostname)]
 ///     #[test]
 ///     fn test_works() {
 ///         assert!(true);
 ///     }
 ///
-///     // 193.194.195.196 is offline
-///     #[test_with::tcp(193.194.195.196)]
+///     // nothing file does not exist
+///     #[test_with::file(/etc/nothing)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // hostname and hosts exist
+///     #[test_with::file(/etc/hostname, /etc/hosts)]
+///     #[test]
+///     fn test_works_too() {
+///         assert!(true);
+///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn file(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_tcp_condition,
+            check_file_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_tcp_condition,
+            check_file_condition,
         )
     }
 }
 
-fn check_tcp_condition(attr_str: String) -> (bool, String) {
-    let sockets: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_sockets = vec![];
-    for socket in sockets.iter() {
-        if TcpStream::connect(socket).is_err() {
-            missing_sockets.push(socket.to_string());
+fn check_file_condition(attr_str: String) -> (bool, String) {
+    let files = split_attr_list(&attr_str);
+    let mut missing_files = vec![];
+    for file in files.iter() {
+        if !Path::new(file.trim_matches('"')).is_file() {
+            missing_files.push(file.to_string());
         }
     }
-    let ignore_msg = if missing_sockets.len() == 1 {
-        format!("because fail to connect socket {}", missing_sockets[0])
+    let ignore_msg = if missing_files.len() == 1 {
+        format!("because file not found: {}", missing_files[0])
     } else {
         format!(
-            "because follow sockets can not connect\n{}\n",
-            missing_sockets.join(", ")
+            "because following files not found: \n{}\n",
+            missing_files.join("\n")
         )
     };
-    (missing_sockets.is_empty(), ignore_msg)
+    (missing_files.is_empty(), ignore_msg)
 }
 
-/// Run test case when the example running and socket connected
+/// Run test case when the example running and the file exist.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(tcp);
+/// test_with::runner!(file);
 /// #[test_with::module]
-/// mod tcp {
-///     // Google DNS is online
-///     #[test_with::runtime_tcp(8.8.8.8:53)]
-///     fn test_works_with_DNS_server() {
+/// mod file {
+///     #[test_with::runtime_file(/etc/hostname)]
+///     fn test_works() {
 ///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_tcp(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_file(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-
 #[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let sockets: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_file(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let files = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -1083,26 +1505,26 @@ pub fn runtime_tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     );
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-
-            let mut missing_sockets = vec![];
+            let mut missing_files = vec![];
             #(
-                if std::net::TcpStream::connect(#sockets).is_err() {
-                    missing_sockets.push(#sockets);
+                if !std::path::Path::new(#files.trim_matches('"')).is_file() {
+                    missing_files.push(#files);
                 }
             )*
-            match missing_sockets.len() {
+
+            match missing_files.len() {
                 0 => {
                     #ident();
                     Ok(())
                 },
-                1 => Err(
-                    format!("{}because {} not response",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_sockets[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following sockets not response: \n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_sockets.join(", ")
-                ).into()),
+                1 => Err(_ignore_reason(format_args!(
+                    "because file not found: {}",
+                    missing_files[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following files not found: \n{}\n",
+                    missing_files.join(", ")
+                ))),
             }
         }
 
@@ -1112,70 +1534,94 @@ pub fn runtime_tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when runner is root
-///
+/// Run test case when the path(file or folder) exist.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with root account
-///     #[test_with::root()]
+///     // etc exists
+///     #[test_with::path(/etc)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // nothing does not exist
+///     #[test_with::path(/nothing)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // etc and tmp exist
+///     #[test_with::path(/etc, /tmp)]
+///     #[test]
+///     fn test_works_too() {
+///         assert!(true);
+///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-pub fn root(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn path(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_root_condition,
+            check_path_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_root_condition,
+            check_path_condition,
         )
     }
 }
 
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-fn check_root_condition(_attr_str: String) -> (bool, String) {
-    let current_user_id = uzers::get_current_uid();
-    (
-        current_user_id == 0,
-        "because this case should run with root".into(),
-    )
+fn check_path_condition(attr_str: String) -> (bool, String) {
+    let paths = split_attr_list(&attr_str);
+    let mut missing_paths = vec![];
+    for path in paths.iter() {
+        if metadata(path.trim_matches('"')).is_err() {
+            missing_paths.push(path.to_string());
+        }
+    }
+    let ignore_msg = if missing_paths.len() == 1 {
+        format!("because path not found: {}", missing_paths[0])
+    } else {
+        format!(
+            "because following paths not found: \n{}\n",
+            missing_paths.join("\n")
+        )
+    };
+    (missing_paths.is_empty(), ignore_msg)
 }
 
-/// Run test case when runner is root
+/// Run test case when the example running and the path(file or folder) exist.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(user);
+/// test_with::runner!(path);
 /// #[test_with::module]
-/// mod user {
-///     // Google DNS is online
-///     #[test_with::runtime_root()]
-///     fn test_ignored() {
-///         panic!("should be ignored")
+/// mod path {
+///     #[test_with::runtime_path(/etc)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_root(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_path(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn runtime_path(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let paths = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -1189,11 +1635,26 @@ pub fn runtime_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
     );
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            if 0 == libtest_with::uzers::get_current_uid() {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because this case should run with root", libtest_with::RUNTIME_IGNORE_PREFIX).into())
+            let mut missing_paths = vec![];
+            #(
+                if std::fs::metadata(#paths.trim_matches('"')).is_err() {
+                    missing_paths.push(#paths.to_string());
+                }
+            )*
+
+            match missing_paths.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because path not found: {}",
+                    missing_paths[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following paths not found: \n{}\n",
+                    missing_paths.join(", ")
+                ))),
             }
         }
 
@@ -1203,86 +1664,97 @@ pub fn runtime_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when runner in group
-///
+/// Run test case when the directory exist, the file is not a directory.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with group avengers
-///     #[test_with::group(avengers)]
+///     // etc is a directory
+///     #[test_with::dir(/etc)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // hostname is a file
+///     #[test_with::dir(/etc/hostname)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // etc and tmp are directories
+///     #[test_with::dir(/etc, /tmp)]
+///     #[test]
+///     fn test_works_too() {
+///         assert!(true);
+///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-pub fn group(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn dir(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_group_condition,
+            check_dir_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_group_condition,
+            check_dir_condition,
         )
     }
 }
 
-#[cfg(feature = "user")]
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-fn check_group_condition(group_name: String) -> (bool, String) {
-    let current_user_id = uzers::get_current_uid();
-
-    let in_group = match uzers::get_user_by_uid(current_user_id) {
-        Some(user) => {
-            let mut in_group = false;
-            for group in user.groups().expect("user not found") {
-                if in_group {
-                    break;
-                }
-                in_group |= group.name().to_string_lossy() == group_name;
-            }
-            in_group
+fn check_dir_condition(attr_str: String) -> (bool, String) {
+    let dirs = split_attr_list(&attr_str);
+    let mut missing_dirs = vec![];
+    for dir in dirs.iter() {
+        if !metadata(dir.trim_matches('"')).is_ok_and(|m| m.is_dir()) {
+            missing_dirs.push(dir.to_string());
         }
-        None => false,
+    }
+    let ignore_msg = if missing_dirs.len() == 1 {
+        format!(
+            "because {} is not a directory (or missing)",
+            missing_dirs[0]
+        )
+    } else {
+        format!(
+            "because following paths are not directories (or missing): \n{}\n",
+            missing_dirs.join("\n")
+        )
     };
-    (
-        in_group,
-        format!("because this case should run user in group {}", group_name),
-    )
+    (missing_dirs.is_empty(), ignore_msg)
 }
 
-/// Run test case when runner in group
+/// Run test case when the example running and the directory exist.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(user);
+/// test_with::runner!(dir);
 /// #[test_with::module]
-/// mod user {
-///     // Only works with group avengers
-///     #[test_with::runtime_group(avengers)]
-///     fn test_ignored() {
-///         panic!("should be ignored")
+/// mod dir {
+///     #[test_with::runtime_dir(/etc)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_group(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_dir(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let group_name = attr.to_string().replace(' ', "");
+pub fn runtime_dir(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let dirs = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -1290,35 +1762,29 @@ pub fn runtime_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
-
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let current_user_id = libtest_with::uzers::get_current_uid();
-            let in_group = match libtest_with::uzers::get_user_by_uid(current_user_id) {
-                Some(user) => {
-                    let mut in_group = false;
-                    for group in user.groups().expect("user not found") {
-                        if in_group {
-                            break;
-                        }
-                        in_group |= group.name().to_string_lossy() == #group_name;
-                    }
-                    in_group
+            let mut missing_dirs = vec![];
+            #(
+                if !std::fs::metadata(#dirs.trim_matches('"')).is_ok_and(|m| m.is_dir()) {
+                    missing_dirs.push(#dirs.to_string());
                 }
-                None => false,
-            };
+            )*
 
-            if in_group {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because this case should run user in group {}",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, #group_name).into())
+            match missing_dirs.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because {} is not a directory (or missing)",
+                    missing_dirs[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following paths are not directories (or missing): \n{}\n",
+                    missing_dirs.join(", ")
+                ))),
             }
         }
 
@@ -1328,14 +1794,20 @@ pub fn runtime_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when runner is specific user
-///
+/// Run test case when the directory exists and is not empty.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with user
-///     #[test_with::user(spider)]
+///     // etc is not empty
+///     #[test_with::dir_not_empty(/etc)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // nothing does not exist
+///     #[test_with::dir_not_empty(/nothing)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
@@ -1344,59 +1816,68 @@ pub fn runtime_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-pub fn user(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn dir_not_empty(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_user_condition,
+            check_dir_not_empty_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_user_condition,
+            check_dir_not_empty_condition,
         )
     }
 }
 
-#[cfg(feature = "user")]
-#[cfg(all(feature = "user", not(target_os = "windows")))]
-fn check_user_condition(user_name: String) -> (bool, String) {
-    let is_user = match uzers::get_current_username() {
-        Some(uname) => uname.to_string_lossy() == user_name,
-        None => false,
+fn check_dir_not_empty_condition(attr_str: String) -> (bool, String) {
+    let dirs = split_attr_list(&attr_str);
+    let mut empty_dirs = vec![];
+    for dir in dirs.iter() {
+        // only the first entry is read
+        if !std::fs::read_dir(dir.trim_matches('"'))
+            .is_ok_and(|mut entries| entries.next().is_some())
+        {
+            empty_dirs.push(dir.to_string());
+        }
+    }
+    let ignore_msg = if empty_dirs.len() == 1 {
+        format!("because {} is empty or missing", empty_dirs[0])
+    } else {
+        format!(
+            "because following directories are empty or missing: \n{}\n",
+            empty_dirs.join("\n")
+        )
     };
-    (
-        is_user,
-        format!("because this case should run with user {}", user_name),
-    )
+    (empty_dirs.is_empty(), ignore_msg)
 }
 
-/// Run test case when runner is specific user
+/// Run test case when the example running and the directory exists and is not empty.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(user);
+/// test_with::runner!(dir);
 /// #[test_with::module]
-/// mod user {
-///     // Only works with user
-///     #[test_with::runtime_user(spider)]
-///     fn test_ignored() {
-///         panic!("should be ignored")
+/// mod dir {
+///     #[test_with::runtime_dir_not_empty(/etc)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_user(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_dir_not_empty(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_user(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let user_name = attr.to_string().replace(' ', "");
+pub fn runtime_dir_not_empty(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let dirs = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -1404,25 +1885,31 @@ pub fn runtime_user(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
-
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let is_user = match libtest_with::uzers::get_current_username() {
-                Some(uname) => uname.to_string_lossy() == #user_name,
-                None => false,
-            };
+            let mut empty_dirs = vec![];
+            #(
+                if !std::fs::read_dir(#dirs.trim_matches('"'))
+                    .is_ok_and(|mut entries| entries.next().is_some())
+                {
+                    empty_dirs.push(#dirs.to_string());
+                }
+            )*
 
-            if is_user {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because this case should run with user {}",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, #user_name).into())
+            match empty_dirs.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because {} is empty or missing",
+                    empty_dirs[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following directories are empty or missing: \n{}\n",
+                    empty_dirs.join(", ")
+                ))),
             }
         }
 
@@ -1432,14 +1919,22 @@ pub fn runtime_user(attr: TokenStream, stream: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Run test case when memory size enough
-///
+/// Run test case when the path is a mount point, ex: the network filesystem is mounted.  The mount
+/// point is detected by the device id differing from its parent's, so a bind mount on the same
+/// filesystem is not detected, and the test case is always ignored on the non-unix systems.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with enough memory size
-///     #[test_with::mem(100GB)]
+///     // the root is always mounted
+///     #[test_with::mounted(/)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // nothing is not mounted
+///     #[test_with::mounted(/nothing)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
@@ -1448,70 +1943,46 @@ pub fn runtime_user(attr: TokenStream, stream: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "resource")]
-pub fn mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn mounted(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_mem_condition,
+            crate::mount::check_mount_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_mem_condition,
+            crate::mount::check_mount_condition,
         )
     }
 }
 
-#[cfg(feature = "resource")]
-fn check_mem_condition(mem_size_str: String) -> (bool, String) {
-    let sys = sysinfo::System::new_with_specifics(
-        sysinfo::RefreshKind::nothing()
-            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_swap()),
-    );
-    let mem_size = match byte_unit::Byte::parse_str(format!("{} B", sys.total_memory()), false) {
-        Ok(b) => b,
-        Err(_) => abort_call_site!("memory size description is not correct"),
-    };
-    let mem_size_limitation = match byte_unit::Byte::parse_str(&mem_size_str, true) {
-        Ok(b) => b,
-        Err(_) => abort_call_site!("system memory size can not get"),
-    };
-    (
-        mem_size >= mem_size_limitation,
-        format!("because the memory less than {}", mem_size_str),
-    )
-}
-
-/// Run test case when the example running and memory size enough
+/// Run test case when the example running and the path is a mount point.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(mount);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough memory size
-///     #[test_with::runtime_mem(100GB)]
-///     fn test_ignored_mem_not_enough() {
-///         panic!("should be ignored")
+/// mod mount {
+///     #[test_with::runtime_mounted(/)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_mounted(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let mem_limitation_str = attr.to_string().replace(' ', "");
-    if byte_unit::Byte::parse_str(&mem_limitation_str, true).is_err() {
-        abort_call_site!("memory size description is not correct")
-    }
-
+pub fn runtime_mounted(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let paths = split_attr_list(&attr_str);
     let ItemFn {
         attrs,
         vis,
@@ -1519,129 +1990,157 @@ pub fn runtime_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    // the target may not be the host, so the target of the example is checked in the generated code
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let sys = libtest_with::sysinfo::System::new_with_specifics(
-                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
-            );
-            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.total_memory()), false) {
-                Ok(b) => b,
-                Err(_) => panic!("system memory size can not get"),
-            };
-            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
-            if  mem_size >= mem_size_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the memory less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #mem_limitation_str).into())
+            #[cfg(not(unix))]
+            {
+                Err(_ignore_reason(format_args!(
+                    "because the mount point is only checked on unix"
+                )))
+            }
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let mut unmounted = vec![];
+                #(
+                    let path = std::path::Path::new(#paths.trim_matches('"'));
+                    let mounted = match (std::fs::metadata(path), std::fs::metadata(path.join(".."))) {
+                        (Ok(meta), Ok(parent)) => {
+                            meta.dev() != parent.dev() || meta.ino() == parent.ino()
+                        }
+                        _ => false,
+                    };
+                    if !mounted {
+                        unmounted.push(#paths.to_string());
+                    }
+                )*
+
+                match unmounted.len() {
+                    0 => {
+                        #ident();
+                        Ok(())
+                    },
+                    1 => Err(_ignore_reason(format_args!(
+                        "because {} is not mounted",
+                        unmounted[0]
+                    ))),
+                    _ => Err(_ignore_reason(format_args!(
+                        "because following paths are not mounted: \n{}\n",
+                        unmounted.join(", ")
+                    ))),
+                }
             }
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Run test case when the example running and free memory size enough
-///```rust
-/// // write as example in examples/*rs
-/// test_with::runner!(resource);
-/// #[test_with::module]
-/// mod resource {
-///     // Only works with enough free memory size
-///     #[test_with::runtime_free_mem(100GB)]
-///     fn test_ignored_free_mem_not_enough() {
+/// Run test case when the http service exist.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // http service exists
+///     #[test_with::http(httpbin.org)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // There is no not.exist.com
+///     #[test_with::http(not.exist.com)]
+///     #[test]
+///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // Probe up to 3 times before ignoring the test case
+///     #[test_with::http(httpbin.org, retries=3)]
+///     #[test]
+///     fn test_works_with_retries() {
+///         assert!(true);
+///     }
+///
+///     // Ignore the test case if the response is slower than 10 seconds
+///     #[test_with::http(httpbin.org, max_ms=10000)]
+///     #[test]
+///     fn test_works_in_time() {
+///         assert!(true);
+///     }
 /// }
-#[cfg(not(feature = "runtime"))]
-#[proc_macro_attribute]
-#[proc_macro_error]
-pub fn runtime_free_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
-    panic!("should be used with runtime feature")
-}
-#[cfg(all(feature = "runtime", feature = "resource"))]
+/// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_free_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let mem_limitation_str = attr.to_string().replace(' ', "");
-    if byte_unit::Byte::parse_str(&mem_limitation_str, true).is_err() {
-        abort_call_site!("memory size description is not correct")
+#[cfg(feature = "http")]
+pub fn http(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_http_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_http_condition,
+        )
     }
+}
 
-    let ItemFn {
-        attrs,
-        vis,
-        sig,
-        block,
-    } = parse_macro_input!(stream as ItemFn);
-    let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
-    quote::quote! {
-        fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let sys = libtest_with::sysinfo::System::new_with_specifics(
-                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
-            );
-            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.free_memory()), false) {
-                Ok(b) => b,
-                Err(_) => panic!("system memory size can not get"),
-            };
-            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
-            if  mem_size >= mem_size_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the memory less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #mem_limitation_str).into())
-            }
-        }
-
-        #(#attrs)*
-        #vis #sig #block
-
-    }
-    .into()
+#[cfg(feature = "http")]
+fn check_http_condition(attr_str: String) -> (bool, String) {
+    let (links, retries) = split_retries_option(&attr_str);
+    let (links, tls) = split_tls_options(links);
+    let (links, max_ms) = split_max_ms_option(links);
+    let client = http_client(&tls);
+    let (missing_links, slow_links) = probe_http_links("http", &links, retries, max_ms, &client);
+    (
+        missing_links.is_empty() && slow_links.is_empty(),
+        http_ignore_msg(&missing_links, &slow_links),
+    )
 }
 
-/// Run test case when the example running and available memory size enough
+/// Run test case when the example running and the http service exist.
+/// The probe result of the same target can be reused within the given seconds with `cache=SECS`,
+/// such that many tests on the same host will not probe it again and again, but the cached result
+/// may be stale if the service goes up or down within the seconds.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(http);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough available memory size
-///     #[test_with::runtime_available_mem(100GB)]
-///     fn test_ignored_available_mem_not_enough() {
-///         panic!("should be ignored")
+/// mod http {
+///     #[test_with::runtime_http(httpbin.org)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     #[test_with::runtime_http(httpbin.org, cache=30)]
+///     fn test_works_with_cached_probe() {
+///         assert!(true);
 ///     }
 /// }
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_available_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_http(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+
+#[cfg(all(feature = "runtime", feature = "http"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_available_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let mem_limitation_str = attr.to_string().replace(' ', "");
-    if byte_unit::Byte::parse_str(&mem_limitation_str, true).is_err() {
-        abort_call_site!("memory size description is not correct")
-    }
-
+pub fn runtime_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let (links, retries) = split_retries_option(&attr_str);
+    let (links, cache_ttl) = split_cache_option(links);
+    let (links, tls) = split_tls_options(links);
+    let (links, max_ms) = split_max_ms_option(links);
     let ItemFn {
         attrs,
         vis,
@@ -1649,117 +2148,108 @@ pub fn runtime_available_mem(attr: TokenStream, stream: TokenStream) -> TokenStr
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let check_tokens = http_check_tokens("http", &links, retries, cache_ttl, max_ms, &tls, &ident);
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let sys = libtest_with::sysinfo::System::new_with_specifics(
-                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
-            );
-            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.available_memory()), false) {
-                Ok(b) => b,
-                Err(_) => panic!("system memory size can not get"),
-            };
-            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
-            if  mem_size >= mem_size_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the memory less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #mem_limitation_str).into())
-            }
+            #check_tokens
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Run test case when swap size enough
-///
+/// Run test case when the https service exist.
+/// The custom root CA can be given with `root_cert="ca.pem"`, and the certificate check can be
+/// turned off with `danger_accept_invalid_certs`, which trusts any certificate including the ones
+/// of a man-in-the-middle, so it should only be used for the services under your control.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with enough swap size
-///     #[test_with::swap(100GB)]
+///     // https server exists
+///     #[test_with::https(www.rust-lang.org)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // There is no not.exist.com
+///     #[test_with::https(not.exist.com)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // The certificate is not checked
+///     #[test_with::https(www.rust-lang.org, danger_accept_invalid_certs)]
+///     #[test]
+///     fn test_works_without_cert_check() {
+///         assert!(true);
+///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "resource")]
-pub fn swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+#[cfg(feature = "http")]
+pub fn https(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_swap_condition,
+            check_https_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_swap_condition,
+            check_https_condition,
         )
     }
 }
 
-#[cfg(feature = "resource")]
-fn check_swap_condition(swap_size_str: String) -> (bool, String) {
-    let sys = sysinfo::System::new_with_specifics(
-        sysinfo::RefreshKind::nothing()
-            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_swap()),
-    );
-    let swap_size = match byte_unit::Byte::parse_str(format!("{} B", sys.total_swap()), false) {
-        Ok(b) => b,
-        Err(_) => abort_call_site!("Swap size description is not correct"),
-    };
-    let swap_size_limitation = match byte_unit::Byte::parse_str(&swap_size_str, true) {
-        Ok(b) => b,
-        Err(_) => abort_call_site!("Can not get system swap size"),
-    };
+#[cfg(feature = "http")]
+fn check_https_condition(attr_str: String) -> (bool, String) {
+    let (links, retries) = split_retries_option(&attr_str);
+    let (links, tls) = split_tls_options(links);
+    let (links, max_ms) = split_max_ms_option(links);
+    let client = http_client(&tls);
+    let (missing_links, slow_links) = probe_http_links("https", &links, retries, max_ms, &client);
     (
-        swap_size >= swap_size_limitation,
-        format!("because the swap less than {}", swap_size_str),
+        missing_links.is_empty() && slow_links.is_empty(),
+        http_ignore_msg(&missing_links, &slow_links),
     )
 }
 
-/// Run test case when the example running and swap enough
+/// Run test case when the example running and the http service exist.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(http);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough swap size
-///     #[test_with::runtime_swap(100GB)]
-///     fn test_ignored_swap_not_enough() {
-///         panic!("should be ignored")
+/// mod http {
+///     #[test_with::runtime_https(httpbin.org)]
+///     fn test_works() {
+///         assert!(true);
 ///     }
 /// }
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_https(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+
+#[cfg(all(feature = "runtime", feature = "http"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let swap_limitation_str = attr.to_string().replace(' ', "");
-    if byte_unit::Byte::parse_str(&swap_limitation_str, true).is_err() {
-        abort_call_site!("swap size description is not correct")
-    }
-
+pub fn runtime_https(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let (links, retries) = split_retries_option(&attr_str);
+    let (links, cache_ttl) = split_cache_option(links);
+    let (links, tls) = split_tls_options(links);
+    let (links, max_ms) = split_max_ms_option(links);
     let ItemFn {
         attrs,
         vis,
@@ -1767,64 +2257,47 @@ pub fn runtime_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let check_tokens = http_check_tokens("https", &links, retries, cache_ttl, max_ms, &tls, &ident);
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let sys = libtest_with::sysinfo::System::new_with_specifics(
-                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
-            );
-            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.total_swap()), false) {
-                Ok(b) => b,
-                Err(_) => panic!("system swap size can not get"),
-            };
-            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
-            if  swap_size >= swap_size_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the swap less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #swap_limitation_str).into())
-            }
+            #check_tokens
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Run test case when the example running and free swap enough
+/// Run test case when the example running and the http service does not respond, ex: the failover
+/// test case runs when the primary service is offline.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(http);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough free swap size
-///     #[test_with::runtime_free_swap(100GB)]
-///     fn test_ignored_free_swap_not_enough() {
-///         panic!("should be ignored")
+/// mod http {
+///     #[test_with::runtime_no_http(primary.not.exist.com)]
+///     fn test_failover() {
+///         assert!(true);
 ///     }
 /// }
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_free_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_no_http(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+
+#[cfg(all(feature = "runtime", feature = "http"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_free_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let swap_limitation_str = attr.to_string().replace(' ', "");
-    if byte_unit::Byte::parse_str(&swap_limitation_str, true).is_err() {
-        abort_call_site!("swap size description is not correct")
-    }
-
+pub fn runtime_no_http(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let urls: Vec<String> = split_attr_list(&attr_str)
+        .into_iter()
+        .map(|link| format!("http://{}", link))
+        .collect();
     let ItemFn {
         attrs,
         vis,
@@ -1832,45 +2305,54 @@ pub fn runtime_free_swap(attr: TokenStream, stream: TokenStream) -> TokenStream
         block,
     } = parse_macro_input!(stream as ItemFn);
     let syn::Signature { ident, .. } = sig.clone();
-    let check_ident = syn::Ident::new(
-        &format!("_check_{}", ident.to_string()),
-        proc_macro2::Span::call_site(),
-    );
-
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let sys = libtest_with::sysinfo::System::new_with_specifics(
-                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
-            );
-            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.free_swap()), false) {
-                Ok(b) => b,
-                Err(_) => panic!("system swap size can not get"),
-            };
-            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
-            if  swap_size >= swap_size_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the swap less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #swap_limitation_str).into())
+            let client = libtest_with::reqwest::blocking::Client::new();
+            let mut online_links = vec![];
+            #(
+                if client.head(#urls).send().is_ok() {
+                    online_links.push(#urls);
+                }
+            )*
+            match online_links.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!("because {} responded", online_links[0]))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following links responded: \n{}\n",
+                    online_links.join(", ")
+                ))),
             }
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Run test case when cpu core enough
+/// Run test case when the server online.
+/// The ICMP echo needs the capability to open raw socket, if the echo fails, ex: the raw socket is
+/// not permitted on CI runners, the host is checked by TCP connect as well, which treats the
+/// refused connection as online.  The method can be forced with `method=raw` or
+/// `method=unprivileged`.
 ///
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with enough cpu core
-///     #[test_with::cpu_core(32)]
+///     // localhost is online
+///     #[test_with::icmp(127.0.0.1)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // 193.194.195.196 is offline
+///     #[test_with::icmp(193.194.195.196)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
@@ -1879,62 +2361,189 @@ pub fn runtime_free_swap(attr: TokenStream, stream: TokenStream) -> TokenStream
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "resource")]
-pub fn cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+#[cfg(feature = "icmp")]
+pub fn icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_cpu_core_condition,
+            check_icmp_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_cpu_core_condition,
+            check_icmp_condition,
         )
     }
 }
 
-#[cfg(feature = "resource")]
-fn check_cpu_core_condition(core_limitation_str: String) -> (bool, String) {
-    (
-        match core_limitation_str.parse::<usize>() {
-            Ok(c) => num_cpus::get() >= c,
-            Err(_) => abort_call_site!("core limitation is incorrect"),
-        },
-        format!("because the cpu core less than {}", core_limitation_str),
-    )
+#[cfg(feature = "icmp")]
+fn check_icmp_condition(attr_str: String) -> (bool, String) {
+    let (ips, retries) = split_retries_option(&attr_str);
+    let (ips, method) = split_icmp_method_option(ips);
+    let (ips, family) = split_family_option(ips);
+    let mut missing_ips = vec![];
+    for ip in ips.iter() {
+        if let Ok(addr) = ip.parse::<IpAddr>() {
+            check_ip_family(&addr, family);
+            if !retry_probe(retries, || match method {
+                IcmpMethod::Raw => ping::ping(addr, None, None, None, None, None).is_ok(),
+                IcmpMethod::Unprivileged => reachable_without_raw_socket(addr),
+                IcmpMethod::Auto => {
+                    ping::ping(addr, None, None, None, None, None).is_ok()
+                        || reachable_without_raw_socket(addr)
+                }
+            }) {
+                missing_ips.push(ip.to_string());
+            }
+        } else {
+            abort_call_site!("ip address malformat")
+        }
+    }
+    let ignore_msg = if missing_ips.len() == 1 {
+        format!("because ip {} not response", missing_ips[0])
+    } else {
+        format!(
+            "because following ip not response: \n{}\n",
+            missing_ips.join(", ")
+        )
+    };
+    (missing_ips.is_empty(), ignore_msg)
 }
 
-/// Run test case when cpu core enough
+/// The way to check the host online
+#[cfg(feature = "icmp")]
+#[derive(Clone, Copy)]
+enum IcmpMethod {
+    /// Try the ICMP echo with raw socket, then fallback to the unprivileged way
+    Auto,
+    /// ICMP echo with raw socket, which needs the privilege
+    Raw,
+    /// TCP connect, the host is online if the connection is accepted or refused
+    Unprivileged,
+}
+
+#[cfg(feature = "icmp")]
+impl IcmpMethod {
+    /// The value of the `method` option, which is also a part of the key of the probe cache
+    #[cfg(feature = "runtime")]
+    fn option(&self) -> &'static str {
+        match self {
+            IcmpMethod::Auto => "auto",
+            IcmpMethod::Raw => "raw",
+            IcmpMethod::Unprivileged => "unprivileged",
+        }
+    }
+}
+
+/// Take out the `method=raw|unprivileged` option from the items of the icmp conditions
+#[cfg(feature = "icmp")]
+fn split_icmp_method_option(items: Vec<&str>) -> (Vec<&str>, IcmpMethod) {
+    let mut remaining = vec![];
+    let mut method = IcmpMethod::Auto;
+    for item in items {
+        if let Some(m) = item.strip_prefix("method=") {
+            method = match m {
+                "raw" => IcmpMethod::Raw,
+                "unprivileged" => IcmpMethod::Unprivileged,
+                _ => abort_call_site!("`method` should be `raw` or `unprivileged`"),
+            };
+        } else {
+            remaining.push(item);
+        }
+    }
+    (remaining, method)
+}
+
+/// The ip address of icmp conditions is not resolved, so it should be the forced family
+#[cfg(feature = "icmp")]
+fn check_ip_family(addr: &IpAddr, family: Option<IpFamily>) {
+    if let Some(family) = family {
+        if !family.matches(addr) {
+            abort_call_site!("ip {} is not {}", addr, family)
+        }
+    }
+}
+
+/// Check the host online without raw socket, the refused connection also means the host response
+#[cfg(feature = "icmp")]
+fn reachable_without_raw_socket(addr: IpAddr) -> bool {
+    match TcpStream::connect_timeout(
+        &std::net::SocketAddr::new(addr, 7),
+        std::time::Duration::from_secs(1),
+    ) {
+        Ok(_) => true,
+        Err(e) => e.kind() == std::io::ErrorKind::ConnectionRefused,
+    }
+}
+
+/// Run test case when the example running and the server online.
+/// Please make sure the role of test case runner have capability to open socket
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(icmp);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough cpu core
-///     #[test_with::runtime_cpu_core(32)]
-///     fn test_ignored_core_not_enough() {
-///         panic!("should be ignored")
+/// mod icmp {
+///     // 193.194.195.196 is offline
+///     #[test_with::runtime_icmp(193.194.195.196)]
+///     fn test_ignored_with_non_existing_host() {
+///         panic!("should be ignored with non existing host")
 ///     }
 /// }
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_cpu_core(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_icmp(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+
+#[cfg(all(feature = "runtime", feature = "icmp"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn runtime_icmp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     let attr_str = attr.to_string().replace(' ', "");
-    let core_limitation = match attr_str.parse::<usize>() {
-        Ok(c) => c,
-        Err(_) => abort_call_site!("core limitation is incorrect"),
-    };
-
+    let (ips, retries) = split_retries_option(&attr_str);
+    let (ips, cache_ttl) = split_cache_option(ips);
+    let (ips, method) = split_icmp_method_option(ips);
+    let (ips, family) = split_family_option(ips);
+    let probes: Vec<proc_macro2::TokenStream> = ips
+        .iter()
+        .map(|ip| {
+            match ip.parse::<IpAddr>() {
+                Ok(addr) => check_ip_family(&addr, family),
+                Err(_) => abort_call_site!("ip address malformat"),
+            }
+            let raw = quote::quote! {
+                libtest_with::ping::ping(#ip.parse().expect("ip address is invalid"), None, None, None, None, None).is_ok()
+            };
+            let unprivileged = quote::quote! {
+                match std::net::TcpStream::connect_timeout(
+                    &std::net::SocketAddr::new(#ip.parse().expect("ip address is invalid"), 7),
+                    std::time::Duration::from_secs(1),
+                ) {
+                    Ok(_) => true,
+                    Err(e) => e.kind() == std::io::ErrorKind::ConnectionRefused,
+                }
+            };
+            let probe = match method {
+                IcmpMethod::Raw => raw,
+                IcmpMethod::Unprivileged => unprivileged,
+                IcmpMethod::Auto => quote::quote! { (#raw || #unprivileged) },
+            };
+            // the probe depends on the method and the family, so they are a part of the key
+            let key = match family {
+                Some(family) => format!(
+                    "icmp://{}?method={}&family={}",
+                    ip,
+                    method.option(),
+                    family.option()
+                ),
+                None => format!("icmp://{}?method={}", ip, method.option()),
+            };
+            cached_probe_tokens(cache_ttl, key, retry_probe_tokens(retries, probe))
+        })
+        .collect();
     let ItemFn {
         attrs,
         vis,
@@ -1946,100 +2555,299 @@ pub fn runtime_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
         &format!("_check_{}", ident.to_string()),
         proc_macro2::Span::call_site(),
     );
-
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            if libtest_with::num_cpus::get() >= #core_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the cpu core less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #core_limitation).into())
+
+            let mut missing_ips = vec![];
+            #(
+                if !#probes {
+                    missing_ips.push(#ips);
+                }
+            )*
+            match missing_ips.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                }
+                ,
+                1 => Err(_ignore_reason(format_args!("because {} not response", missing_ips[0]))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following ips not response: \n{}\n",
+                    missing_ips.join(", ")
+                ))),
             }
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Run test case when physical cpu core enough
+/// Run test case when socket connected
 ///
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // Only works with enough cpu core
-///     #[test_with::phy_core(32)]
+///     // Google DNS is online
+///     #[test_with::tcp(8.8.8.8:53)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+///
+///     // 193.194.195.196 is offline
+///     #[test_with::tcp(193.194.195.196)]
 ///     #[test]
 ///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
+///
+///     // Probe up to 3 times before ignoring the test case
+///     #[test_with::tcp(8.8.8.8:53, retries=3)]
+///     #[test]
+///     fn test_works_with_retries() {
+///         assert!(true);
+///     }
+///
+///     // The port 53 in the range accepts the connection
+///     #[test_with::tcp(8.8.8.8:53-55)]
+///     #[test]
+///     fn test_works_with_port_range() {
+///         assert!(true);
+///     }
+///
+///     // Only connect to the IPv6 addresses of the host
+///     #[test_with::tcp(localhost:53, family=v6)]
+///     #[test]
+///     fn test_works_over_ipv6() {
+///         assert!(true);
+///     }
 /// }
 /// ```
+/// When the host resolves to both IPv4 and IPv6 addresses, any family that connects is accepted,
+/// and the family can be forced with `family=v4` or `family=v6`.
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "resource")]
-pub fn phy_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_cpu_core_condition,
+            check_tcp_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_phy_core_condition,
+            check_tcp_condition,
         )
     }
 }
 
-#[cfg(feature = "resource")]
-fn check_phy_core_condition(core_limitation_str: String) -> (bool, String) {
-    (
-        match core_limitation_str.parse::<usize>() {
-            Ok(c) => num_cpus::get_physical() >= c,
-            Err(_) => abort_call_site!("physical core limitation is incorrect"),
-        },
+fn check_tcp_condition(attr_str: String) -> (bool, String) {
+    let (sockets, retries) = split_retries_option(&attr_str);
+    let (sockets, family) = split_family_option(sockets);
+    let mut missing_sockets = vec![];
+    for socket in sockets.iter() {
+        let candidates = expand_port_range(socket);
+        let connected = retry_probe(retries, || {
+            candidates.iter().any(|candidate| match family {
+                Some(family) => candidate
+                    .to_socket_addrs()
+                    .map(|mut addrs| {
+                        addrs.any(|addr| {
+                            family.matches(&addr.ip()) && TcpStream::connect(addr).is_ok()
+                        })
+                    })
+                    .unwrap_or(false),
+                None => TcpStream::connect(candidate).is_ok(),
+            })
+        });
+        if !connected {
+            match family {
+                Some(family) => missing_sockets.push(format!("{} over {}", socket, family)),
+                None => missing_sockets.push(socket.to_string()),
+            }
+        }
+    }
+    let ignore_msg = if missing_sockets.len() == 1 {
+        format!("because fail to connect socket {}", missing_sockets[0])
+    } else {
         format!(
-            "because the physical cpu core less than {}",
-            core_limitation_str
-        ),
-    )
+            "because follow sockets can not connect\n{}\n",
+            missing_sockets.join(", ")
+        )
+    };
+    (missing_sockets.is_empty(), ignore_msg)
 }
 
-/// Run test case when physical core enough
+/// Run test case when a local service is listening on the port, the port is connected on the IPv4
+/// and IPv6 loopback, so the service on the other hosts does not count.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Nothing is listening on the port 1
+///     #[test_with::listening(1)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn listening(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::socket::check_listening_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::socket::check_listening_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and a local service is listening on the port.
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(resource);
+/// test_with::runner!(local);
 /// #[test_with::module]
-/// mod resource {
-///     // Only works with enough physical cpu core
-///     #[test_with::runtime_phy_cpu_core(32)]
-///     fn test_ignored_phy_core_not_enough() {
+/// mod local {
+///     #[test_with::runtime_listening(1)]
+///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
 /// }
+///```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_phy_cpu_core(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_listening(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "resource"))]
+#[cfg(feature = "runtime")]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_phy_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let core_limitation = match attr_str.parse::<usize>() {
-        Ok(c) => c,
-        Err(_) => abort_call_site!("physical core limitation is incorrect"),
-    };
+pub fn runtime_listening(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let ports = crate::socket::parse_ports(&attr_str);
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let mut idle_ports = vec![];
+            #(
+                let listening = [
+                    std::net::SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, #ports)),
+                    std::net::SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, #ports)),
+                ]
+                .iter()
+                .any(|addr| std::net::TcpStream::connect(addr).is_ok());
+                if !listening {
+                    idle_ports.push(#ports.to_string());
+                }
+            )*
+            match idle_ports.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because nothing is listening on localhost:{}",
+                    idle_ports[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because nothing is listening on following ports of localhost:\n{}\n",
+                    idle_ports.join(", ")
+                ))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the example running and socket connected
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(tcp);
+/// #[test_with::module]
+/// mod tcp {
+///     // Google DNS is online
+///     #[test_with::runtime_tcp(8.8.8.8:53)]
+///     fn test_works_with_DNS_server() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_tcp(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
 
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let (sockets, retries) = split_retries_option(&attr_str);
+    let (sockets, cache_ttl) = split_cache_option(sockets);
+    let (sockets, family) = split_family_option(sockets);
+    let probes: Vec<proc_macro2::TokenStream> = sockets
+        .iter()
+        .map(|socket| {
+            let candidates = expand_port_range(socket);
+            let probe = match family {
+                Some(IpFamily::V4) => quote::quote! {
+                    [#(#candidates),*].iter().any(|socket| {
+                        std::net::ToSocketAddrs::to_socket_addrs(socket)
+                            .map(|mut addrs| addrs.any(|addr| addr.is_ipv4() && std::net::TcpStream::connect(addr).is_ok()))
+                            .unwrap_or(false)
+                    })
+                },
+                Some(IpFamily::V6) => quote::quote! {
+                    [#(#candidates),*].iter().any(|socket| {
+                        std::net::ToSocketAddrs::to_socket_addrs(socket)
+                            .map(|mut addrs| addrs.any(|addr| addr.is_ipv6() && std::net::TcpStream::connect(addr).is_ok()))
+                            .unwrap_or(false)
+                    })
+                },
+                None => quote::quote! {
+                    [#(#candidates),*].iter().any(|socket| std::net::TcpStream::connect(socket).is_ok())
+                },
+            };
+            // the probe depends on the family, so it is a part of the key
+            let key = match family {
+                Some(family) => format!("tcp://{}?family={}", socket, family.option()),
+                None => format!("tcp://{}", socket),
+            };
+            cached_probe_tokens(cache_ttl, key, retry_probe_tokens(retries, probe))
+        })
+        .collect();
+    let sockets: Vec<String> = sockets
+        .iter()
+        .map(|socket| match family {
+            Some(family) => format!("{} over {}", socket, family),
+            None => socket.to_string(),
+        })
+        .collect();
     let ItemFn {
         attrs,
         vis,
@@ -2051,121 +2859,165 @@ pub fn runtime_phy_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStre
         &format!("_check_{}", ident.to_string()),
         proc_macro2::Span::call_site(),
     );
-
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            if libtest_with::num_cpus::get_physical() >= #core_limitation {
-                #ident();
-                Ok(())
-            } else {
-                Err(format!("{}because the physical cpu core less than {}",
-                        libtest_with::RUNTIME_IGNORE_PREFIX, #core_limitation).into())
+
+            let mut missing_sockets = vec![];
+            #(
+                if !#probes {
+                    missing_sockets.push(#sockets);
+                }
+            )*
+            match missing_sockets.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because {} not response",
+                    missing_sockets[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following sockets not response: \n{}\n",
+                    missing_sockets.join(", ")
+                ))),
             }
         }
 
         #(#attrs)*
         #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the example running and the socket can not be connected, ex: the failover
+/// test case runs when the primary database is offline.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(tcp);
+/// #[test_with::module]
+/// mod tcp {
+///     #[test_with::runtime_no_tcp(127.0.0.1:5432)]
+///     fn test_failover() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_tcp(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_tcp(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let sockets = split_attr_list(&attr_str);
+    let candidates: Vec<Vec<String>> = sockets
+        .iter()
+        .map(|socket| expand_port_range(socket))
+        .collect();
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let mut online_sockets = vec![];
+            #(
+                if [#(#candidates),*].iter().any(|socket| std::net::TcpStream::connect(socket).is_ok()) {
+                    online_sockets.push(#sockets);
+                }
+            )*
+            match online_sockets.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!("because {} responded", online_sockets[0]))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following sockets responded: \n{}\n",
+                    online_sockets.join(", ")
+                ))),
+            }
+        }
 
+        #(#attrs)*
+        #vis #sig #block
     }
     .into()
 }
 
-/// Run test case when the executables exist.
+/// Run test case when runner is root
+///
 /// ```
 /// #[cfg(test)]
 /// mod tests {
-///     // `pwd` executable command exists
-///     #[test_with::executable(pwd)]
-///     #[test]
-///     fn test_executable() {
-///         assert!(true);
-///     }
-///
-///     // `/bin/sh` executable exists
-///     #[test_with::executable(/bin/sh)]
-///     #[test]
-///     fn test_executable_with_path() {
-///         assert!(true);
-///     }
 ///
-///     // `non` does not exist
-///     #[test_with::executable(non)]
+///     // Only works with root account
+///     #[test_with::root()]
 ///     #[test]
-///     fn test_non_existing_executable() {
+///     fn test_ignored() {
 ///         panic!("should be ignored")
 ///     }
-///
-///     // `pwd` and `ls` exist
-///     #[test_with::executable(pwd, ls)]
-///     #[test]
-///     fn test_executables_too() {
-///         assert!(true);
-///     }
 /// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
-#[cfg(feature = "executable")]
-pub fn executable(attr: TokenStream, stream: TokenStream) -> TokenStream {
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+pub fn root(attr: TokenStream, stream: TokenStream) -> TokenStream {
     if is_module(&stream) {
         mod_macro(
             attr,
             parse_macro_input!(stream as ItemMod),
-            check_executable_condition,
+            check_root_condition,
         )
     } else {
         fn_macro(
             attr,
             parse_macro_input!(stream as ItemFn),
-            check_executable_condition,
+            check_root_condition,
         )
     }
 }
 
-#[cfg(feature = "executable")]
-fn check_executable_condition(attr_str: String) -> (bool, String) {
-    let executables: Vec<&str> = attr_str.split(',').collect();
-    let mut missing_executables = vec![];
-    for exe in executables.iter() {
-        if which(exe.trim_matches('"')).is_err() {
-            missing_executables.push(exe.to_string());
-        }
-    }
-    let ignore_msg = if missing_executables.len() == 1 {
-        format!("because executable not found: {}", missing_executables[0])
-    } else {
-        format!(
-            "because following executables not found: \n{}\n",
-            missing_executables.join("\n")
-        )
-    };
-    (missing_executables.is_empty(), ignore_msg)
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+fn check_root_condition(_attr_str: String) -> (bool, String) {
+    let current_user_id = uzers::get_current_uid();
+    (
+        current_user_id == 0,
+        "because this case should run with root".into(),
+    )
 }
 
-/// Run test case when the executable existing
+/// Run test case when runner is root
 ///```rust
 /// // write as example in examples/*rs
-/// test_with::runner!(exe);
+/// test_with::runner!(user);
 /// #[test_with::module]
-/// mod exe {
-///     // `/bin/sh` executable exists
-///     #[test_with::runtime_executable(/bin/sh)]
-///     fn test_executable_with_path() {
-///         assert!(true);
+/// mod user {
+///     // Google DNS is online
+///     #[test_with::runtime_root()]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_executable(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_root(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(all(feature = "runtime", feature = "executable"))]
+#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_executable(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let attr_str = attr.to_string().replace(' ', "");
-    let executables: Vec<&str> = attr_str.split(',').collect();
+pub fn runtime_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
     let ItemFn {
         attrs,
         vis,
@@ -2177,343 +3029,310 @@ pub fn runtime_executable(attr: TokenStream, stream: TokenStream) -> TokenStream
         &format!("_check_{}", ident.to_string()),
         proc_macro2::Span::call_site(),
     );
-
     quote::quote! {
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            let mut missing_executables = vec![];
-            #(
-                if libtest_with::which::which(#executables).is_err() {
-                    missing_executables.push(#executables);
-                }
-            )*
-            match missing_executables.len() {
-                0 => {
-                    #ident();
-                    Ok(())
-                },
-                1 => Err(
-                    format!("{}because executable {} not found",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_executables[0]
-                ).into()),
-                _ => Err(
-                    format!("{}because following executables not found:\n{}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, missing_executables.join(", ")
-                ).into()),
+            if 0 == libtest_with::uzers::get_current_uid() {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because this case should run with root")))
             }
         }
 
         #(#attrs)*
         #vis #sig #block
-
     }
     .into()
 }
 
-/// Provide a test runner and test on each module
-///```rust
-/// // example/run-test.rs
+/// Run test case when runner is effective root
+/// The `root` condition checks the real user id, but the effective user id decides the privilege,
+/// ex: the test case runs with a setuid helper, the real user id is not zero but the effective user
+/// id is zero.
 ///
-/// test_with::runner!(module1, module2);
-/// #[test_with::module]
-/// mod module1 {
-///     #[test_with::runtime_env(PWD)]
-///     fn test_works() {
-///         assert!(true);
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with effective root
+///     #[test_with::effective_root()]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
-///
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+pub fn effective_root(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_effective_root_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_effective_root_condition,
+        )
+    }
+}
+
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+fn check_effective_root_condition(_attr_str: String) -> (bool, String) {
+    let effective_user_id = uzers::get_effective_uid();
+    (
+        effective_user_id == 0,
+        "because this case should run with effective root".into(),
+    )
+}
+
+/// Run test case when runner is effective root
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(user);
 /// #[test_with::module]
-/// mod module2 {
-///     #[test_with::runtime_env(PWD)]
-///     fn test_works() {
-///         assert!(true);
+/// mod user {
+///     #[test_with::runtime_effective_root()]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
-///```
 #[cfg(not(feature = "runtime"))]
-#[proc_macro]
-pub fn runner(_input: TokenStream) -> TokenStream {
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_effective_root(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(feature = "runtime")]
-#[proc_macro]
-pub fn runner(input: TokenStream) -> TokenStream {
-    let input_str = input.to_string();
-    let mod_names: Vec<syn::Ident> = input_str
-        .split(",")
-        .map(|s| syn::Ident::new(s.trim(), proc_macro2::Span::call_site()))
-        .collect();
+#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_effective_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
     quote::quote! {
-        fn main() {
-            let args = libtest_with::Arguments::from_args();
-            let mut no_env_tests = Vec::new();
-            #(
-                match #mod_names::_runtime_tests() {
-                    (Some(env), tests) => {
-                        libtest_with::run(&args, tests).exit_if_failed();
-                        drop(env);
-                    },
-                    (None, mut tests) => no_env_tests.append(&mut tests),
-                }
-            )*
-            libtest_with::run(&args, no_env_tests).exit();
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if 0 == libtest_with::uzers::get_effective_uid() {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because this case should run with effective root")))
+            }
         }
+
+        #(#attrs)*
+        #vis #sig #block
     }
     .into()
 }
 
-/// Help each function with `#[test_with::runtime_*]` in the module can register to run
-/// Also you can set up a mock instance for all of the test in the module
-///
-/// ```rust
-///  // example/run-test.rs
-///
-///  test_with::runner!(module1, module2);
-///  #[test_with::module]
-///  mod module1 {
-///      #[test_with::runtime_env(PWD)]
-///      fn test_works() {
-///          assert!(true);
-///      }
-///  }
+/// Run test case when runner in group
 ///
-///  #[test_with::module]
-///  mod module2 {
-///      #[test_with::runtime_env(PWD)]
-///      fn test_works() {
-///          assert!(true);
-///      }
-///  }
 /// ```
-/// You can set up mock with a public struct named `TestEnv` inside the module, or a public type
-/// named `TestEnv` inside the module.  And the type or struct should have a Default trait for
-/// initialize the mock instance.
-/// ```rust
-/// use std::ops::Drop;
-/// use std::process::{Child, Command};
-///
-/// test_with::runner!(net);
-///
-/// #[test_with::module]
-/// mod net {
-///     pub struct TestEnv {
-///         p: Child,
-///     }
-///
-///     impl Default for TestEnv {
-///         fn default() -> TestEnv {
-///             let p = Command::new("python")
-///                 .args(["-m", "http.server"])
-///                 .spawn()
-///                 .expect("failed to execute child");
-///             let mut count = 0;
-///             while count < 3 {
-///                 if libtest_with::reqwest::blocking::get("http://127.0.0.1:8000").is_ok() {
-///                     break;
-///                 }
-///                 std::thread::sleep(std::time::Duration::from_secs(1));
-///                 count += 1;
-///             }
-///             TestEnv { p }
-///         }
-///     }
-///
-///     impl Drop for TestEnv {
-///         fn drop(&mut self) {
-///             self.p.kill().expect("fail to kill python http.server");
-///         }
-///     }
+/// #[cfg(test)]
+/// mod tests {
 ///
-///     #[test_with::runtime_http(127.0.0.1:8000)]
-///     fn test_with_environment() {
-///         assert!(true);
+///     // Only works with group avengers
+///     #[test_with::group(avengers)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
-///
 /// ```
-/// or you can write mock struct in other place and just pass by type.
-/// ```rust
-/// use std::ops::Drop;
-/// use std::process::{Child, Command};
-///
-/// test_with::runner!(net);
-///
-/// pub struct Moc {
-///     p: Child,
-/// }
-///
-/// impl Default for Moc {
-///     fn default() -> Moc {
-///         let p = Command::new("python")
-///             .args(["-m", "http.server"])
-///             .spawn()
-///             .expect("failed to execute child");
-///         let mut count = 0;
-///         while count < 3 {
-///             if libtest_with::reqwest::blocking::get("http://127.0.0.1:8000").is_ok() {
-///                 break;
-///             }
-///             std::thread::sleep(std::time::Duration::from_secs(1));
-///             count += 1;
-///         }
-///         Moc { p }
-///     }
-/// }
-///
-/// impl Drop for Moc {
-///     fn drop(&mut self) {
-///         self.p.kill().expect("fail to kill python http.server");
-///     }
-/// }
-///
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+pub fn group(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_group_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_group_condition,
+        )
+    }
+}
+
+#[cfg(feature = "user")]
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+fn check_group_condition(group_name: String) -> (bool, String) {
+    let current_user_id = uzers::get_current_uid();
+
+    let in_group = match uzers::get_user_by_uid(current_user_id) {
+        Some(user) => {
+            let mut in_group = false;
+            // The groups can not be listed in the containers with minimal /etc/passwd or /etc/group
+            for group in user.groups().unwrap_or_default() {
+                if in_group {
+                    break;
+                }
+                in_group |= group.name().to_string_lossy() == group_name;
+            }
+            in_group
+        }
+        None => false,
+    };
+    (
+        in_group,
+        format!("because this case should run user in group {}", group_name),
+    )
+}
+
+/// Run test case when runner in group
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(user);
 /// #[test_with::module]
-/// mod net {
-///     pub type TestEnv = super::Moc;
-///
-///     #[test_with::runtime_http(127.0.0.1:8000)]
-///     fn test_with_environment() {
-///         assert!(true);
+/// mod user {
+///     // Only works with group avengers
+///     #[test_with::runtime_group(avengers)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
-/// ```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn module(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_group(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(feature = "runtime")]
+#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn module(_attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let ItemMod {
+pub fn runtime_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let group_name = attr.to_string().replace(' ', "");
+    let ItemFn {
         attrs,
         vis,
-        mod_token,
-        ident,
-        content,
-        ..
-    } = parse_macro_input!(stream as ItemMod);
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
 
-    if let Some(content) = content {
-        let content = content.1;
-        if crate::utils::has_test_cfg(&attrs) {
-            abort_call_site!("should not use `#[cfg(test)]` on the mod with `#[test_with::module]`")
-        } else {
-            let mut test_env_type = None;
-            let test_names: Vec<String> = content
-                .iter()
-                .filter_map(|c| match c {
-                    Item::Fn(ItemFn {
-                        sig: syn::Signature { ident, .. },
-                        attrs,
-                        ..
-                    }) => match crate::utils::test_with_attrs(&attrs) {
-                        (true, true, _) => abort_call_site!(
-                            "should not use #[test] for method in `#[test_with::module]`"
-                        ),
-                        (_, true, false) => abort_call_site!(
-                            "use `#[test_with::runtime_*]` for method in `#[test_with::module]`"
-                        ),
-                        (false, true, true) => Some(ident.to_string()),
-                        _ => None,
-                    },
-                    Item::Struct(ItemStruct { ident, vis, .. })
-                    | Item::Type(ItemType { ident, vis, .. }) => {
-                        if ident.to_string() == "TestEnv" {
-                            match vis {
-                                syn::Visibility::Public(_) => test_env_type = Some(ident),
-                                _ => abort_call_site!("TestEnv should be pub for testing"),
-                            }
-                        }
-                        None
-                    }
-                    _ => None,
-                })
-                .collect();
-            let check_names: Vec<syn::Ident> = test_names
-                .iter()
-                .map(|c| {
-                    syn::Ident::new(
-                        &format!("_check_{}", c.to_string()),
-                        proc_macro2::Span::call_site(),
-                    )
-                })
-                .collect();
-            if let Some(test_env_type) = test_env_type {
-                quote::quote! {
-                    #(#attrs)*
-                    #vis #mod_token #ident {
-                        use super::*;
-                        pub fn _runtime_tests() -> (Option<#test_env_type>, Vec<libtest_with::Trial>) {
-                            use libtest_with::Trial;
-                            (
-                                Some(#test_env_type::default()),
-                                vec![
-                                    #(Trial::test(#test_names, #check_names),)*
-                                ]
-                            )
+    quote::quote! {
+
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let current_user_id = libtest_with::uzers::get_current_uid();
+            let in_group = match libtest_with::uzers::get_user_by_uid(current_user_id) {
+                Some(user) => {
+                    let mut in_group = false;
+                    // The groups can not be listed in the containers with minimal /etc/passwd or /etc/group
+                    for group in user.groups().unwrap_or_default() {
+                        if in_group {
+                            break;
                         }
-                        #(#content)*
+                        in_group |= group.name().to_string_lossy() == #group_name;
                     }
+                    in_group
                 }
-                .into()
+                None => false,
+            };
+
+            if in_group {
+                #ident();
+                Ok(())
             } else {
-                quote::quote! {
-                    #(#attrs)*
-                    #vis #mod_token #ident {
-                        use super::*;
-                        pub fn _runtime_tests() -> (Option<()>, Vec<libtest_with::Trial>) {
-                            use libtest_with::Trial;
-                            (
-                                None,
-                                vec![
-                                    #(Trial::test(#test_names, #check_names),)*
-                                ]
-                            )
-                        }
-                        #(#content)*
-                    }
-                }
-                .into()
+                Err(_ignore_reason(format_args!(
+                    "because this case should run user in group {}",
+                    #group_name
+                )))
             }
         }
-    } else {
-        abort_call_site!("should use on mod with context")
+
+        #(#attrs)*
+        #vis #sig #block
     }
+    .into()
 }
 
-/// Ignore test case when function return some reason
-/// The function should be `fn() -> Option<String>`
+/// Run test case when runner is specific user
+///
 /// ```
-/// test_with::runner!(custom_mod);
+/// #[cfg(test)]
+/// mod tests {
 ///
-/// fn something_happened() -> Option<String> {
-///     Some("because something happened".to_string())
+///     // Only works with user
+///     #[test_with::user(spider)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
 /// }
-///
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+pub fn user(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_user_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_user_condition,
+        )
+    }
+}
+
+#[cfg(feature = "user")]
+#[cfg(all(feature = "user", not(target_os = "windows")))]
+fn check_user_condition(user_name: String) -> (bool, String) {
+    let is_user = match uzers::get_current_username() {
+        Some(uname) => uname.to_string_lossy() == user_name,
+        None => false,
+    };
+    (
+        is_user,
+        format!("because this case should run with user {}", user_name),
+    )
+}
+
+/// Run test case when runner is specific user
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(user);
 /// #[test_with::module]
-/// mod custom_mod {
-/// #[test_with::runtime_ignore_if(something_happened)]
-/// fn test_ignored() {
-///     assert!(false);
+/// mod user {
+///     // Only works with user
+///     #[test_with::runtime_user(spider)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
 ///     }
 /// }
-/// ```
 #[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_ignore_if(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+pub fn runtime_user(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
     panic!("should be used with runtime feature")
 }
-#[cfg(feature = "runtime")]
+#[cfg(all(feature = "runtime", feature = "user", not(target_os = "windows")))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn runtime_ignore_if(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    let ignore_function = syn::Ident::new(
-        &attr.to_string().replace(' ', ""),
-        proc_macro2::Span::call_site(),
-    );
+pub fn runtime_user(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let user_name = attr.to_string().replace(' ', "");
     let ItemFn {
         attrs,
         vis,
@@ -2525,13 +3344,23 @@ pub fn runtime_ignore_if(attr: TokenStream, stream: TokenStream) -> TokenStream
         &format!("_check_{}", ident.to_string()),
         proc_macro2::Span::call_site(),
     );
+
     quote::quote! {
+
         fn #check_ident() -> Result<(), libtest_with::Failed> {
-            if let Some(msg) = #ignore_function() {
-                Err(format!("{}{msg}", libtest_with::RUNTIME_IGNORE_PREFIX).into())
-            } else {
+            let is_user = match libtest_with::uzers::get_current_username() {
+                Some(uname) => uname.to_string_lossy() == #user_name,
+                None => false,
+            };
+
+            if is_user {
                 #ident();
                 Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because this case should run with user {}",
+                    #user_name
+                )))
             }
         }
 
@@ -2541,332 +3370,4798 @@ pub fn runtime_ignore_if(attr: TokenStream, stream: TokenStream) -> TokenStream
     .into()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{check_env_condition, check_no_env_condition};
+/// The user/group conditions are not supported on Windows, and the test cases with them are
+/// always ignored, such that the cross-platform test crates still compile.
+#[cfg(all(feature = "user", target_os = "windows"))]
+fn check_user_unsupported_condition(_attr_str: String) -> (bool, String) {
+    (
+        false,
+        "because user/group conditions unsupported on Windows".into(),
+    )
+}
 
-    mod env_macro {
-        use super::*;
+#[cfg(all(feature = "user", target_os = "windows"))]
+fn user_unsupported_macro(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_user_unsupported_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_user_unsupported_condition,
+        )
+    }
+}
 
-        #[test]
-        fn single_env_var_should_be_not_set() {
-            //* Given
-            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+/// Run test case when runner is root, and it is always ignored on Windows
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", target_os = "windows"))]
+pub fn root(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    user_unsupported_macro(attr, stream)
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = env_var.to_string();
+/// Run test case when runner is effective root, and it is always ignored on Windows
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", target_os = "windows"))]
+pub fn effective_root(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    user_unsupported_macro(attr, stream)
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+/// Run test case when runner in group, and it is always ignored on Windows
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", target_os = "windows"))]
+pub fn group(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    user_unsupported_macro(attr, stream)
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(ignore_msg.contains(env_var));
+/// Run test case when runner is specific user, and it is always ignored on Windows
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(all(feature = "user", target_os = "windows"))]
+pub fn user(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    user_unsupported_macro(attr, stream)
+}
+
+#[cfg(all(feature = "runtime", feature = "user", target_os = "windows"))]
+fn runtime_user_unsupported_macro(stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let _ = #ident;
+            Err(_ignore_reason(format_args!("because user/group conditions unsupported on Windows")))
         }
 
-        #[test]
-        fn multiple_env_vars_should_not_be_set() {
-            //* Given
-            let env_var1 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-            let env_var2 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}", env_var1, env_var2);
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+/// Run test case when runner is root, and it is always ignored on Windows
+#[cfg(all(feature = "runtime", feature = "user", target_os = "windows"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    runtime_user_unsupported_macro(stream)
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(ignore_msg.contains(env_var1));
-            assert!(ignore_msg.contains(env_var2));
-        }
+/// Run test case when runner is effective root, and it is always ignored on Windows
+#[cfg(all(feature = "runtime", feature = "user", target_os = "windows"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_effective_root(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    runtime_user_unsupported_macro(stream)
+}
 
-        #[test]
-        fn single_env_var_should_be_set() {
-            //* Given
-            let env_var = "PATH";
+/// Run test case when runner in group, and it is always ignored on Windows
+#[cfg(all(feature = "runtime", feature = "user", target_os = "windows"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_group(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    runtime_user_unsupported_macro(stream)
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = env_var.to_string();
+/// Run test case when runner is specific user, and it is always ignored on Windows
+#[cfg(all(feature = "runtime", feature = "user", target_os = "windows"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_user(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    runtime_user_unsupported_macro(stream)
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+/// Run test case when the runner has the capability in the effective capability set
+/// Many operations, ex: opening a raw socket, only need a capability rather than full root.
+/// The capabilities are only supported on Linux, the test cases are always ignored on other
+/// platforms.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with CAP_SYS_MODULE
+///     #[test_with::capability(CAP_SYS_MODULE)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "capability")]
+pub fn capability(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_capability_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_capability_condition,
+        )
+    }
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(!ignore_msg.contains(env_var));
+/// Run test case when the runner has the capability in the effective capability set
+/// The capability is checked with the `CapEff` field of `/proc/self/status` when the example
+/// running.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(capability);
+/// #[test_with::module]
+/// mod capability {
+///     // Only works with CAP_SYS_MODULE
+///     #[test_with::runtime_capability(CAP_SYS_MODULE)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_capability(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "capability"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_capability(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr.to_string().replace(' ', "");
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    #[cfg(target_os = "linux")]
+    let check_tokens = {
+        let (cap_index, cap_name) = capability_index(&attr_str);
+        quote::quote! {
+            let has_cap = std::fs::read_to_string("/proc/self/status")
+                .ok()
+                .and_then(|status| {
+                    status
+                        .lines()
+                        .find_map(|l| l.strip_prefix("CapEff:"))
+                        .and_then(|v| u64::from_str_radix(v.trim(), 16).ok())
+                })
+                .map(|cap_eff| cap_eff & (1u64 << #cap_index) != 0)
+                .unwrap_or(false);
+            if has_cap {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because missing capability {}", #cap_name)))
+            }
+        }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let check_tokens = {
+        let _ = attr_str;
+        quote::quote! {
+            let _ = #ident;
+            Err(_ignore_reason(format_args!("because capabilities are only supported on Linux")))
         }
+    };
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars containing spaces and newlines.
-        ///
-        /// ```no_run
-        /// #[test_with::env(
-        ///   PATH,
-        ///   HOME
-        /// )]
-        /// #[test]
-        /// fn some_test() {}
-        #[test]
-        fn multiple_env_vars_should_be_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "HOME";
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            #check_tokens
+        }
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("\t{},\n\t{}\n", env_var1, env_var2);
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+/// Run test case when memory size enough
+/// The size can be described in decimal units, ex: `1GB` is 10^9 bytes, or in binary units, ex:
+/// `1GiB` is 2^30 bytes.
+/// The memory limit of cgroup v1 or v2, ex: the limit of the container, is used when it is lower
+/// than the memory of the host, and `source=host` forces the memory of the host, ex:
+/// `#[test_with::mem(2GB, source=host)]`.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough memory size
+///     #[test_with::mem(100GB)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_mem_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_mem_condition,
+        )
+    }
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(!ignore_msg.contains(env_var1));
-            assert!(!ignore_msg.contains(env_var2));
-        }
+/// Parse the size limitation of the resource macros, the unit should be explicit with the decimal
+/// units (KB, MB, GB, TB) or binary units (KiB, MiB, GiB, TiB), the unit without `B`, ex: `1G`, is
+/// ambiguous and not allowed.
+#[cfg(feature = "resource")]
+fn parse_size_limitation(size_str: &str) -> byte_unit::Byte {
+    let unit = size_str.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    if unit.len() == 1 && "kmgtpe".contains(unit.to_ascii_lowercase().as_str()) {
+        abort_call_site!(
+            "the unit of `{}` is ambiguous, use decimal units (KB, MB, GB, TB) or binary units (KiB, MiB, GiB, TiB)",
+            size_str
+        )
+    }
+    match byte_unit::Byte::parse_str(size_str, true) {
+        Ok(b) => b,
+        Err(_) => abort_call_site!(
+            "`{}` is not a correct size, use decimal units (KB, MB, GB, TB) or binary units (KiB, MiB, GiB, TiB)",
+            size_str
+        ),
+    }
+}
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars and one of them is not set.
-        #[test]
-        fn multiple_env_vars_but_one_is_not_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "HOME";
-            let env_var3 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+#[cfg(feature = "resource")]
+fn check_mem_condition(attr_str: String) -> (bool, String) {
+    let (mem_size_str, source) = crate::cgroup::split_memory_source_option(&attr_str);
+    let sys = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::nothing()
+            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_swap()),
+    );
+    let total_memory = crate::cgroup::total_memory(sys.total_memory(), source);
+    let mem_size = match byte_unit::Byte::parse_str(format!("{} B", total_memory), false) {
+        Ok(b) => b,
+        Err(_) => abort_call_site!("memory size description is not correct"),
+    };
+    let mem_size_limitation = parse_size_limitation(mem_size_str);
+    (
+        mem_size >= mem_size_limitation,
+        format!("because the memory less than {}", mem_size_str),
+    )
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+/// Run test case when the example running and memory size enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough memory size
+///     #[test_with::runtime_mem(100GB)]
+///     fn test_ignored_mem_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr.to_string().replace(' ', "");
+    let (mem_limitation_str, source) = crate::cgroup::split_memory_source_option(&attr_str);
+    parse_size_limitation(mem_limitation_str);
+    let total_memory = crate::cgroup::total_memory_tokens(source);
 
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(!ignore_msg.contains(env_var1));
-            assert!(!ignore_msg.contains(env_var2));
-            assert!(ignore_msg.contains(env_var3));
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
+            );
+            let host_memory = sys.total_memory();
+            let total_memory = #total_memory;
+            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", total_memory), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system memory size can not get"),
+            };
+            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
+            if  mem_size >= mem_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the memory less than {}",
+                    #mem_limitation_str
+                )))
+            }
         }
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars and various of them are not set.
-        #[test]
-        fn multiple_env_vars_and_various_not_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-            let env_var3 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
-
-            //* When
-            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+        #(#attrs)*
+        #vis #sig #block
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the missing env var names
-            assert!(!ignore_msg.contains(env_var1));
-            assert!(ignore_msg.contains(env_var2));
-            assert!(ignore_msg.contains(env_var3));
-        }
     }
+    .into()
+}
 
-    mod no_env_macro {
-        use super::*;
+/// Run test case when free memory size enough
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough free memory size
+///     #[test_with::free_mem(100GB)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn free_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_free_mem_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_free_mem_condition,
+        )
+    }
+}
 
-        #[test]
-        fn single_env_var_not_set() {
-            //* Given
-            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+#[cfg(feature = "resource")]
+fn memory_system() -> sysinfo::System {
+    sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::nothing()
+            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_ram()),
+    )
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = env_var.to_string();
+#[cfg(feature = "resource")]
+fn check_memory_size(mem_size_str: &str, memory: u64) -> (bool, String) {
+    let mem_size = match byte_unit::Byte::parse_str(format!("{} B", memory), false) {
+        Ok(b) => b,
+        Err(_) => abort_call_site!("memory size description is not correct"),
+    };
+    (
+        mem_size >= parse_size_limitation(mem_size_str),
+        format!("because the memory less than {}", mem_size_str),
+    )
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+#[cfg(feature = "resource")]
+fn check_free_mem_condition(mem_size_str: String) -> (bool, String) {
+    check_memory_size(&mem_size_str, memory_system().free_memory())
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(!ignore_msg.contains(env_var));
-        }
+/// Run test case when available memory size enough
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough available memory size
+///     #[test_with::available_mem(100GB)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn available_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_available_mem_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_available_mem_condition,
+        )
+    }
+}
 
-        #[test]
-        fn multiple_env_vars_not_set() {
-            //* Given
-            let env_var1 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-            let env_var2 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+#[cfg(feature = "resource")]
+fn check_available_mem_condition(mem_size_str: String) -> (bool, String) {
+    check_memory_size(&mem_size_str, memory_system().available_memory())
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}", env_var1, env_var2);
+/// Run test case when the example running and free memory size enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough free memory size
+///     #[test_with::runtime_free_mem(100GB)]
+///     fn test_ignored_free_mem_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let mem_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&mem_limitation_str);
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(!ignore_msg.contains(env_var1));
-            assert!(!ignore_msg.contains(env_var2));
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
+            );
+            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.free_memory()), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system memory size can not get"),
+            };
+            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
+            if  mem_size >= mem_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the memory less than {}",
+                    #mem_limitation_str
+                )))
+            }
         }
 
-        #[test]
-        fn single_env_var_set() {
-            //* Given
-            let env_var = "PATH";
+        #(#attrs)*
+        #vis #sig #block
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = env_var.to_string();
+    }
+    .into()
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+/// Run test case when the example running and available memory size enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough available memory size
+///     #[test_with::runtime_available_mem(100GB)]
+///     fn test_ignored_available_mem_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_available_mem(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_available_mem(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let mem_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&mem_limitation_str);
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(ignore_msg.contains(env_var));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_ram()),
+            );
+            let mem_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.available_memory()), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system memory size can not get"),
+            };
+            let mem_size_limitation = libtest_with::byte_unit::Byte::parse_str(#mem_limitation_str, true).expect("mem limitation should correct");
+            if  mem_size >= mem_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the memory less than {}",
+                    #mem_limitation_str
+                )))
+            }
         }
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars containing spaces and newlines.
-        ///
-        /// ```no_run
-        /// #[test_with::no_env(
-        ///   PATH,
-        ///   HOME
-        /// )]
-        /// #[test]
-        /// fn some_test() {}
-        #[test]
-        fn multiple_env_vars_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "HOME";
+        #(#attrs)*
+        #vis #sig #block
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("\t{},\n\t{}\n", env_var1, env_var2);
+    }
+    .into()
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+/// Run test case when swap size enough
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough swap size
+///     #[test_with::swap(100GB)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_swap_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_swap_condition,
+        )
+    }
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(ignore_msg.contains(env_var1));
-            assert!(ignore_msg.contains(env_var2));
-        }
+#[cfg(feature = "resource")]
+fn total_swap_size() -> byte_unit::Byte {
+    let sys = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::nothing()
+            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_swap()),
+    );
+    match byte_unit::Byte::parse_str(format!("{} B", sys.total_swap()), false) {
+        Ok(b) => b,
+        Err(_) => abort_call_site!("Swap size description is not correct"),
+    }
+}
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars and one of them is set.
-        #[test]
-        fn multiple_env_vars_but_one_is_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
-            let env_var3 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+#[cfg(feature = "resource")]
+fn check_swap_condition(swap_size_str: String) -> (bool, String) {
+    let swap_size_limitation = parse_size_limitation(&swap_size_str);
+    (
+        total_swap_size() >= swap_size_limitation,
+        format!("because the swap less than {}", swap_size_str),
+    )
+}
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+/// Run test case when the example running and swap enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough swap size
+///     #[test_with::runtime_swap(100GB)]
+///     fn test_ignored_swap_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let swap_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&swap_limitation_str);
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(ignore_msg.contains(env_var1));
-            assert!(!ignore_msg.contains(env_var2));
-            assert!(!ignore_msg.contains(env_var3));
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
+            );
+            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.total_swap()), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system swap size can not get"),
+            };
+            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
+            if  swap_size >= swap_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the swap less than {}",
+                    #swap_limitation_str
+                )))
+            }
         }
 
-        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
-        /// when the attribute string contains multiple env vars and various of them are set.
-        #[test]
-        fn multiple_env_vars_and_various_are_set() {
-            //* Given
-            let env_var1 = "PATH";
-            let env_var2 = "HOME";
-            let env_var3 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+        #(#attrs)*
+        #vis #sig #block
 
-            // The `test_with::env(<attr_str>)` macro arguments
-            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+    }
+    .into()
+}
 
-            //* When
-            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+/// Run test case when the swap size is not more than the limitation, for the cases reproduced
+/// only on the system with low swap
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with swap size not more than 999GB
+///     #[test_with::max_swap(999GB)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn max_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_max_swap_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_max_swap_condition,
+        )
+    }
+}
 
-            //* Then
-            // Assert if the test should be ignored
-            assert!(!is_ok);
-            // Assert the ignore message should contain only the found env var names
-            assert!(ignore_msg.contains(env_var1));
-            assert!(ignore_msg.contains(env_var2));
-            assert!(!ignore_msg.contains(env_var3));
+#[cfg(feature = "resource")]
+fn check_max_swap_condition(swap_size_str: String) -> (bool, String) {
+    let swap_size_limitation = parse_size_limitation(&swap_size_str);
+    (
+        total_swap_size() <= swap_size_limitation,
+        format!("because swap exceeds {}", swap_size_str),
+    )
+}
+
+/// Run test case when the example running and the swap size is not more than the limitation
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with swap size not more than 999GB
+///     #[test_with::runtime_max_swap(999GB)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_max_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_max_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let swap_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&swap_limitation_str);
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
+            );
+            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.total_swap()), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system swap size can not get"),
+            };
+            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
+            if  swap_size <= swap_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because swap exceeds {}",
+                    #swap_limitation_str
+                )))
+            }
         }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when the example running and free swap enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough free swap size
+///     #[test_with::runtime_free_swap(100GB)]
+///     fn test_ignored_free_swap_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let swap_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&swap_limitation_str);
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
+            );
+            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", sys.free_swap()), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system swap size can not get"),
+            };
+            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
+            if  swap_size >= swap_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the swap less than {}",
+                    #swap_limitation_str
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when the example running and available swap enough
+/// The available swap is the total swap minus the used swap reported by the system.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough available swap size
+///     #[test_with::runtime_available_swap(100GB)]
+///     fn test_ignored_available_swap_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_available_swap(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_available_swap(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let swap_limitation_str = attr.to_string().replace(' ', "");
+    parse_size_limitation(&swap_limitation_str);
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
+            );
+            let available_swap = sys.total_swap().saturating_sub(sys.used_swap());
+            let swap_size = match libtest_with::byte_unit::Byte::parse_str(format!("{} B", available_swap), false) {
+                Ok(b) => b,
+                Err(_) => panic!("system swap size can not get"),
+            };
+            let swap_size_limitation = libtest_with::byte_unit::Byte::parse_str(#swap_limitation_str, true).expect("swap limitation should correct");
+            if  swap_size >= swap_size_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the available swap less than {}",
+                    #swap_limitation_str
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Parse the percentage, ex: `50` or `50%`, and it should be in 0..=100.
+#[cfg(feature = "resource")]
+fn parse_percent_limitation(percent_str: &str) -> u64 {
+    match percent_str.trim_end_matches('%').parse::<u64>() {
+        Ok(percent) if percent <= 100 => percent,
+        _ => abort_call_site!("`{}` should be a percentage in 0..=100", percent_str),
+    }
+}
+
+/// Run test case when the percentage of free swap is enough
+/// The test case is ignored if there is no swap, unless the percentage is zero.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when at least half of the swap is free
+///     #[test_with::free_swap_percent(50)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn free_swap_percent(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_free_swap_percent_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_free_swap_percent_condition,
+        )
+    }
+}
+
+#[cfg(feature = "resource")]
+fn check_free_swap_percent_condition(percent_str: String) -> (bool, String) {
+    let percent_limitation = parse_percent_limitation(&percent_str);
+    let sys = sysinfo::System::new_with_specifics(
+        sysinfo::RefreshKind::nothing()
+            .with_memory(sysinfo::MemoryRefreshKind::nothing().with_swap()),
+    );
+    let free_percent = (sys.free_swap() * 100)
+        .checked_div(sys.total_swap())
+        .unwrap_or(0);
+    (
+        free_percent >= percent_limitation,
+        format!(
+            "because the free swap {}% less than {}%",
+            free_percent, percent_limitation
+        ),
+    )
+}
+
+/// Run test case when the example running and the percentage of free swap is enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough free swap percentage
+///     #[test_with::runtime_free_swap_percent(100)]
+///     fn test_ignored_free_swap_percent_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_swap_percent(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_free_swap_percent(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let percent_limitation = parse_percent_limitation(&attr.to_string().replace(' ', ""));
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let sys = libtest_with::sysinfo::System::new_with_specifics(
+                libtest_with::sysinfo::RefreshKind::nothing().with_memory(libtest_with::sysinfo::MemoryRefreshKind::nothing().with_swap()),
+            );
+            let free_percent = (sys.free_swap() * 100).checked_div(sys.total_swap()).unwrap_or(0);
+            if free_percent >= #percent_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the free swap {}% less than {}%",
+                    free_percent, #percent_limitation
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when cpu core enough
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough cpu core
+///     #[test_with::cpu_core(32)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_cpu_core_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_cpu_core_condition,
+        )
+    }
+}
+
+#[cfg(feature = "resource")]
+fn check_cpu_core_condition(core_limitation_str: String) -> (bool, String) {
+    (
+        match core_limitation_str.parse::<usize>() {
+            Ok(c) => num_cpus::get() >= c,
+            Err(_) => abort_call_site!("core limitation is incorrect"),
+        },
+        format!("because the cpu core less than {}", core_limitation_str),
+    )
+}
+
+/// Run test case when cpu core enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough cpu core
+///     #[test_with::runtime_cpu_core(32)]
+///     fn test_ignored_core_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_cpu_core(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr.to_string().replace(' ', "");
+    let core_limitation = match attr_str.parse::<usize>() {
+        Ok(c) => c,
+        Err(_) => abort_call_site!("core limitation is incorrect"),
+    };
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if libtest_with::num_cpus::get() >= #core_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the cpu core less than {}",
+                    #core_limitation
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when physical cpu core enough
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with enough cpu core
+///     #[test_with::phy_core(32)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn phy_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_phy_core_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_phy_core_condition,
+        )
+    }
+}
+
+#[cfg(feature = "resource")]
+fn check_phy_core_condition(core_limitation_str: String) -> (bool, String) {
+    (
+        match core_limitation_str.parse::<usize>() {
+            Ok(c) => num_cpus::get_physical() >= c,
+            Err(_) => abort_call_site!("physical core limitation is incorrect"),
+        },
+        format!(
+            "because the physical cpu core less than {}",
+            core_limitation_str
+        ),
+    )
+}
+
+/// Run test case when physical core enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with enough physical cpu core
+///     #[test_with::runtime_phy_cpu_core(32)]
+///     fn test_ignored_phy_core_not_enough() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_phy_cpu_core(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_phy_cpu_core(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr.to_string().replace(' ', "");
+    let core_limitation = match attr_str.parse::<usize>() {
+        Ok(c) => c,
+        Err(_) => abort_call_site!("physical core limitation is incorrect"),
+    };
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if libtest_with::num_cpus::get_physical() >= #core_limitation {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the physical cpu core less than {}",
+                    #core_limitation
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when the simultaneous multithreading (SMT), ex: Hyper-Threading, is enabled, that
+/// is, there are more logical cpu cores than physical ones.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with SMT enabled
+///     #[test_with::smt]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn smt(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_smt_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_smt_condition,
+        )
+    }
+}
+
+#[cfg(feature = "resource")]
+fn check_smt_condition(_attr_str: String) -> (bool, String) {
+    (
+        num_cpus::get() > num_cpus::get_physical(),
+        "because SMT is not enabled".to_string(),
+    )
+}
+
+/// Run test case when the example running and the simultaneous multithreading (SMT) is enabled
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with SMT enabled
+///     #[test_with::runtime_smt]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_smt(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_smt(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if libtest_with::num_cpus::get() > libtest_with::num_cpus::get_physical() {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because SMT is not enabled")))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the available parallelism is enough, which is reported by
+/// `std::thread::available_parallelism`.  Different from [macro@cpu_core] and [macro@phy_core]
+/// counting the cpu cores of the machine, the available parallelism also respects the cgroup cpu
+/// quota and the cpu affinity, so it is more accurate for the concurrency test cases in containers.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when 32 threads can run in parallel
+///     #[test_with::parallelism(32)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn parallelism(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_parallelism_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_parallelism_condition,
+        )
+    }
+}
+
+#[cfg(feature = "resource")]
+fn check_parallelism_condition(attr_str: String) -> (bool, String) {
+    let required = match attr_str.trim().parse::<usize>() {
+        Ok(required) if required > 0 => required,
+        _ => abort_call_site!("the parallelism should be a positive number, ex: `parallelism(8)`"),
+    };
+    match std::thread::available_parallelism() {
+        Ok(parallelism) if parallelism.get() >= required => (true, String::new()),
+        Ok(parallelism) => (
+            false,
+            format!(
+                "because the available parallelism {} is less than {}",
+                parallelism, required
+            ),
+        ),
+        Err(_) => (
+            false,
+            "because the available parallelism is unknown".to_string(),
+        ),
+    }
+}
+
+/// Run test case when the example running and the available parallelism is enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works when 32 threads can run in parallel
+///     #[test_with::runtime_parallelism(32)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_parallelism(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_parallelism(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let required = match attr.to_string().trim().parse::<usize>() {
+        Ok(required) if required > 0 => required,
+        _ => abort_call_site!("the parallelism should be a positive number, ex: `parallelism(8)`"),
+    };
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            match std::thread::available_parallelism() {
+                Ok(parallelism) if parallelism.get() >= #required => {
+                    #ident();
+                    Ok(())
+                }
+                Ok(parallelism) => Err(_ignore_reason(format_args!(
+                    "because the available parallelism {} is less than {}",
+                    parallelism, #required
+                ))),
+                Err(_) => Err(_ignore_reason(format_args!(
+                    "because the available parallelism is unknown"
+                ))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the soft limit of the open files is not less than the number, ex: the test
+/// opens many sockets, and fails with "too many open files" when the limit is low.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when the open files limit is enough
+///     #[test_with::min_open_files(1048576000)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn min_open_files(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_ulimit_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_ulimit_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the soft limit of the open files is not less than
+/// the number.  The limit is read with `ulimit -n` when the example running.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works when the open files limit is enough
+///     #[test_with::runtime_min_open_files(1048576000)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_open_files(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_open_files(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let min_open_files = parse_open_files_limitation(&attr_to_string(attr));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if !cfg!(unix) {
+                return Err(_ignore_reason(format_args!("because the open files limit is only supported on Unix")));
+            }
+            // the limit is unknown or unlimited if the output is not a number
+            let limit = std::process::Command::new("sh")
+                .args(["-c", "ulimit -n"])
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().parse::<u64>().ok());
+            match limit {
+                Some(limit) if limit < #min_open_files => {
+                    Err(_ignore_reason(format_args!(
+                        "because the open files limit {} is less than {}",
+                        limit, #min_open_files
+                    )))
+                }
+                _ => {
+                    #ident();
+                    Ok(())
+                }
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the Linux kernel version meets the requirement, ex: the test case needs a
+/// syscall of the newer kernel.  The comparator can be `>=`, `<=`, `==`, `>` or `<`, and it is
+/// `>=` if absent.  The test case is always ignored on the other operating systems.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works on the kernel 999.0 or newer
+///     #[test_with::kernel(>= 999.0)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn kernel(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_kernel_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_kernel_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the Linux kernel version meets the requirement.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works on the kernel 999.0 or newer
+///     #[test_with::runtime_kernel(>= 999.0)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_kernel(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_kernel(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let (comparator, (major, minor, patch), version_str) =
+        parse_kernel_requirement(&attr_to_string(attr));
+    let op: proc_macro2::TokenStream = comparator.parse().expect("comparator is a valid token");
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if !cfg!(target_os = "linux") {
+                return Err(_ignore_reason(format_args!("because the kernel version is only checked on Linux")));
+            }
+            let kernel = libtest_with::sysinfo::System::kernel_version().unwrap_or_default();
+            // the parts after the patch version are ignored, ex: `6.1.0-18-amd64`
+            let version = kernel.split(['-', '+']).next().and_then(|release| {
+                let mut parts = release.split('.').map(|part| part.parse::<u64>().ok());
+                Some((
+                    parts.next()??,
+                    parts.next().unwrap_or(Some(0))?,
+                    parts.next().unwrap_or(Some(0))?,
+                ))
+            });
+            match version {
+                Some(version) if version #op (#major, #minor, #patch) => {
+                    #ident();
+                    Ok(())
+                }
+                Some(_) => Err(_ignore_reason(format_args!(
+                    "because the kernel version {} is not {} {}",
+                    kernel, #comparator, #version_str
+                ))),
+                None => Err(_ignore_reason(format_args!("because the kernel version is unknown"))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the available entropy of the kernel is not less than the bits, ex: the
+/// crypto test blocks on a fresh VM with low entropy.  The entropy is read from
+/// `/proc/sys/kernel/random/entropy_avail`, and the test case is always ignored on the other
+/// operating systems.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with the huge entropy
+///     #[test_with::min_entropy(1048576000)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "resource")]
+pub fn min_entropy(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::entropy::check_entropy_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::entropy::check_entropy_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the available entropy is not less than the bits.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(resource);
+/// #[test_with::module]
+/// mod resource {
+///     // Only works with the huge entropy
+///     #[test_with::runtime_min_entropy(1048576000)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_entropy(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "resource"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_min_entropy(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let required = crate::entropy::parse_entropy_requirement(&attr_to_string(attr));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if !cfg!(target_os = "linux") {
+                return Err(_ignore_reason(format_args!("because the available entropy is only checked on Linux")));
+            }
+            let entropy = std::fs::read_to_string("/proc/sys/kernel/random/entropy_avail")
+                .ok()
+                .and_then(|content| content.trim().parse::<u64>().ok());
+            match entropy {
+                Some(entropy) if entropy >= #required => {
+                    #ident();
+                    Ok(())
+                }
+                Some(entropy) => Err(_ignore_reason(format_args!(
+                    "because the available entropy {} is less than {}",
+                    entropy, #required
+                ))),
+                None => Err(_ignore_reason(format_args!("because the available entropy is unknown"))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when a display is available, ex: the GUI or headed browser test.  The display is
+/// available when `DISPLAY` or `WAYLAND_DISPLAY` is set, and it is always available on macOS and
+/// Windows.
+///
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with a display
+///     #[test_with::display]
+///     #[test]
+///     fn test_headed_browser() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn display(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::display::check_display_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::display::check_display_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and a display is available.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(gui);
+/// #[test_with::module]
+/// mod gui {
+///     // Only works with a display
+///     #[test_with::runtime_display]
+///     fn test_headed_browser() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_display(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_display(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let has_display = crate::display::display_check_tokens();
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if #has_display {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because no display available")))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the number of monitors is enough, ex: the test of a multi-monitor layout.
+/// The monitors are listed with `xrandr --listmonitors` when `DISPLAY` is set, otherwise the
+/// connected outputs in `/sys/class/drm` are counted.  The monitors are only counted on Linux, and
+/// the test case is ignored "because the number of monitors is unknown" when neither of them is
+/// available, ex: in a container.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with two monitors
+///     #[test_with::monitors(2)]
+///     #[test]
+///     fn test_dual_monitor_layout() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn monitors(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::monitors::check_monitors_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::monitors::check_monitors_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the number of monitors is enough
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(gui);
+/// #[test_with::module]
+/// mod gui {
+///     // Only works with two monitors
+///     #[test_with::runtime_monitors(2)]
+///     fn test_dual_monitor_layout() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_monitors(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_monitors(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let required = crate::monitors::parse_monitors(&attr.to_string());
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let check = crate::monitors::monitors_check_tokens(required);
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            match #check {
+                None => {
+                    #ident();
+                    Ok(())
+                }
+                Some(reason) => Err(_ignore_reason(format_args!("{}", reason))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the systemd units are active, ex: the database service is running but the
+/// listening port is unknown.  The state is checked with `systemctl is-active`, and the test case is
+/// always ignored on the other operating systems than Linux.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when postgresql.service is active
+///     #[test_with::systemd_active(postgresql.service)]
+///     #[test]
+///     fn test_database() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn systemd_active(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::systemd::check_systemd_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::systemd::check_systemd_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the systemd units are active.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(service);
+/// #[test_with::module]
+/// mod service {
+///     #[test_with::runtime_systemd_active(postgresql.service)]
+///     fn test_database() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_systemd_active(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_systemd_active(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let units = crate::systemd::parse_units(&attr_to_string(attr));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if !cfg!(target_os = "linux") {
+                return Err(_ignore_reason(format_args!("because systemd units are only checked on Linux")));
+            }
+            let mut inactive_units = vec![];
+            #(
+                let state = std::process::Command::new("systemctl")
+                    .args(["is-active", #units])
+                    .output()
+                    .ok()
+                    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                    .filter(|state| !state.is_empty())
+                    .unwrap_or_else(|| "unknown".to_string());
+                if state != "active" {
+                    inactive_units.push(format!("{} is {}", #units, state));
+                }
+            )*
+            match inactive_units.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!("because {}", inactive_units[0]))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following units are not active: \n{}\n",
+                    inactive_units.join("\n")
+                ))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the command succeeds, ex: `pg_isready -q` exits with 0, as an escape hatch
+/// for the conditions which are not provided by this crate.  The test case is ignored with the
+/// command and the exit status when the command fails, can not be run, or does not finish in the
+/// timeout, which is 5 seconds and can be changed with `timeout=SECS`.
+///
+/// The command line is split into words and run directly without a shell, the words are separated
+/// by whitespace, the text in single quotes is kept as it is, and in double quotes or outside quotes
+/// a backslash escapes the next character, so variables, globs, pipes and redirections are not
+/// expanded.  With the `shell` option, the command line is passed to `sh -c` (`cmd /C` on Windows)
+/// as it is.  The output of the command is discarded.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when the database accepts connections
+///     #[test_with::command("pg_isready -q")]
+///     #[test]
+///     fn test_database() {
+///         assert!(true);
+///     }
+///
+///     // Run by the shell, and wait at most 10 seconds
+///     #[test_with::command("docker ps | grep -q postgres", shell, timeout=10)]
+///     #[test]
+///     fn test_container() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn command(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::command::check_command_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::command::check_command_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the command succeeds, the command line is split or
+/// run by the shell in the same way as [macro@command].
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(service);
+/// #[test_with::module]
+/// mod service {
+///     #[test_with::runtime_command("pg_isready -q")]
+///     fn test_database() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_command(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_command(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let check =
+        crate::command::command_check_tokens(&crate::command::parse_command(&attr_to_string(attr)));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            match #check {
+                None => {
+                    #ident();
+                    Ok(())
+                }
+                Some(reason) => Err(_ignore_reason(format_args!("{}", reason))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the output of the command contains the text, ex: `nvidia-smi -L` lists the
+/// GPU, which generalizes the hardware and service detection.  The stdout of the command is
+/// searched for the text regardless of the exit status, and only the first 1 MiB of the output is
+/// kept.  The command line is split or run by the shell, and the timeout is set, in the same way as
+/// [macro@command].
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when the A100 GPU is listed
+///     #[test_with::command_output("nvidia-smi -L", contains="A100")]
+///     #[test]
+///     fn test_gpu() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn command_output(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::command::check_command_output_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::command::check_command_output_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the output of the command contains the text.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(gpu);
+/// #[test_with::module]
+/// mod gpu {
+///     #[test_with::runtime_command_output("nvidia-smi -L", contains="A100")]
+///     fn test_gpu() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_command_output(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_command_output(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let check = crate::command::command_output_check_tokens(&crate::command::parse_command_output(
+        &attr_to_string(attr),
+    ));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            match #check {
+                None => {
+                    #ident();
+                    Ok(())
+                }
+                Some(reason) => Err(_ignore_reason(format_args!("{}", reason))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the shared libraries can be loaded, ex: the test case loads a plugin with
+/// `dlopen`.  The library is loaded with the search rules of the dynamic loader, so it is more
+/// precise than checking the path of the file.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when libssl.so.3 can be loaded
+///     #[test_with::library(libssl.so.3)]
+///     #[test]
+///     fn test_ssl_plugin() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[cfg(feature = "library")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn library(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::library::check_library_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::library::check_library_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the shared libraries can be loaded.
+/// The libraries are loaded when the example running, so the crate of the example should depend
+/// on `libloading`.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(plugin);
+/// #[test_with::module]
+/// mod plugin {
+///     #[test_with::runtime_library(libssl.so.3)]
+///     fn test_ssl_plugin() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_library(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "library"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_library(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let libraries = crate::library::parse_library_names(&attr_to_string(attr));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let mut unloadable = vec![];
+            #(
+                // SAFETY: the library is trusted because the test case will load it anyway
+                if unsafe { libloading::Library::new(#libraries) }.is_err() {
+                    unloadable.push(#libraries);
+                }
+            )*
+            match unloadable.len() {
+                0 => {
+                    #ident();
+                    Ok(())
+                },
+                1 => Err(_ignore_reason(format_args!(
+                    "because {} could not be loaded",
+                    unloadable[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following libraries could not be loaded: \n{}\n",
+                    unloadable.join("\n")
+                ))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the executables exist.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///     // `pwd` executable command exists
+///     #[test_with::executable(pwd)]
+///     #[test]
+///     fn test_executable() {
+///         assert!(true);
+///     }
+///
+///     // `/bin/sh` executable exists
+///     #[test_with::executable(/bin/sh)]
+///     #[test]
+///     fn test_executable_with_path() {
+///         assert!(true);
+///     }
+///
+///     // `non` does not exist
+///     #[test_with::executable(non)]
+///     #[test]
+///     fn test_non_existing_executable() {
+///         panic!("should be ignored")
+///     }
+///
+///     // `pwd` and `ls` exist
+///     #[test_with::executable(pwd, ls)]
+///     #[test]
+///     fn test_executables_too() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+#[cfg(feature = "executable")]
+pub fn executable(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_executable_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_executable_condition,
+        )
+    }
+}
+
+#[cfg(feature = "executable")]
+fn check_executable_condition(attr_str: String) -> (bool, String) {
+    let executables = split_attr_list(&attr_str);
+    let mut missing_executables = vec![];
+    for exe in executables.iter() {
+        if which(exe.trim_matches('"')).is_err() {
+            missing_executables.push(exe.to_string());
+        }
+    }
+    let ignore_msg = if missing_executables.len() == 1 {
+        format!("because executable not found: {}", missing_executables[0])
+    } else {
+        format!(
+            "because following executables not found: \n{}\n",
+            missing_executables.join("\n")
+        )
+    };
+    (missing_executables.is_empty(), ignore_msg)
+}
+
+/// Run test case when the executable existing, and the version of the executable can be required
+/// with `>=`, `<=`, `==`, `>` or `<`, ex: `ffmpeg >= 6.0`, which is the first dotted number in the
+/// output of `ffmpeg --version`.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(exe);
+/// #[test_with::module]
+/// mod exe {
+///     // `/bin/sh` executable exists
+///     #[test_with::runtime_executable(/bin/sh)]
+///     fn test_executable_with_path() {
+///         assert!(true);
+///     }
+///
+///     // `ffmpeg` 6.0 or newer exists
+///     #[test_with::runtime_executable(ffmpeg >= 6.0)]
+///     fn test_ffmpeg_version() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_executable(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "executable"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_executable(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let checks: Vec<proc_macro2::TokenStream> = split_attr_list(&attr_str)
+        .into_iter()
+        .map(executable_check_tokens)
+        .collect();
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let mut missing_executables: Vec<&str> = vec![];
+            let mut unmet_versions: Vec<String> = vec![];
+            #(#checks)*
+            match (missing_executables.len(), unmet_versions.len()) {
+                (0, 0) => {
+                    #ident();
+                    Ok(())
+                },
+                (0, 1) => Err(_ignore_reason(format_args!("because {}", unmet_versions[0]))),
+                (0, _) => Err(_ignore_reason(format_args!(
+                    "because following executables are not in the required versions:\n{}\n",
+                    unmet_versions.join(", ")
+                ))),
+                (1, _) => Err(_ignore_reason(format_args!(
+                    "because executable {} not found",
+                    missing_executables[0]
+                ))),
+                _ => Err(_ignore_reason(format_args!(
+                    "because following executables not found:\n{}\n",
+                    missing_executables.join(", ")
+                ))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+
+    }
+    .into()
+}
+
+/// Run test case when the version of the executable matches the version pinned in the file, ex:
+/// `.python-version` or `.nvmrc`.  The condition is written as `"FILE" OP EXECUTABLE`, and reads
+/// from left to right, ex: `".nvmrc" <= node` runs the test case when `node` is not older than the
+/// pinned version, and the comparator is one of `>=`, `<=`, `==`, `>` or `<`.
+///
+/// The pinned version is the first line of the file which is not empty nor a comment, without the
+/// prefix before the digits, ex: `18` of `v18` or `3.11` of `python-3.11`, and the aliases, ex:
+/// `lts/*` or `system`, are unknown versions.  The version of the executable is the first dotted
+/// number in the output of `EXECUTABLE --version`, and it is compared in the precision of the
+/// pinned version, so `3.11` in the file equals `3.11.4`.  The file is relative to the working
+/// directory, which is the package root when running `cargo test`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when `python --version` is the version in `.python-version`
+///     #[test_with::file_version(".python-version" == python)]
+///     #[test]
+///     fn test_python() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn file_version(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::file_version::check_file_version_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::file_version::check_file_version_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the version of the executable matches the version
+/// pinned in the file, in the same way as [macro@file_version].
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(pinned);
+/// #[test_with::module]
+/// mod pinned {
+///     #[test_with::runtime_file_version(".nvmrc" == node)]
+///     fn test_node() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_file_version(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_file_version(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let check = crate::file_version::file_version_check_tokens(&attr_to_string(attr));
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            match #check {
+                None => {
+                    #ident();
+                    Ok(())
+                }
+                Some(reason) => Err(_ignore_reason(format_args!("{}", reason))),
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the hostname matches one of the hostnames, ex: the test case only runs on a
+/// specific build machine.  The hostnames are case-insensitive and `*` matches any characters,
+/// ex: `gpu-*`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works on the machines named `gpu-01`, `gpu-02`, or starting with `cuda-`
+///     #[test_with::hostname(gpu-01, gpu-02, cuda-*)]
+///     #[test]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[cfg(feature = "hostname")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn hostname(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_hostname_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_hostname_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the hostname matches one of the hostnames
+/// The hostname is read in the same way as [macro@hostname] when the example running.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(machine);
+/// #[test_with::module]
+/// mod machine {
+///     // Only works on the machines starting with `gpu-`
+///     #[test_with::runtime_hostname(gpu-*)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_hostname(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(all(feature = "runtime", feature = "hostname"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_hostname(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let patterns = parse_hostname_patterns(&attr_to_string(attr));
+    let all_patterns = patterns.join(", ");
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            let hostname = crate::_test_with_hostname::hostname();
+            if [#(#patterns),*]
+                .iter()
+                .any(|pattern| crate::_test_with_hostname::wildcard_match(pattern, &hostname))
+            {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(
+                    "because the hostname {} is not one of {}",
+                    hostname, #all_patterns
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Provide a test runner and test on each module
+///```rust
+/// // example/run-test.rs
+///
+/// test_with::runner!(module1, module2);
+/// #[test_with::module]
+/// mod module1 {
+///     #[test_with::runtime_env(PWD)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///
+/// #[test_with::module]
+/// mod module2 {
+///     #[test_with::runtime_env(PWD)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///```
+/// Set `TEST_WITH_JSON` to a file path, and one JSON object per test with `module`, `name`,
+/// `result`, `reason` and `duration_ms` will be appended to the file for tooling.
+/// Set `TEST_WITH_DEADLINE` to seconds, and the runner will be aborted when it does not finish in
+/// the seconds, the `Drop` of the running tests and `TestEnv` may not complete when aborted.
+/// Set `TEST_WITH_SUMMARY` to a file path, and one JSON object with the `example` name and the
+/// `passed`, `failed` and `ignored` counts will be appended to the file when the runner exits, the
+/// appending is locked with a `.lock` marker file, such that the examples can run in parallel.
+/// The runner exits when the tests of a module with `TestEnv` fail, pass `continue_on_failure`,
+/// ex: `test_with::runner!(continue_on_failure, module1, module2)`, to run all the modules and
+/// exit once at the end with the combined status.
+/// The modules can be split into other files, ex: `mod suite;` with
+/// `#[test_with::module] pub mod db { .. }` in `suite.rs`, then pass the path of the module,
+/// ex: `test_with::runner!(suite::db)`.
+#[cfg(not(feature = "runtime"))]
+#[proc_macro]
+pub fn runner(_input: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro]
+pub fn runner(input: TokenStream) -> TokenStream {
+    let input_str = input.to_string();
+    let mut continue_on_failure = false;
+    // the module can be a path, ex: `suite::db`, when it is declared in another file
+    let mod_names: Vec<syn::Path> = input_str
+        .split(",")
+        .map(str::trim)
+        .filter(|s| {
+            let is_option = *s == "continue_on_failure";
+            continue_on_failure |= is_option;
+            !is_option
+        })
+        .map(|s| match syn::parse_str::<syn::Path>(s) {
+            Ok(path) => path,
+            Err(_) => abort_call_site!("`{}` is not a module of `#[test_with::module]`", s),
+        })
+        .collect();
+    #[cfg(feature = "hostname")]
+    let hostname_helpers = crate::hostname::hostname_helpers_tokens();
+    #[cfg(not(feature = "hostname"))]
+    let hostname_helpers = quote::quote! {};
+    // the failure of a module exits the runner by default, with `continue_on_failure`, all the
+    // modules run and the runner exits once at the end with the combined status
+    let (init_status, check_conclusion, exit_if_any_failed) = if continue_on_failure {
+        (
+            quote::quote! { let mut any_failed = false; },
+            quote::quote! { any_failed |= conclusion.has_failed(); },
+            quote::quote! {
+                if any_failed {
+                    std::process::exit(101);
+                }
+            },
+        )
+    } else {
+        (
+            quote::quote! {},
+            quote::quote! {
+                if conclusion.has_failed() {
+                    _test_with_write_summary(summary);
+                }
+                conclusion.exit_if_failed();
+            },
+            quote::quote! {},
+        )
+    };
+    quote::quote! {
+        #hostname_helpers
+
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        fn _test_with_probe_cache<F: FnOnce() -> bool>(key: &str, ttl: u64, probe: F) -> bool {
+            use std::collections::HashMap;
+            use std::sync::{Mutex, OnceLock};
+            use std::time::{Duration, Instant};
+            static CACHE: OnceLock<Mutex<HashMap<String, (Instant, bool)>>> = OnceLock::new();
+            let cache = CACHE.get_or_init(Default::default);
+            if let Ok(cache) = cache.lock() {
+                if let Some((probed_at, result)) = cache.get(key) {
+                    if probed_at.elapsed() < Duration::from_secs(ttl) {
+                        return *result;
+                    }
+                }
+            }
+            let result = probe();
+            if let Ok(mut cache) = cache.lock() {
+                cache.insert(key.to_string(), (Instant::now(), result));
+            }
+            result
+        }
+
+        // the counts of all the modules are appended to the file of `TEST_WITH_SUMMARY` as one JSON
+        // line, and a marker file locks the appending, such that the lines of the examples running
+        // in parallel do not interleave
+        #[doc(hidden)]
+        fn _test_with_write_summary(summary: (u64, u64, u64)) {
+            let Ok(path) = std::env::var("TEST_WITH_SUMMARY") else {
+                return;
+            };
+            let example = option_env!("CARGO_BIN_NAME").unwrap_or(env!("CARGO_CRATE_NAME"));
+            let line = format!(
+                "{{\"example\":\"{}\",\"passed\":{},\"failed\":{},\"ignored\":{}}}\n",
+                example, summary.0, summary.1, summary.2
+            );
+            let lock_file = format!("{}.lock", path);
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+            // the line is still appended when the lock is left behind by a killed runner
+            let locked = loop {
+                if std::fs::File::create_new(&lock_file).is_ok() {
+                    break true;
+                }
+                if std::time::Instant::now() >= deadline {
+                    break false;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            };
+            if let Ok(mut file) = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+            {
+                use std::io::Write;
+                let _ = file.write_all(line.as_bytes());
+            }
+            if locked {
+                let _ = std::fs::remove_file(&lock_file);
+            }
+        }
+
+        fn main() {
+            // the watchdog aborts the whole runner after the deadline, such that a stuck fixture can
+            // not hang forever
+            if let Some(deadline) = std::env::var("TEST_WITH_DEADLINE")
+                .ok()
+                .and_then(|secs| secs.trim().parse::<u64>().ok())
+            {
+                std::thread::spawn(move || {
+                    std::thread::sleep(std::time::Duration::from_secs(deadline));
+                    eprintln!(
+                        "test-with: the runner does not finish in TEST_WITH_DEADLINE={} seconds, abort",
+                        deadline
+                    );
+                    std::process::abort();
+                });
+            }
+            // `--tag` is taken out before the arguments of libtest, the tests with any of the tags
+            // are selected, and all the tests are selected without `--tag`
+            let mut tags = vec![];
+            let mut libtest_args = vec![];
+            let mut cli_args = std::env::args();
+            while let Some(arg) = cli_args.next() {
+                match arg.strip_prefix("--tag") {
+                    Some("") => tags.extend(cli_args.next()),
+                    Some(tag) if tag.starts_with('=') => tags.push(tag[1..].to_string()),
+                    _ => libtest_args.push(arg),
+                }
+            }
+            let select_tagged = |tests: &mut Vec<libtest_with::Trial>, test_tags: &[(&str, &[&str])]| {
+                if !tags.is_empty() {
+                    tests.retain(|test| {
+                        test_tags.iter().any(|(name, test_tags)| {
+                            *name == test.name() && test_tags.iter().any(|t| tags.iter().any(|tag| tag == t))
+                        })
+                    });
+                }
+            };
+            let args = libtest_with::Arguments::from_iter(libtest_args);
+            let mut no_env_tests = Vec::new();
+            // the passed, failed and ignored counts of all the modules
+            let mut summary = (0, 0, 0);
+            #init_status
+            #(
+                let (env, mut tests) = #mod_names::_runtime_tests();
+                select_tagged(&mut tests, #mod_names::_runtime_test_tags());
+                match (env, tests) {
+                    (Some(env), tests) => {
+                        let conclusion = libtest_with::run(&args, tests);
+                        // the process exits when failed, so the env is dropped before the check
+                        drop(env);
+                        summary.0 += conclusion.num_passed;
+                        summary.1 += conclusion.num_failed;
+                        summary.2 += conclusion.num_ignored;
+                        #check_conclusion
+                    },
+                    (None, mut tests) => no_env_tests.append(&mut tests),
+                }
+            )*
+            let conclusion = libtest_with::run(&args, no_env_tests);
+            summary.0 += conclusion.num_passed;
+            summary.1 += conclusion.num_failed;
+            summary.2 += conclusion.num_ignored;
+            _test_with_write_summary(summary);
+            #exit_if_any_failed
+            conclusion.exit();
+        }
+    }
+    .into()
+}
+
+/// Help each function with `#[test_with::runtime_*]` in the module can register to run
+/// Also you can set up a mock instance for all of the test in the module
+///
+/// ```rust
+///  // example/run-test.rs
+///
+///  test_with::runner!(module1, module2);
+///  #[test_with::module]
+///  mod module1 {
+///      #[test_with::runtime_env(PWD)]
+///      fn test_works() {
+///          assert!(true);
+///      }
+///  }
+///
+///  #[test_with::module]
+///  mod module2 {
+///      #[test_with::runtime_env(PWD)]
+///      fn test_works() {
+///          assert!(true);
+///      }
+///  }
+/// ```
+/// You can set up mock with a public struct named `TestEnv` inside the module, or a public type
+/// named `TestEnv` inside the module.  And the type or struct should have a Default trait for
+/// initialize the mock instance.
+/// ```rust
+/// use std::ops::Drop;
+/// use std::process::{Child, Command};
+///
+/// test_with::runner!(net);
+///
+/// #[test_with::module]
+/// mod net {
+///     pub struct TestEnv {
+///         p: Child,
+///     }
+///
+///     impl Default for TestEnv {
+///         fn default() -> TestEnv {
+///             let p = Command::new("python")
+///                 .args(["-m", "http.server"])
+///                 .spawn()
+///                 .expect("failed to execute child");
+///             let mut count = 0;
+///             while count < 3 {
+///                 if libtest_with::reqwest::blocking::get("http://127.0.0.1:8000").is_ok() {
+///                     break;
+///                 }
+///                 std::thread::sleep(std::time::Duration::from_secs(1));
+///                 count += 1;
+///             }
+///             TestEnv { p }
+///         }
+///     }
+///
+///     impl Drop for TestEnv {
+///         fn drop(&mut self) {
+///             self.p.kill().expect("fail to kill python http.server");
+///         }
+///     }
+///
+///     #[test_with::runtime_http(127.0.0.1:8000)]
+///     fn test_with_environment() {
+///         assert!(true);
+///     }
+/// }
+///
+/// ```
+/// or you can write mock struct in other place and just pass by type.
+/// ```rust
+/// use std::ops::Drop;
+/// use std::process::{Child, Command};
+///
+/// test_with::runner!(net);
+///
+/// pub struct Moc {
+///     p: Child,
+/// }
+///
+/// impl Default for Moc {
+///     fn default() -> Moc {
+///         let p = Command::new("python")
+///             .args(["-m", "http.server"])
+///             .spawn()
+///             .expect("failed to execute child");
+///         let mut count = 0;
+///         while count < 3 {
+///             if libtest_with::reqwest::blocking::get("http://127.0.0.1:8000").is_ok() {
+///                 break;
+///             }
+///             std::thread::sleep(std::time::Duration::from_secs(1));
+///             count += 1;
+///         }
+///         Moc { p }
+///     }
+/// }
+///
+/// impl Drop for Moc {
+///     fn drop(&mut self) {
+///         self.p.kill().expect("fail to kill python http.server");
+///     }
+/// }
+///
+/// #[test_with::module]
+/// mod net {
+///     pub type TestEnv = super::Moc;
+///
+///     #[test_with::runtime_http(127.0.0.1:8000)]
+///     fn test_with_environment() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+/// If the setup and teardown should run around every test instead of the whole module, write
+/// `fn before_each()` and `fn after_each()` inside the module.  The hooks run around the body of
+/// the test, so they are skipped when the test is ignored by its conditions, the panic of
+/// `before_each` fails the test, and `after_each` still runs when the test or `before_each` panics.
+/// ```rust
+/// test_with::runner!(hooks);
+///
+/// #[test_with::module]
+/// mod hooks {
+///     fn before_each() {
+///         // Set up before each test here
+///     }
+///
+///     fn after_each() {
+///         // Tear down after each test here
+///     }
+///
+///     #[test_with::runtime_env(PWD)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+/// If the mock may fail to set up, ex: Docker is not running, write `fn try_default()` inside the
+/// module returning `Option<TestEnv>` or `Result<TestEnv, E>` with `E: Display` instead of the
+/// Default trait, then all the tests in the module are ignored with the reason when it fails,
+/// rather than panicking the whole runner.
+/// ```rust
+/// test_with::runner!(docker);
+///
+/// #[test_with::module]
+/// mod docker {
+///     pub struct TestEnv;
+///
+///     fn try_default() -> Result<TestEnv, String> {
+///         Err("docker is not running".to_string())
+///     }
+///
+///     #[test_with::runtime_env(PWD)]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn module(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn module(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemMod {
+        attrs,
+        vis,
+        mod_token,
+        ident,
+        content,
+        ..
+    } = parse_macro_input!(stream as ItemMod);
+
+    if let Some(content) = content {
+        let content = content.1;
+        if crate::utils::has_test_cfg(&attrs) {
+            abort_call_site!("should not use `#[cfg(test)]` on the mod with `#[test_with::module]`")
+        } else {
+            let mut test_env_type = None;
+            let mut before_each = None;
+            let mut after_each = None;
+            let mut try_default = None;
+            let mut test_tags = vec![];
+            let mut assert_ignored = vec![];
+            let (test_names, conditions): (Vec<String>, Vec<String>) = content
+                .iter()
+                .filter_map(|c| match c {
+                    Item::Fn(ItemFn {
+                        sig: syn::Signature { ident, .. },
+                        attrs,
+                        ..
+                    }) => match crate::utils::test_with_attrs(&attrs) {
+                        (true, true, _) => abort_call_site!(
+                            "should not use #[test] for method in `#[test_with::module]`"
+                        ),
+                        (_, true, false) => abort_call_site!(
+                            "use `#[test_with::runtime_*]` for method in `#[test_with::module]`"
+                        ),
+                        (false, true, true) => {
+                            test_tags.push(crate::utils::runtime_tags(attrs));
+                            assert_ignored.push(crate::utils::has_assert_ignored_attr(attrs));
+                            Some((ident.to_string(), crate::utils::runtime_conditions(attrs)))
+                        }
+                        (false, false, _) => {
+                            if ident == "before_each" {
+                                before_each = Some(ident);
+                            } else if ident == "after_each" {
+                                after_each = Some(ident);
+                            } else if ident == "try_default" {
+                                try_default = Some(ident);
+                            }
+                            None
+                        }
+                        _ => None,
+                    },
+                    Item::Struct(ItemStruct { ident, vis, .. })
+                    | Item::Type(ItemType { ident, vis, .. }) => {
+                        if ident.to_string() == "TestEnv" {
+                            match vis {
+                                syn::Visibility::Public(_) => test_env_type = Some(ident),
+                                _ => abort_call_site!("TestEnv should be pub for testing"),
+                            }
+                        }
+                        None
+                    }
+                    _ => None,
+                })
+                .unzip();
+            let check_names: Vec<syn::Ident> = test_names
+                .iter()
+                .map(|c| {
+                    syn::Ident::new(
+                        &format!("_check_{}", c.to_string()),
+                        proc_macro2::Span::call_site(),
+                    )
+                })
+                .collect();
+            let mod_name = ident.to_string();
+            if try_default.is_some() && test_env_type.is_none() {
+                abort_call_site!("`try_default` needs a pub `TestEnv` in the module")
+            }
+            let reporter = crate::utils::runtime_reporter_tokens();
+            // the hooks run in the body of the test cases, which is only called when the
+            // conditions are met
+            let has_hooks = before_each.is_some() || after_each.is_some();
+            let each_hooks = if has_hooks {
+                crate::utils::each_hooks_tokens(before_each, after_each)
+            } else {
+                quote::quote! {}
+            };
+            let content: Vec<Item> = content
+                .iter()
+                .cloned()
+                .map(|item| match item {
+                    Item::Fn(item_fn)
+                        if has_hooks
+                            && crate::utils::test_with_attrs(&item_fn.attrs)
+                                == (false, true, true) =>
+                    {
+                        Item::Fn(crate::utils::with_each_hooks(item_fn))
+                    }
+                    item => item,
+                })
+                .collect();
+            let tag_lists: Vec<proc_macro2::TokenStream> = test_tags
+                .iter()
+                .map(|tags| quote::quote! { &[#(#tags),*] })
+                .collect();
+            let runtime_test_tags = quote::quote! {
+                pub fn _runtime_test_tags() -> &'static [(&'static str, &'static [&'static str])] {
+                    &[#((#test_names, #tag_lists)),*]
+                }
+            };
+            if let Some(test_env_type) = test_env_type {
+                let test_env = match try_default {
+                    Some(try_default) => quote::quote! {
+                        // `try_default` can return `Option<TestEnv>` or `Result<TestEnv, E>`
+                        trait _TryTestEnv<T> {
+                            fn into_test_env(self) -> Result<T, String>;
+                        }
+                        impl<T> _TryTestEnv<T> for Option<T> {
+                            fn into_test_env(self) -> Result<T, String> {
+                                self.ok_or_else(|| "because TestEnv is not available".to_string())
+                            }
+                        }
+                        impl<T, E: std::fmt::Display> _TryTestEnv<T> for Result<T, E> {
+                            fn into_test_env(self) -> Result<T, String> {
+                                self.map_err(|e| format!("because TestEnv is not available: {}", e))
+                            }
+                        }
+                        match _TryTestEnv::into_test_env(#try_default()) {
+                            Ok(test_env) => test_env,
+                            Err(reason) => return (
+                                None,
+                                vec![
+                                    #(Trial::test(#test_names, {
+                                        let reason = reason.clone();
+                                        move || Err(_ignore_reason(format_args!("{}", reason)))
+                                    }),)*
+                                ]
+                            ),
+                        }
+                    },
+                    None => quote::quote! { #test_env_type::default() },
+                };
+                quote::quote! {
+                    #(#attrs)*
+                    #vis #mod_token #ident {
+                        use super::*;
+                        pub fn _runtime_tests() -> (Option<#test_env_type>, Vec<libtest_with::Trial>) {
+                            use libtest_with::Trial;
+                            let test_env = { #test_env };
+                            (
+                                Some(test_env),
+                                vec![
+                                    #(Trial::test(#test_names, || _assert_outcome(#assert_ignored, _report_check(#mod_name, #test_names, #conditions, #check_names))),)*
+                                ]
+                            )
+                        }
+                        #runtime_test_tags
+                        #reporter
+                        #each_hooks
+                        #(#content)*
+                    }
+                }
+                .into()
+            } else {
+                quote::quote! {
+                    #(#attrs)*
+                    #vis #mod_token #ident {
+                        use super::*;
+                        pub fn _runtime_tests() -> (Option<()>, Vec<libtest_with::Trial>) {
+                            use libtest_with::Trial;
+                            (
+                                None,
+                                vec![
+                                    #(Trial::test(#test_names, || _assert_outcome(#assert_ignored, _report_check(#mod_name, #test_names, #conditions, #check_names))),)*
+                                ]
+                            )
+                        }
+                        #runtime_test_tags
+                        #reporter
+                        #each_hooks
+                        #(#content)*
+                    }
+                }
+                .into()
+            }
+        }
+    } else {
+        abort_call_site!("should use on mod with context")
+    }
+}
+
+/// Tag the runtime test case, then the runner can select the test cases with any of the tags by
+/// `--tag`, ex: `cargo run --example test -- --tag db`.  All the test cases run without `--tag`.
+/// ```
+/// test_with::runner!(tagged_mod);
+///
+/// #[test_with::module]
+/// mod tagged_mod {
+///     #[test_with::tag(slow, db)]
+///     #[test_with::runtime_env(PWD)]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn tag(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn tag(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    // the tags are collected by `#[test_with::module]`
+    stream
+}
+
+/// Assert the runtime test case is ignored, the runner fails when the test case runs, such that the
+/// gating of the test case can be checked on CI, ex: a DB test is correctly ignored when the DB is
+/// down.
+/// ```
+/// test_with::runner!(gating);
+///
+/// #[test_with::module]
+/// mod gating {
+///     #[test_with::assert_ignored]
+///     #[test_with::runtime_env(NOTHING)]
+///     fn test_ignored_without_env() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn assert_ignored(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn assert_ignored(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    // the assertion is collected by `#[test_with::module]`
+    stream
+}
+
+/// Ignore test case when function return some reason
+/// The function should be `fn() -> Option<String>`
+/// ```
+/// test_with::runner!(custom_mod);
+///
+/// fn something_happened() -> Option<String> {
+///     Some("because something happened".to_string())
+/// }
+///
+/// #[test_with::module]
+/// mod custom_mod {
+/// #[test_with::runtime_ignore_if(something_happened)]
+/// fn test_ignored() {
+///     assert!(false);
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_ignore_if(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_ignore_if(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ignore_function = syn::Ident::new(
+        &attr.to_string().replace(' ', ""),
+        proc_macro2::Span::call_site(),
+    );
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(
+        &format!("_check_{}", ident.to_string()),
+        proc_macro2::Span::call_site(),
+    );
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if let Some(msg) = #ignore_function() {
+                Err(_ignore_reason(format_args!("{msg}")))
+            } else {
+                #ident();
+                Ok(())
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Ignore test case when the custom checker is not met, ex: the condition provided by another
+/// crate.  The checker follows the convention of the conditions of this crate, which is
+/// `fn(String) -> (bool, String)`, the argument is the text after the path, and the test case is
+/// ignored with the returned message, ex: "because no GPU found".  A string literal is passed as its
+/// value, and the other tokens are passed as written without the spaces, ex: `db:5432`.  Unlike
+/// [macro@runtime_ignore_if], the checker takes an argument, so the conditions can be collected in
+/// a library.
+/// ```
+/// test_with::runner!(custom_checker);
+///
+/// fn has_gpu(vendor: String) -> (bool, String) {
+///     (false, format!("because no {} GPU found", vendor))
+/// }
+///
+/// #[test_with::module]
+/// mod custom_checker {
+///     #[test_with::runtime_custom(crate::has_gpu, "nvidia")]
+///     fn test_ignored() {
+///         panic!("should be ignored")
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_custom(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_custom(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::custom::runtime_custom_macro(attr, parse_macro_input!(stream as ItemFn))
+}
+
+/// Retry a flaky test case up to N times in runtime, the test case is only reported failed if all
+/// of the attempts fail.
+/// ```
+/// test_with::runner!(flaky_mod);
+///
+/// #[test_with::module]
+/// mod flaky_mod {
+///     #[test_with::runtime_retry(3)]
+///     fn test_flaky() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+/// When it composes with other runtime conditions, put `runtime_retry` at the top, then only the
+/// test body is retried after the condition is checked.
+/// ```
+/// test_with::runner!(flaky_mod);
+///
+/// #[test_with::module]
+/// mod flaky_mod {
+///     #[test_with::runtime_retry(3)]
+///     #[test_with::runtime_env(PWD)]
+///     fn test_flaky() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_retry(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_retry(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let attempts = match attr.to_string().replace(' ', "").parse::<usize>() {
+        Ok(n) if n > 0 => n,
+        _ => abort_call_site!("the number of attempts should be a positive number"),
+    };
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let test_name = ident.to_string();
+    let retry_fn = quote::quote! {
+        #(#attrs)*
+        #vis #sig {
+            for attempt in 1..=#attempts {
+                match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #block)) {
+                    Ok(()) => {
+                        if attempt > 1 {
+                            println!("{} ok (after {} retries)", #test_name, attempt - 1);
+                        }
+                        return;
+                    }
+                    Err(payload) if attempt == #attempts => std::panic::resume_unwind(payload),
+                    Err(_) => (),
+                }
+            }
+        }
+    };
+    if crate::utils::test_with_attrs(&attrs).2 {
+        // the `_check_*` function is provided by the other runtime condition
+        retry_fn.into()
+    } else {
+        let check_ident =
+            syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+        quote::quote! {
+            fn #check_ident() -> Result<(), libtest_with::Failed> {
+                #ident();
+                Ok(())
+            }
+
+            #retry_fn
+        }
+        .into()
+    }
+}
+
+/// Pass the test case in runtime when the test body panics, and the panic message should contain
+/// the `expected` string if it is given, like `#[should_panic]` of libtest.
+/// ```
+/// test_with::runner!(panic_mod);
+///
+/// #[test_with::module]
+/// mod panic_mod {
+///     #[test_with::runtime_should_panic]
+///     fn test_panic() {
+///         panic!("boom");
+///     }
+///
+///     #[test_with::runtime_should_panic(expected = "boom")]
+///     #[test_with::runtime_env(PWD)]
+///     fn test_panic_with_message() {
+///         panic!("boom");
+///     }
+/// }
+/// ```
+/// When it composes with other runtime conditions, put `runtime_should_panic` at the top.
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_should_panic(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_should_panic(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let expected = if attr.is_empty() {
+        None
+    } else {
+        match syn::parse::<syn::MetaNameValue>(attr) {
+            Ok(syn::MetaNameValue {
+                path,
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(expected),
+                        ..
+                    }),
+                ..
+            }) if path.is_ident("expected") => Some(expected.value()),
+            _ => abort_call_site!(
+                "should be `runtime_should_panic` or `runtime_should_panic(expected = \"...\")`"
+            ),
+        }
+    };
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let panicked_arm = match expected {
+        Some(expected) => quote::quote! {
+            Err(payload) => {
+                let msg = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                if !msg.contains(#expected) {
+                    panic!(
+                        "panic did not contain expected string\n      panic message: `{:?}`,\n expected substring: `{:?}`",
+                        msg, #expected
+                    );
+                }
+            }
+        },
+        None => quote::quote! { Err(_) => () },
+    };
+    let should_panic_fn = quote::quote! {
+        #(#attrs)*
+        #vis #sig {
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #block)) {
+                Ok(()) => panic!("test did not panic as expected"),
+                #panicked_arm
+            }
+        }
+    };
+    if crate::utils::test_with_attrs(&attrs).2 {
+        // the `_check_*` function is provided by the other runtime condition
+        should_panic_fn.into()
+    } else {
+        let check_ident =
+            syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+        quote::quote! {
+            fn #check_ident() -> Result<(), libtest_with::Failed> {
+                #ident();
+                Ok(())
+            }
+
+            #should_panic_fn
+        }
+        .into()
+    }
+}
+
+/// Run runtime test cases one by one when the lock is acquired, such that the test cases across
+/// different modules can be coordinated.  The parameters are the same as [macro@lock].
+/// ```
+/// test_with::runner!(module1, module2);
+///
+/// #[test_with::module]
+/// mod module1 {
+///     #[test_with::runtime_lock(LOCK)]
+///     fn test_1() {
+///         assert!(true);
+///     }
+/// }
+///
+/// #[test_with::module]
+/// mod module2 {
+///     #[test_with::runtime_lock(LOCK, 3)]
+///     #[test_with::runtime_env(PWD)]
+///     fn test_2() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+/// When it composes with other runtime conditions, put `runtime_lock` at the top.
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_lock(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_lock(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let locked_body = crate::utils::locked_body(attr, &block.stmts);
+    let locked_fn = quote::quote! {
+        #(#attrs)*
+        #vis #sig {
+            #locked_body
+        }
+    };
+    if crate::utils::test_with_attrs(&attrs).2 {
+        // the `_check_*` function is provided by the other runtime condition
+        locked_fn.into()
+    } else {
+        let check_ident =
+            syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+        quote::quote! {
+            fn #check_ident() -> Result<(), libtest_with::Failed> {
+                #ident();
+                Ok(())
+            }
+
+            #locked_fn
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_env_condition, check_env_file_condition, check_no_env_condition, ci_env_var,
+        dotenv_var_names, is_true_env_value,
+    };
+    #[cfg(feature = "resource")]
+    use super::{check_phy_core_condition, parse_percent_limitation, parse_size_limitation};
+
+    mod env_macro {
+        use super::*;
+
+        #[test]
+        fn single_env_var_should_be_not_set() {
+            //* Given
+            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = env_var.to_string();
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(ignore_msg.contains(env_var));
+        }
+
+        #[test]
+        fn multiple_env_vars_should_not_be_set() {
+            //* Given
+            let env_var1 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+            let env_var2 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}", env_var1, env_var2);
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(ignore_msg.contains(env_var1));
+            assert!(ignore_msg.contains(env_var2));
+        }
+
+        #[test]
+        fn enough_env_vars_should_be_set() {
+            //* Given
+            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("PATH, {}, at_least=1", env_var);
+
+            //* When
+            let (is_ok, _) = check_env_condition(attr_str);
+
+            //* Then
+            assert!(is_ok);
+        }
+
+        #[test]
+        fn not_enough_env_vars_should_be_set() {
+            //* Given
+            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("PATH, {}, at_least=2", env_var);
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            assert!(!is_ok);
+            // Assert the ignore message should report the found and required numbers
+            assert!(ignore_msg.contains("only 1 of"));
+            assert!(ignore_msg.contains("at least 2 required"));
+        }
+
+        #[test]
+        fn single_env_var_should_be_set() {
+            //* Given
+            let env_var = "PATH";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = env_var.to_string();
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(!ignore_msg.contains(env_var));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars containing spaces and newlines.
+        ///
+        /// ```no_run
+        /// #[test_with::env(
+        ///   PATH,
+        ///   HOME
+        /// )]
+        /// #[test]
+        /// fn some_test() {}
+        #[test]
+        fn multiple_env_vars_should_be_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "HOME";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("\t{},\n\t{}\n", env_var1, env_var2);
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(!ignore_msg.contains(env_var1));
+            assert!(!ignore_msg.contains(env_var2));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars and one of them is not set.
+        #[test]
+        fn multiple_env_vars_but_one_is_not_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "HOME";
+            let env_var3 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(!ignore_msg.contains(env_var1));
+            assert!(!ignore_msg.contains(env_var2));
+            assert!(ignore_msg.contains(env_var3));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars and various of them are not set.
+        #[test]
+        fn multiple_env_vars_and_various_not_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+            let env_var3 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the missing env var names
+            assert!(!ignore_msg.contains(env_var1));
+            assert!(ignore_msg.contains(env_var2));
+            assert!(ignore_msg.contains(env_var3));
+        }
+    }
+
+    mod no_env_macro {
+        use super::*;
+
+        #[test]
+        fn single_env_var_not_set() {
+            //* Given
+            let env_var = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = env_var.to_string();
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(!ignore_msg.contains(env_var));
+        }
+
+        #[test]
+        fn multiple_env_vars_not_set() {
+            //* Given
+            let env_var1 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+            let env_var2 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}", env_var1, env_var2);
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(!ignore_msg.contains(env_var1));
+            assert!(!ignore_msg.contains(env_var2));
+        }
+
+        #[test]
+        fn single_env_var_set() {
+            //* Given
+            let env_var = "PATH";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = env_var.to_string();
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(ignore_msg.contains(env_var));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars containing spaces and newlines.
+        ///
+        /// ```no_run
+        /// #[test_with::no_env(
+        ///   PATH,
+        ///   HOME
+        /// )]
+        /// #[test]
+        /// fn some_test() {}
+        #[test]
+        fn multiple_env_vars_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "HOME";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("\t{},\n\t{}\n", env_var1, env_var2);
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(ignore_msg.contains(env_var1));
+            assert!(ignore_msg.contains(env_var2));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars and one of them is set.
+        #[test]
+        fn multiple_env_vars_but_one_is_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+            let env_var3 = "ANOTHER_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(ignore_msg.contains(env_var1));
+            assert!(!ignore_msg.contains(env_var2));
+            assert!(!ignore_msg.contains(env_var3));
+        }
+
+        /// Test the `test_with::env(<attr_str>)` macro should parse the attribute string correctly
+        /// when the attribute string contains multiple env vars and various of them are set.
+        #[test]
+        fn multiple_env_vars_and_various_are_set() {
+            //* Given
+            let env_var1 = "PATH";
+            let env_var2 = "HOME";
+            let env_var3 = "A_RIDICULOUS_ENV_VAR_NAME_THAT_SHOULD_NOT_BE_SET";
+
+            // The `test_with::env(<attr_str>)` macro arguments
+            let attr_str = format!("{}, {}, {}", env_var1, env_var2, env_var3);
+
+            //* When
+            let (is_ok, ignore_msg) = check_no_env_condition(attr_str);
+
+            //* Then
+            // Assert if the test should be ignored
+            assert!(!is_ok);
+            // Assert the ignore message should contain only the found env var names
+            assert!(ignore_msg.contains(env_var1));
+            assert!(ignore_msg.contains(env_var2));
+            assert!(!ignore_msg.contains(env_var3));
+        }
+    }
+
+    mod env_file_macro {
+        use super::*;
+
+        #[test]
+        fn dotenv_var_names_skip_comments_and_export() {
+            //* Given
+            let content = "# comment\nDB_URL=postgres://localhost\n\nexport API_KEY = secret\n";
+
+            //* When
+            let var_names = dotenv_var_names(content);
+
+            //* Then
+            assert_eq!(var_names, vec!["DB_URL", "API_KEY"]);
+        }
+
+        #[test]
+        fn env_file_should_not_exist() {
+            //* Given
+            let attr_str = "\"/a/ridiculous/env/file/that/should/not/exist\":DB_URL".to_string();
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_file_condition(attr_str);
+
+            //* Then
+            assert!(!is_ok);
+            assert!(ignore_msg.contains("not found"));
+        }
+
+        #[test]
+        fn env_file_with_one_variable_not_defined() {
+            //* Given
+            let file = std::env::temp_dir().join("test_with_env_file_condition.env");
+            std::fs::write(&file, "DB_URL=postgres://localhost\n").unwrap();
+
+            // The `test_with::env_file(<attr_str>)` macro arguments
+            let attr_str = format!("\"{}\":DB_URL,API_KEY", file.display());
+
+            //* When
+            let (is_ok, ignore_msg) = check_env_file_condition(attr_str);
+
+            //* Then
+            assert!(!is_ok);
+            assert!(!ignore_msg.contains("DB_URL"));
+            assert!(ignore_msg.contains("API_KEY"));
+        }
+    }
+
+    #[cfg(feature = "resource")]
+    mod resource_macro {
+        use super::*;
+
+        #[test]
+        fn binary_unit_size() {
+            //* Given
+            let size_str = "1GiB";
+
+            //* When
+            let size = parse_size_limitation(size_str);
+
+            //* Then
+            assert_eq!(size.as_u64(), 1073741824);
+        }
+
+        #[test]
+        fn percent_with_or_without_sign() {
+            //* Given
+            let percents = ["0", "50", "100%"];
+
+            //* When
+            let parsed: Vec<_> = percents
+                .iter()
+                .map(|p| parse_percent_limitation(p))
+                .collect();
+
+            //* Then
+            assert_eq!(parsed, vec![0, 50, 100]);
+        }
+
+        #[test]
+        fn physical_core_condition() {
+            //* Given
+            let more_than_physical = (num_cpus::get_physical() + 1).to_string();
+
+            //* When
+            let (fulfilled, ignore_msg) = check_phy_core_condition(more_than_physical.clone());
+
+            //* Then
+            assert!(!fulfilled);
+            assert_eq!(
+                ignore_msg,
+                format!(
+                    "because the physical cpu core less than {}",
+                    more_than_physical
+                )
+            );
+        }
+
+        #[test]
+        fn decimal_unit_size() {
+            //* Given
+            let size_str = "1GB";
+
+            //* When
+            let size = parse_size_limitation(size_str);
+
+            //* Then
+            assert_eq!(size.as_u64(), 1000000000);
+        }
+    }
+
+    mod env_true_macro {
+        use super::*;
+
+        #[test]
+        fn opt_in_values() {
+            //* Given
+            let values = ["1", "TRUE", "Yes", " on ", "0", "false", "", "enabled"];
+
+            //* When
+            let enabled: Vec<_> = values.iter().map(|v| is_true_env_value(v)).collect();
+
+            //* Then
+            assert_eq!(
+                enabled,
+                vec![true, true, true, true, false, false, false, false]
+            );
+        }
+    }
+
+    mod skip_if_ci_macro {
+        use super::*;
+
+        #[test]
+        fn ci_marker_is_set() {
+            //* Given
+            let lookup = |name: &str| (name == "GITLAB_CI").then(|| "true".to_string());
+
+            //* When
+            let ci_var = ci_env_var(lookup);
+
+            //* Then
+            assert_eq!(ci_var, Some("GITLAB_CI"));
+        }
+
+        #[test]
+        fn ci_marker_is_falsy() {
+            //* Given
+            let lookup = |name: &str| match name {
+                "CI" => Some("false".to_string()),
+                "TRAVIS" => Some("0".to_string()),
+                "DRONE" => Some(String::new()),
+                _ => None,
+            };
+
+            //* When
+            let ci_var = ci_env_var(lookup);
+
+            //* Then
+            assert_eq!(ci_var, None);
+        }
+    }
+}
+
+/// Run test case one by one when the lock is acquired
+/// It will automatically implement a file lock for the test case to prevent it run in the same
+/// time. Also, you can pass the second parameter to specific the waiting seconds, default will be
+/// 60 seconds.  The lock is polled from every 50 milliseconds with exponential backoff capped at 1
+/// second, and the first poll interval can be set with `poll_ms`, ex: `lock(LOCK, 30, poll_ms=10)`.
+/// The lock is a marker file created with `File::create_new` by default, which is left behind if
+/// the test process is killed.  With `backend=flock`, an OS advisory lock is held on the lock file
+/// instead with `flock` on unix and `LockFileEx` on Windows, which the OS releases when the process
+/// exits.
+/// The environment variables in the lock name, ex: `lock(DB_${CI_JOB_ID})`, are expanded when
+/// compiling, such that the parallel CI jobs on the same runner will not collide, and the unset
+/// variables expand to empty.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // `LOCK` is file based lock to prevent test1 an test2 run at the same time
+///     #[test_with::lock(LOCK)]
+///     #[test]
+///     fn test_1() {
+///         assert!(true);
+///     }
+///
+///     // `LOCK` is file based lock to prevent test1 an test2 run at the same time
+///     #[test_with::lock(LOCK)]
+///     #[test]
+///     fn test_2() {
+///         assert!(true);
+///     }
+///
+///     // `ANOTHER_LOCK` is file based lock to prevent test3 an test4 run at the same time with 3 sec
+///     // waiting time.
+///     #[test_with::lock(ANOTHER_LOCK, 3)]
+///     fn test_3() {
+///         assert!(true);
+///     }
+///
+///     // `ANOTHER_LOCK` is file based lock to prevent test3 an test4 run at the same time with 3 sec
+///     // waiting time.
+///     #[test_with::lock(ANOTHER_LOCK, 3)]
+///     fn test_4() {
+///         assert!(true);
+///     }
+///
+/// }
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn lock(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::lock] only works with fn")
+    } else {
+        lock_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Run no more than the number of the test cases marked with `throttle` at the same time.
+/// Different from [macro@lock], the test cases are limited regardless of their names, ex: the
+/// resource-heavy test cases.  The running test cases are counted by the limit in the test process,
+/// so the test cases with different limits are bounded separately, and the test cases in different
+/// test binaries or processes are not bounded together.  The counters are kept by
+/// `test_with::throttles!()`, which should be called once at the crate root.
+/// ```
+/// test_with::throttles!();
+///
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // No more than 2 of the test cases run at the same time
+///     #[test_with::throttle(2)]
+///     #[test]
+///     fn test_1() {
+///         assert!(true);
+///     }
+///
+///     #[test_with::throttle(2)]
+///     #[test]
+///     fn test_2() {
+///         assert!(true);
+///     }
+///
+///     #[test_with::throttle(2)]
+///     #[test]
+///     fn test_3() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn throttle(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::throttle] only works with fn")
+    } else {
+        throttle_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Define the counters of [macro@throttle] at the crate root, it should be called once in each
+/// test crate using [macro@throttle].
+#[proc_macro]
+pub fn throttles(_input: TokenStream) -> TokenStream {
+    throttles_tokens().into()
+}
+
+/// Run the test cases in the same group one by one with a mutex in the test process, ex: the test
+/// cases share an in-process resource.  Different from [macro@lock], there is no lock file, so it
+/// is faster and leaves nothing behind, but it does not coordinate the test cases in separate test
+/// binaries or processes.  The mutexes are kept by `test_with::serial_groups!()`, which should be
+/// called once at the crate root, ex: `lib.rs` for the unit tests or each file of `tests/*.rs`.
+/// ```
+/// test_with::serial_groups!();
+///
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // `test_1` and `test_2` do not run at the same time
+///     #[test_with::serial_group(DB)]
+///     #[test]
+///     fn test_1() {
+///         assert!(true);
+///     }
+///
+///     #[test_with::serial_group(DB)]
+///     #[test]
+///     fn test_2() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn serial_group(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::serial_group] only works with fn")
+    } else {
+        serial_group_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Define the mutexes of [macro@serial_group] at the crate root, it should be called once in each
+/// test crate using [macro@serial_group].
+#[proc_macro]
+pub fn serial_groups(_input: TokenStream) -> TokenStream {
+    serial_groups_tokens().into()
+}
+
+/// Set the environment variables for the test case, and restore the previous values, or unset
+/// them, after the test case.  The test case always runs, and the environment is shared by the
+/// whole process, so the test cases reading the same variables should run with `#[serial]`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     #[test_with::with_env(LOG=debug, TZ=UTC)]
+///     #[test]
+///     fn test_with_controlled_env() {
+///         assert_eq!(std::env::var("LOG").unwrap(), "debug");
+///         assert_eq!(std::env::var("TZ").unwrap(), "UTC");
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn with_env(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::with_env] only works with fn")
+    } else {
+        with_env_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Run test case when all of the conditions are met, the conditions are the conditions of this crate, ex: `env(DB_URL)`,
+/// and they can be nested with `all`, `any` and `not`.  The ignore message lists the unmet
+/// conditions as a tree.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when PWD is set, and the root path or the file exists
+///     #[test_with::all(env(PWD), any(path(/), file(/nothing)))]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn all(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::combinator::check_all_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::combinator::check_all_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and all of the conditions are met.  The conditions are checked when the
+/// runner starts by their runtime macros, ex: `env(DB_URL)` is checked as `runtime_env(DB_URL)`, and
+/// `ci` is also supported.  They can be nested with `all`, `any` and `not`.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(combinator);
+/// #[test_with::module]
+/// mod combinator {
+///     // Only works when PWD is set, and the root path or the file exists
+///     #[test_with::runtime_all(env(PWD), any(path(/), file(/nothing)))]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_all(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_all(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::combinator::runtime_condition_macro(
+        crate::combinator::Condition::All(crate::combinator::parse_conditions(attr.into())),
+        parse_macro_input!(stream as ItemFn),
+    )
+}
+
+/// Run test case when any of the conditions is met, the conditions are the conditions of this crate, ex: `env(DB_URL)`,
+/// and they can be nested with `all`, `any` and `not`.  The ignore message lists the unmet
+/// conditions as a tree.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when PWD is set, or NOTHING and the file exist
+///     #[test_with::any(env(PWD), all(env(NOTHING), file(/nothing)))]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn any(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::combinator::check_any_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::combinator::check_any_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and any of the conditions is met.  The conditions are checked when the
+/// runner starts by their runtime macros, ex: `env(DB_URL)` is checked as `runtime_env(DB_URL)`, and
+/// `ci` is also supported.  They can be nested with `all`, `any` and `not`.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(combinator);
+/// #[test_with::module]
+/// mod combinator {
+///     // Only works when PWD is set, or NOTHING and the file exist
+///     #[test_with::runtime_any(env(PWD), all(env(NOTHING), file(/nothing)))]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_any(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_any(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::combinator::runtime_condition_macro(
+        crate::combinator::Condition::Any(crate::combinator::parse_conditions(attr.into())),
+        parse_macro_input!(stream as ItemFn),
+    )
+}
+
+/// Run test case when the conditions are met, which is the readable alias of [macro@all], ex:
+/// `#[test_with::when(env(RUN_SLOW))]`.  The conditions can be nested with `all`, `any` and `not`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when PWD is set
+///     #[test_with::when(env(PWD))]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn when(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::combinator::check_when_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::combinator::check_when_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and the conditions are met, which is the readable alias
+/// of [macro@runtime_all].
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(combinator);
+/// #[test_with::module]
+/// mod combinator {
+///     // Only works when PWD is set
+///     #[test_with::runtime_when(env(PWD))]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_when(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_when(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::combinator::runtime_condition_macro(
+        crate::combinator::when_condition(attr.into()),
+        parse_macro_input!(stream as ItemFn),
+    )
+}
+
+/// Run test case unless any of the conditions is met, ex: `#[test_with::unless(ci)]` runs the test
+/// case only on the local machine.  The conditions can be nested with `all`, `any` and `not`, and
+/// `ci` is met when any of the common CI markers is set, the same as [macro@skip_if_ci].
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works when NOTHING is not set
+///     #[test_with::unless(env(NOTHING))]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn unless(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            crate::combinator::check_unless_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            crate::combinator::check_unless_condition,
+        )
+    }
+}
+
+/// Run test case when the example running and none of the conditions is met.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(combinator);
+/// #[test_with::module]
+/// mod combinator {
+///     // Only works when NOTHING is not set
+///     #[test_with::runtime_unless(env(NOTHING))]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_unless(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_unless(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::combinator::runtime_condition_macro(
+        crate::combinator::unless_condition(attr.into()),
+        parse_macro_input!(stream as ItemFn),
+    )
+}
+
+/// Expect the test case to panic only when the sub-condition holds, ex: the behavior differs on
+/// some platforms.  Different from the other conditions, the test case always runs, and only the
+/// `#[should_panic]` is added when the sub-condition holds.  The sub-condition is `os(...)` or a
+/// condition of this crate, ex: `env(CI)`, and they can be combined with `all`, `any` and `not`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     #[test_with::expect_panic_if(os(windows))]
+///     #[test]
+///     fn test_panic_on_windows() {
+///         if cfg!(windows) {
+///             panic!("not supported on windows")
+///         }
+///     }
+///
+///     #[test_with::expect_panic_if(any(os(windows), not(env(PWD))))]
+///     #[test]
+///     fn test_panic_on_windows_or_without_pwd() {
+///         if cfg!(windows) || std::env::var("PWD").is_err() {
+///             panic!("not supported")
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn expect_panic_if(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::expect_panic_if] only works with fn")
+    } else {
+        expect_panic_if_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Run test case when the target family is one of the families, `unix`, `windows` or `wasm`,
+/// otherwise the test case is ignored.  The target family is checked with `cfg_attr` when
+/// compiling the test case, so it works when cross compiling, ex: to `wasm32-unknown-unknown`.
+/// With `#[wasm_bindgen_test]` below this attribute, `#[test]` is not added, and the ignored test
+/// case needs `wasm-bindgen-test` 0.3.34 or newer, which supports `#[ignore]`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works on unix or windows
+///     #[test_with::target_family(unix, windows)]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn target_family(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::target_family] only works with fn")
+    } else {
+        target_family_macro(attr, parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Ignore test case when the target family is `wasm`, ex: the test case needs threads or the file
+/// system, which are not available under `wasm32`.  It works like `target_family(unix, windows)`.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works on the native targets
+///     #[test_with::not_wasm]
+///     #[test]
+///     fn test_works() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn not_wasm(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::not_wasm] only works with fn")
+    } else {
+        not_wasm_macro(parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Run test case when the test case is built in release, ex: the test case measures the
+/// performance with optimizations.  The build profile is detected by `debug_assertions`, which is
+/// disabled by `cargo test --release` and checked with `cfg_attr` when compiling the test case.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only works with `cargo test --release`
+///     #[test_with::release]
+///     #[test]
+///     fn test_performance() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn release(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::release] only works with fn")
+    } else {
+        release_macro(parse_macro_input!(stream as ItemFn))
     }
 }
 
-/// Run test case one by one when the lock is acquired
-/// It will automatically implement a file lock for the test case to prevent it run in the same
-/// time. Also, you can pass the second parameter to specific the waiting seconds, default will be
-/// 60 seconds.
+/// Run test case when the test case is built with debug assertions, ex: the test case expects the
+/// panic of `debug_assert!` or the overflow check.
 /// ```
 /// #[cfg(test)]
 /// mod tests {
 ///
-///     // `LOCK` is file based lock to prevent test1 an test2 run at the same time
-///     #[test_with::lock(LOCK)]
+///     // Only works without `--release`
+///     #[test_with::debug]
 ///     #[test]
-///     fn test_1() {
+///     fn test_debug_assertion() {
 ///         assert!(true);
 ///     }
-///
-///     // `LOCK` is file based lock to prevent test1 an test2 run at the same time
-///     #[test_with::lock(LOCK)]
-///     #[test]
-///     fn test_2() {
+/// }
+/// ```
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn debug(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        abort_call_site!("#[test_with::debug] only works with fn")
+    } else {
+        debug_macro(parse_macro_input!(stream as ItemFn))
+    }
+}
+
+/// Run test case when the example running is built in release.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(profile);
+/// #[test_with::module]
+/// mod profile {
+///     #[test_with::runtime_release]
+///     fn test_performance() {
 ///         assert!(true);
 ///     }
-///
-///     // `ANOTHER_LOCK` is file based lock to prevent test3 an test4 run at the same time with 3 sec
-///     // waiting time.
-///     #[test_with::lock(ANOTHER_LOCK, 3)]
-///     fn test_3() {
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_release(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_release(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::profile::runtime_profile_macro(true, parse_macro_input!(stream as ItemFn))
+}
+
+/// Run test case when the example running is built with debug assertions.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(profile);
+/// #[test_with::module]
+/// mod profile {
+///     #[test_with::runtime_debug]
+///     fn test_debug_assertion() {
 ///         assert!(true);
 ///     }
-///
-///     // `ANOTHER_LOCK` is file based lock to prevent test3 an test4 run at the same time with 3 sec
-///     // waiting time.
-///     #[test_with::lock(ANOTHER_LOCK, 3)]
-///     fn test_4() {
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_debug(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_debug(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::profile::runtime_profile_macro(false, parse_macro_input!(stream as ItemFn))
+}
+
+/// Run test case when the example running under a debugger, and it is ignored otherwise, ex: the
+/// test case with a longer timeout for stepping through.  The debugger is detected with the
+/// `TracerPid` of `/proc/self/status` on Linux and Android, and `IsDebuggerPresent` on Windows, and
+/// it is never detected on the other operating systems, ex: macOS.  The debugger is only attached
+/// when the test binary runs, so there is no compile-time variant of this condition.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(debugger);
+/// #[test_with::module]
+/// mod debugger {
+///     #[test_with::runtime_under_debugger]
+///     fn test_step_through() {
 ///         assert!(true);
 ///     }
-///
 /// }
+///```
+#[cfg(not(feature = "runtime"))]
 #[proc_macro_attribute]
 #[proc_macro_error]
-pub fn lock(attr: TokenStream, stream: TokenStream) -> TokenStream {
-    if is_module(&stream) {
-        abort_call_site!("#[test_with::lock] only works with fn")
-    } else {
-        lock_macro(attr, parse_macro_input!(stream as ItemFn))
-    }
+pub fn runtime_under_debugger(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_under_debugger(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::debugger::runtime_debugger_macro(true, parse_macro_input!(stream as ItemFn))
+}
+
+/// Run test case when the example running not under a debugger, and it is ignored otherwise, ex:
+/// the timing test case failing when it is stepped through.  The debugger is detected in the same
+/// way as [macro@runtime_under_debugger].
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(debugger);
+/// #[test_with::module]
+/// mod debugger {
+///     #[test_with::runtime_no_debugger]
+///     fn test_timing() {
+///         assert!(true);
+///     }
+/// }
+///```
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_debugger(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+#[cfg(feature = "runtime")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_debugger(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    crate::debugger::runtime_debugger_macro(false, parse_macro_input!(stream as ItemFn))
 }
 
 /// Run test case when the timezone is expected.
@@ -2928,7 +8223,7 @@ fn check_timezone(attr_str: &String) -> (bool, Vec<&str>) {
     let mut match_tz = false;
     let current_tz = chrono::Local::now().offset().local_minus_utc() / 60;
 
-    for tz in attr_str.split(',') {
+    for tz in split_attr_list(attr_str) {
         let parsed_tz = match tz {
             "NZDT" => Ok(13 * 60),
             "NZST" => Ok(12 * 60),
@@ -3055,22 +8350,257 @@ pub fn runtime_timezone(attr: TokenStream, stream: TokenStream) -> TokenStream {
                     #ident();
                     Ok(())
             } else if incorrect_tzs.len() == 1 {
-                Err(
-                    format!("{}because timezone {} is incorrect",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, incorrect_tzs[0]
-                ).into())
+                Err(_ignore_reason(format_args!(
+                    "because timezone {} is incorrect",
+                    incorrect_tzs[0]
+                )))
             } else if incorrect_tzs.len() > 1 {
-                Err(
-                    format!("{}because following timezones are incorrect:\n{:?}\n",
-                            libtest_with::RUNTIME_IGNORE_PREFIX, incorrect_tzs
-                ).into())
+                Err(_ignore_reason(format_args!(
+                    "because following timezones are incorrect:\n{:?}\n",
+                    incorrect_tzs
+                )))
             } else {
-                Err(
-                    format!(
-                    "{}because the test case not run in following timezone:\n{}\n",
-                    libtest_with::RUNTIME_IGNORE_PREFIX,
+                Err(_ignore_reason(format_args!(
+                    "because the test case not run in following timezone:\n{}\n",
                     #attr_str
-                ).into())
+                )))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the daylight saving time is in effect.
+/// The local timezone is decided by the `TZ` environment variable or the system setting, and the
+/// daylight saving time is detected by comparing current offset with the offsets in January and
+/// July, so the timezone without daylight saving time will be treated as not in effect.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only run in summer time
+///     #[test_with::dst()]
+///     #[test]
+///     fn test_works_in_summer_time() {
+///         assert!(true);
+///     }
+///
+///     // Only run in winter time or the timezone without summer time
+///     #[test_with::no_dst()]
+///     #[test]
+///     fn test_works_in_winter_time() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[cfg(feature = "timezone")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn dst(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_dst_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_dst_condition,
+        )
+    }
+}
+
+/// Run test case when the daylight saving time is not in effect.
+/// Please check [macro@dst] for the detection of daylight saving time.
+/// ```
+/// #[cfg(test)]
+/// mod tests {
+///
+///     // Only run in winter time or the timezone without summer time
+///     #[test_with::no_dst()]
+///     #[test]
+///     fn test_works_in_winter_time() {
+///         assert!(true);
+///     }
+///
+///     // Only run in summer time
+///     #[test_with::dst()]
+///     #[test]
+///     fn test_works_in_summer_time() {
+///         assert!(true);
+///     }
+/// }
+/// ```
+#[cfg(feature = "timezone")]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn no_dst(attr: TokenStream, stream: TokenStream) -> TokenStream {
+    if is_module(&stream) {
+        mod_macro(
+            attr,
+            parse_macro_input!(stream as ItemMod),
+            check_no_dst_condition,
+        )
+    } else {
+        fn_macro(
+            attr,
+            parse_macro_input!(stream as ItemFn),
+            check_no_dst_condition,
+        )
+    }
+}
+
+// The daylight saving time is in effect if the current offset is the larger one of the offsets in
+// January and July
+#[cfg(feature = "timezone")]
+fn is_dst() -> bool {
+    use chrono::{Datelike, TimeZone};
+
+    let now = chrono::Local::now();
+    let offset_at = |month| {
+        chrono::Local
+            .with_ymd_and_hms(now.year(), month, 1, 0, 0, 0)
+            .single()
+            .map(|d| d.offset().local_minus_utc())
+    };
+    match (offset_at(1), offset_at(7)) {
+        (Some(jan), Some(jul)) if jan != jul => now.offset().local_minus_utc() == jan.max(jul),
+        _ => false,
+    }
+}
+
+#[cfg(feature = "timezone")]
+fn check_dst_condition(_attr_str: String) -> (bool, String) {
+    (
+        is_dst(),
+        "because the daylight saving time is not in effect".into(),
+    )
+}
+
+#[cfg(feature = "timezone")]
+fn check_no_dst_condition(_attr_str: String) -> (bool, String) {
+    (
+        !is_dst(),
+        "because the daylight saving time is in effect".into(),
+    )
+}
+
+/// Run test case when the example running and the daylight saving time is in effect.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(timezone);
+/// #[test_with::module]
+/// mod timezone {
+///     #[test_with::runtime_dst()]
+///     fn test_works_in_summer_time() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_dst(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+
+#[cfg(all(feature = "runtime", feature = "timezone"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_dst(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            use libtest_with::chrono::{Datelike, TimeZone};
+
+            let now = libtest_with::chrono::Local::now();
+            let offset_at = |month| {
+                libtest_with::chrono::Local
+                    .with_ymd_and_hms(now.year(), month, 1, 0, 0, 0)
+                    .single()
+                    .map(|d| d.offset().local_minus_utc())
+            };
+            let is_dst = match (offset_at(1), offset_at(7)) {
+                (Some(jan), Some(jul)) if jan != jul => now.offset().local_minus_utc() == jan.max(jul),
+                _ => false,
+            };
+
+            if is_dst {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because the daylight saving time is not in effect")))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
+
+/// Run test case when the example running and the daylight saving time is not in effect.
+///```rust
+/// // write as example in examples/*rs
+/// test_with::runner!(timezone);
+/// #[test_with::module]
+/// mod timezone {
+///     #[test_with::runtime_no_dst()]
+///     fn test_works_in_winter_time() {
+///         assert!(true);
+///     }
+/// }
+#[cfg(not(feature = "runtime"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_dst(_attr: TokenStream, _stream: TokenStream) -> TokenStream {
+    panic!("should be used with runtime feature")
+}
+
+#[cfg(all(feature = "runtime", feature = "timezone"))]
+#[proc_macro_attribute]
+#[proc_macro_error]
+pub fn runtime_no_dst(_attr: TokenStream, stream: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(stream as ItemFn);
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    quote::quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            use libtest_with::chrono::{Datelike, TimeZone};
+
+            let now = libtest_with::chrono::Local::now();
+            let offset_at = |month| {
+                libtest_with::chrono::Local
+                    .with_ymd_and_hms(now.year(), month, 1, 0, 0, 0)
+                    .single()
+                    .map(|d| d.offset().local_minus_utc())
+            };
+            let is_dst = match (offset_at(1), offset_at(7)) {
+                (Some(jan), Some(jul)) if jan != jul => now.offset().local_minus_utc() == jan.max(jul),
+                _ => false,
+            };
+
+            if !is_dst {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!("because the daylight saving time is in effect")))
             }
         }
 
@@ -0,0 +1,57 @@
+use crate::utils::split_attr_list;
+
+/// Parse the names of the shared libraries, ex: `libssl.so.3, "libz.so.1"`
+pub(crate) fn parse_library_names(attr_str: &str) -> Vec<String> {
+    let libraries: Vec<String> = split_attr_list(attr_str)
+        .into_iter()
+        .map(|library| library.trim_matches('"').to_string())
+        .collect();
+    if libraries.is_empty() {
+        proc_macro_error2::abort_call_site!(
+            "the library is required, ex: `#[test_with::library(libssl.so.3)]`"
+        );
+    }
+    libraries
+}
+
+/// Load the shared library with the search rules of the dynamic loader, ex: `LD_LIBRARY_PATH` and
+/// the cache of `ldconfig` on Linux, and the library is unloaded right after the check.
+pub(crate) fn is_loadable(library: &str) -> bool {
+    // SAFETY: the initialization routines of the library are run when loading, the library is
+    // trusted because the test case will load it anyway.
+    unsafe { libloading::Library::new(library) }.is_ok()
+}
+
+pub(crate) fn check_library_condition(attr_str: String) -> (bool, String) {
+    let unloadable: Vec<String> = parse_library_names(&attr_str)
+        .into_iter()
+        .filter(|library| !is_loadable(library))
+        .collect();
+    let ignore_msg = if unloadable.len() == 1 {
+        format!("because {} could not be loaded", unloadable[0])
+    } else {
+        format!(
+            "because following libraries could not be loaded: \n{}\n",
+            unloadable.join("\n")
+        )
+    };
+    (unloadable.is_empty(), ignore_msg)
+}
+
+#[cfg(all(test, target_os = "linux", target_env = "gnu"))]
+mod tests {
+    use super::{is_loadable, parse_library_names};
+
+    #[test]
+    fn load_shared_libraries() {
+        //* Given
+        let libraries = parse_library_names("libc.so.6, \"libnothing.so.1\"");
+
+        //* When
+        let loadable: Vec<bool> = libraries.iter().map(|l| is_loadable(l)).collect();
+
+        //* Then
+        assert_eq!(libraries, vec!["libc.so.6", "libnothing.so.1"]);
+        assert_eq!(loadable, vec![true, false]);
+    }
+}
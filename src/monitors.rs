@@ -0,0 +1,144 @@
+use proc_macro_error2::abort_call_site;
+
+/// Parse the number of monitors, ex: `monitors(2)`
+pub(crate) fn parse_monitors(attr_str: &str) -> usize {
+    match attr_str.trim().parse::<usize>() {
+        Ok(required) if required > 0 => required,
+        _ => abort_call_site!(
+            "the number of monitors should be a positive number, ex: `monitors(2)`"
+        ),
+    }
+}
+
+/// The monitors of the X11 session listed by `xrandr --listmonitors`, ex: `Monitors: 2`
+#[cfg(target_os = "linux")]
+fn xrandr_monitors() -> Option<usize> {
+    std::env::var_os("DISPLAY").filter(|display| !display.is_empty())?;
+    let output = std::process::Command::new("xrandr")
+        .arg("--listmonitors")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()?
+        .strip_prefix("Monitors:")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The connected outputs of the graphic cards, ex: `/sys/class/drm/card0-HDMI-A-1/status` is
+/// `connected`, which also covers the Wayland sessions without `xrandr`.  It is unknown when there
+/// is no output, ex: in a container or a virtual machine without the graphic card.
+#[cfg(target_os = "linux")]
+fn drm_monitors() -> Option<usize> {
+    let statuses: Vec<String> = std::fs::read_dir("/sys/class/drm")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("status")).ok())
+        .collect();
+    if statuses.is_empty() {
+        return None;
+    }
+    Some(
+        statuses
+            .iter()
+            .filter(|status| status.trim() == "connected")
+            .count(),
+    )
+}
+
+/// The number of monitors, which is asked from `xrandr` in the X11 session, otherwise counted from
+/// the connected outputs of the graphic cards.
+#[cfg(target_os = "linux")]
+pub(crate) fn monitor_count() -> Option<usize> {
+    xrandr_monitors().or_else(drm_monitors)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn check_monitors_condition(attr_str: String) -> (bool, String) {
+    let required = parse_monitors(&attr_str);
+    match monitor_count() {
+        Some(count) if count >= required => (true, String::new()),
+        Some(count) => (
+            false,
+            format!(
+                "because {} monitors detected, at least {} required",
+                count, required
+            ),
+        ),
+        None => (false, "because the number of monitors is unknown".into()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_monitors_condition(attr_str: String) -> (bool, String) {
+    parse_monitors(&attr_str);
+    (false, "because monitors are only counted on Linux".into())
+}
+
+/// The generated check of the monitors, which evaluates to the reason to ignore when there are not
+/// enough monitors, the target of the example is checked in the generated code.
+#[cfg(feature = "runtime")]
+pub(crate) fn monitors_check_tokens(required: usize) -> proc_macro2::TokenStream {
+    quote::quote! {{
+        #[cfg(target_os = "linux")]
+        let reason = {
+            let xrandr_monitors = || -> Option<usize> {
+                std::env::var_os("DISPLAY").filter(|display| !display.is_empty())?;
+                let output = std::process::Command::new("xrandr")
+                    .arg("--listmonitors")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())?;
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()?
+                    .strip_prefix("Monitors:")?
+                    .trim()
+                    .parse()
+                    .ok()
+            };
+            let drm_monitors = || -> Option<usize> {
+                let statuses: Vec<String> = std::fs::read_dir("/sys/class/drm")
+                    .ok()?
+                    .flatten()
+                    .filter_map(|entry| std::fs::read_to_string(entry.path().join("status")).ok())
+                    .collect();
+                if statuses.is_empty() {
+                    return None;
+                }
+                Some(statuses.iter().filter(|status| status.trim() == "connected").count())
+            };
+            match xrandr_monitors().or_else(drm_monitors) {
+                Some(count) if count >= #required => None,
+                Some(count) => Some(format!(
+                    "because {} monitors detected, at least {} required",
+                    count, #required
+                )),
+                None => Some("because the number of monitors is unknown".to_string()),
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let reason = Some("because monitors are only counted on Linux".to_string());
+        reason
+    }}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_monitors;
+
+    #[test]
+    fn parse_monitor_number() {
+        //* Given
+        let attr_str = " 2 ";
+
+        //* When
+        let required = parse_monitors(attr_str);
+
+        //* Then
+        assert_eq!(required, 2);
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(unix)]
+use std::path::Path;
+
+#[cfg(unix)]
+use crate::utils::split_attr_list;
+
+/// Check the path is a mount point by the device ids, the mount point is on the other device than
+/// its parent, and the root is the mount point whose parent is itself.
+///
+/// This is a heuristic, a bind mount of a folder on the same filesystem has the same device id as
+/// its parent so it is not detected, and a subvolume of btrfs has its own device id so it is
+/// taken as a mount point even it is not mounted.
+#[cfg(unix)]
+pub(crate) fn is_mount_point(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(path), std::fs::metadata(path.join(".."))) {
+        (Ok(meta), Ok(parent)) => meta.dev() != parent.dev() || meta.ino() == parent.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn check_mount_condition(attr_str: String) -> (bool, String) {
+    let paths = split_attr_list(&attr_str);
+    let mut unmounted = vec![];
+    for path in paths.iter() {
+        if !is_mount_point(Path::new(path.trim_matches('"'))) {
+            unmounted.push(path.to_string());
+        }
+    }
+    let ignore_msg = if unmounted.len() == 1 {
+        format!("because {} is not mounted", unmounted[0])
+    } else {
+        format!(
+            "because following paths are not mounted: \n{}\n",
+            unmounted.join("\n")
+        )
+    };
+    (unmounted.is_empty(), ignore_msg)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn check_mount_condition(_attr_str: String) -> (bool, String) {
+    (
+        false,
+        "because the mount point is only checked on unix".into(),
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::is_mount_point;
+    use std::path::Path;
+
+    #[test]
+    fn detect_mount_points() {
+        //* Given
+        let paths = ["/", "/nothing"];
+
+        //* When
+        let mounted: Vec<_> = paths
+            .iter()
+            .map(|path| is_mount_point(Path::new(path)))
+            .collect();
+
+        //* Then
+        assert_eq!(mounted, vec![true, false]);
+    }
+}
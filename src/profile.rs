@@ -0,0 +1,55 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::ItemFn;
+
+use crate::target::ignore_on_target;
+
+/// The build profile is only known when compiling the test case, ex: `cargo test --release`
+/// compiles the test cases without debug assertions, so the ignore is generated with `cfg_attr`.
+pub(crate) fn release_macro(input: ItemFn) -> TokenStream {
+    ignore_on_target(
+        quote! { debug_assertions },
+        "because debug assertions are enabled",
+        input,
+    )
+}
+
+pub(crate) fn debug_macro(input: ItemFn) -> TokenStream {
+    ignore_on_target(
+        quote! { not(debug_assertions) },
+        "because debug assertions are disabled",
+        input,
+    )
+}
+
+/// The example is built in the profile of the runner, so the check is a constant of the example.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_profile_macro(release: bool, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let syn::Signature { ident, .. } = sig.clone();
+    let check_ident = syn::Ident::new(&format!("_check_{}", ident), proc_macro2::Span::call_site());
+    let ignore_msg = if release {
+        "because debug assertions are enabled"
+    } else {
+        "because debug assertions are disabled"
+    };
+    quote! {
+        fn #check_ident() -> Result<(), libtest_with::Failed> {
+            if cfg!(debug_assertions) != #release {
+                #ident();
+                Ok(())
+            } else {
+                Err(_ignore_reason(format_args!(#ignore_msg)))
+            }
+        }
+
+        #(#attrs)*
+        #vis #sig #block
+    }
+    .into()
+}
@@ -0,0 +1,74 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
+
+use proc_macro_error2::abort_call_site;
+
+use crate::utils::split_attr_list;
+
+/// Parse the ports of the local services, ex: `8080, 5432`
+pub(crate) fn parse_ports(attr_str: &str) -> Vec<u16> {
+    let ports: Vec<u16> = split_attr_list(attr_str)
+        .into_iter()
+        .map(|port| match port.parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => abort_call_site!(
+                "`{}` is not a port, ex: `#[test_with::listening(8080)]`",
+                port
+            ),
+        })
+        .collect();
+    if ports.is_empty() {
+        abort_call_site!("the port is required, ex: `#[test_with::listening(8080)]`");
+    }
+    ports
+}
+
+/// The port is listened by a local service when it accepts the connection on the IPv4 or IPv6
+/// loopback, so a service on the other hosts does not count.
+pub(crate) fn is_listening(port: u16) -> bool {
+    [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port)),
+    ]
+    .iter()
+    .any(|addr| TcpStream::connect(addr).is_ok())
+}
+
+pub(crate) fn check_listening_condition(attr_str: String) -> (bool, String) {
+    let idle_ports: Vec<String> = parse_ports(&attr_str)
+        .into_iter()
+        .filter(|port| !is_listening(*port))
+        .map(|port| port.to_string())
+        .collect();
+    let ignore_msg = if idle_ports.len() == 1 {
+        format!(
+            "because nothing is listening on localhost:{}",
+            idle_ports[0]
+        )
+    } else {
+        format!(
+            "because nothing is listening on following ports of localhost:\n{}\n",
+            idle_ports.join(", ")
+        )
+    };
+    (idle_ports.is_empty(), ignore_msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_listening, parse_ports};
+
+    #[test]
+    fn detect_listening_port() {
+        //* Given
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        //* When
+        let listening = is_listening(port);
+        drop(listener);
+
+        //* Then
+        assert!(listening);
+        assert_eq!(parse_ports("8080, 5432"), vec![8080, 5432]);
+    }
+}
@@ -0,0 +1,78 @@
+use proc_macro_error2::abort_call_site;
+
+use crate::utils::split_attr_list;
+
+/// Parse the systemd units, ex: `postgresql.service, docker.socket`
+pub(crate) fn parse_units(attr_str: &str) -> Vec<String> {
+    let units: Vec<String> = split_attr_list(attr_str)
+        .into_iter()
+        .map(|unit| unit.trim_matches('"').to_string())
+        .collect();
+    if units.is_empty() {
+        abort_call_site!(
+            "the unit is required, ex: `#[test_with::systemd_active(postgresql.service)]`"
+        );
+    }
+    units
+}
+
+/// The state of the unit reported by `systemctl is-active`, ex: `active`, `inactive` or `failed`,
+/// and it is `unknown` when `systemctl` can not be run, ex: the system is not booted with systemd.
+#[cfg(target_os = "linux")]
+pub(crate) fn unit_state(unit: &str) -> String {
+    std::process::Command::new("systemctl")
+        .args(["is-active", unit])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|state| !state.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn check_systemd_condition(attr_str: String) -> (bool, String) {
+    let inactive_units: Vec<String> = parse_units(&attr_str)
+        .into_iter()
+        .filter_map(|unit| {
+            let state = unit_state(&unit);
+            (state != "active").then(|| format!("{} is {}", unit, state))
+        })
+        .collect();
+    let ignore_msg = if inactive_units.len() == 1 {
+        format!("because {}", inactive_units[0])
+    } else {
+        format!(
+            "because following units are not active: \n{}\n",
+            inactive_units.join("\n")
+        )
+    };
+    (inactive_units.is_empty(), ignore_msg)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_systemd_condition(attr_str: String) -> (bool, String) {
+    parse_units(&attr_str);
+    (
+        false,
+        "because systemd units are only checked on Linux".into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_units;
+
+    #[test]
+    fn parse_systemd_units() {
+        //* Given
+        let attr_str = "postgresql.service, \"docker.socket\"";
+
+        //* When
+        let units = parse_units(attr_str);
+
+        //* Then
+        assert_eq!(units, vec!["postgresql.service", "docker.socket"]);
+        #[cfg(target_os = "linux")]
+        assert_ne!(super::unit_state("nothing.service"), "active");
+    }
+}
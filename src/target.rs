@@ -0,0 +1,104 @@
+use proc_macro::TokenStream;
+use proc_macro_error2::abort_call_site;
+use quote::quote;
+use syn::{Attribute, ItemFn};
+
+use crate::utils::{attr_to_string, check_before_attrs, has_test_attr, split_attr_list};
+
+/// The target families of `cfg(target_family)`
+const TARGET_FAMILIES: [&str; 3] = ["unix", "windows", "wasm"];
+
+/// Parse the target families, ex: `unix, wasm`
+pub(crate) fn parse_target_families(attr_str: &str) -> Vec<&str> {
+    let families: Vec<&str> = split_attr_list(attr_str)
+        .into_iter()
+        .map(|family| family.trim_matches('"'))
+        .collect();
+    if families.is_empty() {
+        abort_call_site!("the target family is required, ex: `#[test_with::target_family(unix)]`");
+    }
+    if let Some(family) = families.iter().find(|f| !TARGET_FAMILIES.contains(f)) {
+        abort_call_site!(
+            "`{}` is not a target family, the target family should be `unix`, `windows` or `wasm`",
+            family
+        );
+    }
+    families
+}
+
+// `#[wasm_bindgen_test]` registers the test case for wasm, so `#[test]` is not added
+fn has_wasm_bindgen_test_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "wasm_bindgen_test")
+    })
+}
+
+/// The target is only known when compiling the test case, so the ignore is generated with
+/// `cfg_attr` instead of checking the condition in the macro, and it still works when cross
+/// compiling.
+pub(crate) fn ignore_on_target(
+    predicate: proc_macro2::TokenStream,
+    ignore_msg: &str,
+    input: ItemFn,
+) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+
+    check_before_attrs(&attrs);
+    let test_attr = if has_test_attr(&attrs) || has_wasm_bindgen_test_attr(&attrs) {
+        quote! {}
+    } else {
+        quote! { #[test] }
+    };
+
+    quote! {
+        #(#attrs)*
+        #test_attr
+        #[cfg_attr(#predicate, ignore = #ignore_msg)]
+        #vis #sig #block
+    }
+    .into()
+}
+
+pub(crate) fn target_family_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let attr_str = attr_to_string(attr);
+    let families = parse_target_families(&attr_str);
+    let ignore_msg = format!("because the target family is not {}", families.join(" or "));
+    ignore_on_target(
+        quote! { not(any(#(target_family = #families),*)) },
+        &ignore_msg,
+        input,
+    )
+}
+
+pub(crate) fn not_wasm_macro(input: ItemFn) -> TokenStream {
+    ignore_on_target(
+        quote! { target_family = "wasm" },
+        "because the target is wasm",
+        input,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_target_families;
+
+    #[test]
+    fn parse_families() {
+        //* Given
+        let attr_str = "unix, \"wasm\"";
+
+        //* When
+        let families = parse_target_families(attr_str);
+
+        //* Then
+        assert_eq!(families, vec!["unix", "wasm"]);
+    }
+}
@@ -0,0 +1,157 @@
+use proc_macro_error2::abort_call_site;
+
+/// The version of the compiler, ex: `rustc 1.77.0 (aedd173a2 2024-03-17)`, which is captured by
+/// the build script.
+const RUSTC_VERSION: &str = env!("TEST_WITH_RUSTC_VERSION");
+
+/// Parse the `major.minor.patch` version, the missing parts are zero and the pre-release suffix,
+/// ex: `-nightly`, is ignored.
+pub(crate) fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// The comparators of the version requirement, the longer ones are matched first.
+pub(crate) const COMPARATORS: [&str; 5] = [">=", "<=", "==", ">", "<"];
+
+/// Compare the versions with one of the [COMPARATORS].
+pub(crate) fn compare_versions(
+    left: (u64, u64, u64),
+    comparator: &str,
+    right: (u64, u64, u64),
+) -> bool {
+    match comparator {
+        ">=" => left >= right,
+        "<=" => left <= right,
+        ">" => left > right,
+        "<" => left < right,
+        _ => left == right,
+    }
+}
+
+/// The first dotted number in the output of `--version`, ex: `6.0` of `ffmpeg version 6.0-static`.
+pub(crate) fn find_version(text: &str) -> Option<(u64, u64, u64)> {
+    text.split_whitespace().find_map(|word| {
+        let word = word.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let end = word
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(word.len());
+        let version = word[..end].trim_end_matches('.');
+        if !version.contains('.') {
+            return None;
+        }
+        let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+        Some((
+            parts.next()??,
+            parts.next().flatten().unwrap_or(0),
+            parts.next().flatten().unwrap_or(0),
+        ))
+    })
+}
+
+/// The generated code of [find_version], which evaluates to the version found in the `text`.
+#[cfg(feature = "runtime")]
+pub(crate) fn find_version_tokens(text: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote::quote! {
+        #text.split_whitespace().find_map(|word| {
+            let word = word.trim_start_matches(|c: char| !c.is_ascii_digit());
+            let end = word
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(word.len());
+            let version = word[..end].trim_end_matches('.');
+            if !version.contains('.') {
+                return None;
+            }
+            let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+            Some((
+                parts.next()??,
+                parts.next().flatten().unwrap_or(0),
+                parts.next().flatten().unwrap_or(0),
+            ))
+        })
+    }
+}
+
+/// The version of the compiler compiling the test cases
+pub(crate) fn rustc_version() -> Option<(u64, u64, u64)> {
+    RUSTC_VERSION
+        .split_whitespace()
+        .nth(1)
+        .and_then(parse_version)
+}
+
+pub(crate) fn check_min_rust_condition(attr_str: String) -> (bool, String) {
+    let min_version = match parse_version(attr_str.trim_matches('"')) {
+        Some(v) => v,
+        None => abort_call_site!("the minimal Rust version should be like `1.75` or `1.75.0`"),
+    };
+    match rustc_version() {
+        Some(version) if version >= min_version => (true, String::new()),
+        Some((major, minor, patch)) => (
+            false,
+            format!(
+                "because the Rust version {}.{}.{} is older than {}",
+                major, minor, patch, attr_str
+            ),
+        ),
+        None => (false, "because the Rust version is unknown".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_min_rust_condition, parse_version};
+
+    #[test]
+    fn parse_versions() {
+        //* Given
+        let versions = ["1.75", "1.75.1", "1.80.0-nightly", "2"];
+
+        //* When
+        let parsed: Vec<_> = versions.iter().map(|v| parse_version(v)).collect();
+
+        //* Then
+        assert_eq!(
+            parsed,
+            vec![
+                Some((1, 75, 0)),
+                Some((1, 75, 1)),
+                Some((1, 80, 0)),
+                Some((2, 0, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn min_rust_should_be_met() {
+        //* Given
+        let attr_str = "1.0".to_string();
+
+        //* When
+        let (is_ok, ignore_msg) = check_min_rust_condition(attr_str);
+
+        //* Then
+        assert!(is_ok);
+        assert!(ignore_msg.is_empty());
+    }
+
+    #[test]
+    fn min_rust_should_not_be_met() {
+        //* Given
+        let attr_str = "999.0".to_string();
+
+        //* When
+        let (is_ok, ignore_msg) = check_min_rust_condition(attr_str);
+
+        //* Then
+        assert!(!is_ok);
+        assert!(ignore_msg.contains("is older than 999.0"));
+    }
+}
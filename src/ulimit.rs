@@ -0,0 +1,51 @@
+use proc_macro_error2::abort_call_site;
+
+/// Parse the minimal number of the open files, ex: `4096`
+pub(crate) fn parse_open_files_limitation(attr_str: &str) -> u64 {
+    match attr_str.trim().parse::<u64>() {
+        Ok(limit) if limit > 0 => limit,
+        _ => abort_call_site!("the open files limitation should be a positive number, ex: 4096"),
+    }
+}
+
+/// The soft limit of the open files of the process, `None` for unlimited
+#[cfg(unix)]
+fn open_files_limit() -> Option<u64> {
+    let mut rlim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `rlim` is a valid pointer to the `rlimit` struct
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlim) } != 0
+        || rlim.rlim_cur == libc::RLIM_INFINITY
+    {
+        return None;
+    }
+    // `rlim_t` is not `u64` on every platform
+    #[allow(clippy::unnecessary_cast)]
+    Some(rlim.rlim_cur as u64)
+}
+
+#[cfg(unix)]
+pub(crate) fn check_ulimit_condition(attr_str: String) -> (bool, String) {
+    let min_open_files = parse_open_files_limitation(&attr_str);
+    match open_files_limit() {
+        Some(limit) if limit < min_open_files => (
+            false,
+            format!(
+                "because the open files limit {} is less than {}",
+                limit, min_open_files
+            ),
+        ),
+        _ => (true, String::new()),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn check_ulimit_condition(attr_str: String) -> (bool, String) {
+    parse_open_files_limitation(&attr_str);
+    (
+        false,
+        "because the open files limit is only supported on Unix".into(),
+    )
+}
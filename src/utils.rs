@@ -4,18 +4,26 @@ use proc_macro::TokenStream;
 #[cfg(feature = "ign-msg")]
 use proc_macro2::Span;
 use proc_macro2::TokenTree;
-use proc_macro_error2::abort_call_site;
+use proc_macro_error2::{abort_call_site, emit_warning};
 use quote::quote;
+use syn::Ident;
 #[cfg(feature = "ign-msg")]
 use syn::Signature;
-use syn::{Attribute, Meta};
-use syn::{Block, Ident, Item, ItemFn, ItemMod};
+use syn::{parse_quote, Attribute, Meta};
+use syn::{Block, Item, ItemFn, ItemMacro, ItemMod, Stmt};
 
-// check for `#[test]`, `#[tokio::test]`, `#[async_std::test]`
+use crate::condition::split_top_level;
+
+// check for `#[test]`, `#[tokio::test]`, `#[async_std::test]`, and the test attributes of other
+// test frameworks, `#[rstest]`, `#[test_case(..)]` and `#[proptest]`, which generate the `#[test]`
+// by themselves
 pub(crate) fn has_test_attr(attrs: &[Attribute]) -> bool {
     for attr in attrs.iter() {
         if let Some(seg) = attr.path().segments.last() {
-            if seg.ident == "test" {
+            if ["test", "rstest", "test_case", "proptest", "property_test"]
+                .iter()
+                .any(|name| seg.ident == name)
+            {
                 return true;
             }
         }
@@ -63,22 +71,28 @@ pub(crate) fn check_before_attrs(attrs: &[Attribute]) {
     }
 }
 
-// check for `#[cfg(test)]`
+// check for `#[cfg(test)]`, and `#[cfg(all(test, ..))]` also counts, but `#[cfg(not(test))]` does
+// not
 pub(crate) fn has_test_cfg(attrs: &[Attribute]) -> bool {
-    for attr in attrs.iter() {
-        if let Meta::List(metalist) = &attr.meta {
-            for token in metalist.tokens.clone().into_iter() {
-                if let TokenTree::Group(group) = token {
-                    for tt in group.stream().into_iter() {
-                        if "test" == tt.to_string() {
-                            return true;
-                        }
-                    }
-                }
-            }
+    attrs.iter().any(|attr| match &attr.meta {
+        Meta::List(metalist) if metalist.path.is_ident("cfg") => {
+            cfg_implies_test(metalist.tokens.clone())
+        }
+        _ => false,
+    })
+}
+
+fn cfg_implies_test(predicate: proc_macro2::TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = predicate.into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Ident(ident)] => ident == "test",
+        [TokenTree::Ident(ident), TokenTree::Group(group)] if ident == "all" => {
+            split_top_level(group.stream())
+                .into_iter()
+                .any(cfg_implies_test)
         }
+        _ => false,
     }
-    false
 }
 
 #[cfg(feature = "ign-msg")]
@@ -105,6 +119,188 @@ pub(crate) fn is_module(context: &TokenStream) -> bool {
     re.is_match(&context.to_string())
 }
 
+/// Join the tokens of the attribute without the spaces between them, ex: `/ bin / sh` becomes
+/// `/bin/sh`, but the spaces inside the string literals are kept, such that the quoted paths with
+/// spaces, ex: `"/Applications/My App.app"`, are not mangled.
+pub(crate) fn attr_to_string(attr: TokenStream) -> String {
+    tokens_to_string(attr.into())
+}
+
+pub(crate) fn tokens_to_string(tokens: proc_macro2::TokenStream) -> String {
+    let mut attr_str = String::new();
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Brace => ("{", "}"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                attr_str.push_str(open);
+                attr_str.push_str(&tokens_to_string(group.stream()));
+                attr_str.push_str(close);
+            }
+            token => attr_str.push_str(&token.to_string()),
+        }
+    }
+    attr_str
+}
+
+/// Join the items of the env attribute, the variable names are joined without spaces, but the
+/// value after `=` keeps the spaces between words, ex: `VAR = some value` becomes
+/// `VAR=some value`.
+#[cfg(feature = "runtime")]
+pub(crate) fn env_attr_to_string(attr: TokenStream) -> String {
+    env_attr_items(attr.into())
+        .into_iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!("{}={}", name, value),
+            None => name,
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// split the env attribute into the variable names with the optional values after `=`
+fn env_attr_items(tokens: proc_macro2::TokenStream) -> Vec<(String, Option<String>)> {
+    let mut items = vec![];
+    let mut name = proc_macro2::TokenStream::new();
+    let mut value: Option<Vec<TokenTree>> = None;
+    let mut push_item = |name: &mut proc_macro2::TokenStream, value: &mut Option<Vec<_>>| {
+        items.push((
+            tokens_to_string(std::mem::take(name)),
+            value.take().map(value_tokens_to_string),
+        ));
+    };
+    for token in tokens {
+        match (&token, value.as_mut()) {
+            (TokenTree::Punct(p), _) if p.as_char() == ',' => push_item(&mut name, &mut value),
+            (TokenTree::Punct(p), None) if p.as_char() == '=' => value = Some(vec![]),
+            (_, Some(value)) => value.push(token),
+            (_, None) => name.extend([token]),
+        }
+    }
+    if !name.is_empty() || value.is_some() {
+        push_item(&mut name, &mut value);
+    }
+    items
+}
+
+// the words of the value are separated by a space, and the punctuations are joined to the words
+fn value_tokens_to_string(tokens: Vec<TokenTree>) -> String {
+    let mut value = String::new();
+    let mut prev_is_word = false;
+    for token in tokens {
+        let is_word = matches!(token, TokenTree::Ident(_) | TokenTree::Literal(_));
+        if is_word && prev_is_word {
+            value.push(' ');
+        }
+        value.push_str(&tokens_to_string(token.into()));
+        prev_is_word = is_word;
+    }
+    value
+}
+
+/// Take out the `reason="..."` option, which overrides the generated ignore message, ex:
+/// `#[test_with::tcp(db:5432, reason="needs the staging DB tunnel")]`.  The reason can contain
+/// commas, so it is taken out before the other options.
+pub(crate) fn split_reason_option(attr_str: &str) -> (String, Option<String>) {
+    let start = match attr_str
+        .match_indices("reason=\"")
+        .map(|(i, _)| i)
+        .find(|i| *i == 0 || attr_str[..*i].ends_with(','))
+    {
+        Some(start) => start,
+        None => return (attr_str.to_string(), None),
+    };
+    let literal_start = start + "reason=".len();
+    // the end of the string literal, the escaped quotes are skipped
+    let mut escaped = false;
+    let mut literal_end = None;
+    for (i, c) in attr_str[literal_start + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                literal_end = Some(literal_start + 1 + i + 1);
+                break;
+            }
+            _ => (),
+        }
+    }
+    let reason = match literal_end
+        .and_then(|end| syn::parse_str::<syn::LitStr>(&attr_str[literal_start..end]).ok())
+    {
+        Some(reason) => reason.value(),
+        None => abort_call_site!("the reason should be a string literal, ex: `reason=\"why\"`"),
+    };
+    let before = attr_str[..start].trim_end_matches(',');
+    let after = attr_str[literal_end.expect("the reason is parsed")..].trim_start_matches(',');
+    let remaining = match (before.is_empty(), after.is_empty()) {
+        (false, false) => format!("{},{}", before, after),
+        _ => format!("{}{}", before, after),
+    };
+    (remaining, Some(reason))
+}
+
+/// Take out the `allow_non_test` option, which confirms that the function without test attribute
+/// is intended to be a test case.
+pub(crate) fn split_allow_non_test_option(attr_str: &str) -> (String, bool) {
+    let items: Vec<&str> = attr_str.split(',').collect();
+    let remaining: Vec<&str> = items
+        .iter()
+        .copied()
+        .filter(|item| *item != "allow_non_test")
+        .collect();
+    (remaining.join(","), remaining.len() != items.len())
+}
+
+/// Take out the options shared by the functions and the modules, `reason="..."` and
+/// `allow_non_test`, and the remaining attribute is passed to the condition.
+pub(crate) fn split_condition_options(attr_str: &str) -> (String, Option<String>, bool) {
+    let (attr_str, reason) = split_reason_option(attr_str);
+    let (attr_str, allow_non_test) = split_allow_non_test_option(&attr_str);
+    (attr_str, reason, allow_non_test)
+}
+
+// the name of test case usually contains `test`, ex: `test_works`, or starts with `it_`, `should_`
+fn looks_like_test(ident: &Ident) -> bool {
+    let name = ident.to_string();
+    name.contains("test") || name.starts_with("it_") || name.starts_with("should_")
+}
+
+// the warning is emitted with `proc_macro_error2`, which only shows on the nightly toolchain, but it
+// never fails the build of the crates denying the warnings, ex: `-D warnings`
+fn warn_non_test(ident: &Ident) {
+    emit_warning!(
+        ident.span(),
+        "`{}` has no test attribute and will become a test case by `#[test_with::*]`, add `#[test]` or pass `allow_non_test` to confirm",
+        ident
+    );
+}
+
+// when the `test-with-explain` feature is enabled, the ignore reason is emitted as a note on the
+// ignored item, there is no warning API for proc macro on stable, so a deprecated item is used, and
+// the note fails the build with `-D warnings`, which is fine for the opt-in feature of debugging
+fn explain_note(ident: &Ident, attr_str: &str, ignore_msg: &str) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "test-with-explain") {
+        return quote! {};
+    }
+    let note = format!(
+        "`{}` is ignored with the condition `{}` {}",
+        ident, attr_str, ignore_msg
+    );
+    let explain = Ident::new("IgnoredByTestWith", ident.span());
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct IgnoredByTestWith;
+            let _ = #explain;
+        };
+    }
+}
+
 pub(crate) fn fn_macro(
     attr: TokenStream,
     input: ItemFn,
@@ -124,10 +320,14 @@ pub(crate) fn fn_macro(
         sig,
         block,
     } = input;
-    let attr_str = attr.to_string().replace(' ', "");
-    let (all_var_exist, ignore_msg) = check_condition(attr_str);
+    let (attr_str, reason, allow_non_test) = split_condition_options(&attr_to_string(attr));
+    let (all_var_exist, ignore_msg) = check_condition(attr_str.clone());
+    let ignore_msg = reason.unwrap_or(ignore_msg);
     check_before_attrs(&attrs);
     let has_test = has_test_attr(&attrs);
+    if !(has_test || allow_non_test || looks_like_test(&sig.ident)) {
+        warn_non_test(&sig.ident);
+    }
 
     if all_var_exist && has_test {
         quote! {
@@ -143,18 +343,22 @@ pub(crate) fn fn_macro(
         }
         .into()
     } else if has_test {
+        let explain_note = explain_note(&sig.ident, &attr_str, &ignore_msg);
         #[cfg(feature = "ign-msg")]
         rewrite_fn_sig_with_msg(&mut sig, &ignore_msg);
         quote! {
+           #explain_note
            #(#attrs)*
            #[ignore = #ignore_msg ]
            #vis #sig #block
         }
         .into()
     } else {
+        let explain_note = explain_note(&sig.ident, &attr_str, &ignore_msg);
         #[cfg(feature = "ign-msg")]
         rewrite_fn_sig_with_msg(&mut sig, &ignore_msg);
         quote! {
+           #explain_note
            #(#attrs)*
            #[test]
            #[ignore = #ignore_msg ]
@@ -164,6 +368,73 @@ pub(crate) fn fn_macro(
     }
 }
 
+// add `#[ignore]` on the test function of the ignored module and keep the original function, such
+// that the attributes and parameters for other test frameworks, ex: `#[rstest]` with `#[case]`,
+// still work
+fn ignore_test_fn(mut item_fn: ItemFn, ignore_msg: &String) -> ItemFn {
+    if has_test_attr(&item_fn.attrs) {
+        #[cfg(feature = "ign-msg")]
+        {
+            item_fn.sig.ident = rewrite_fn_ident_with_msg(item_fn.sig.ident, ignore_msg);
+        }
+        item_fn.attrs.push(parse_quote!(#[ignore = #ignore_msg]));
+    }
+    item_fn
+}
+
+// the start of the function item ending at `fn`, which is before its attributes, visibility, ex:
+// `pub(crate)`, and qualifiers, ex: `async`
+fn fn_item_start(tokens: &[TokenTree]) -> usize {
+    let mut start = tokens.len();
+    loop {
+        start -= match &tokens[..start] {
+            [.., TokenTree::Punct(p), TokenTree::Group(g)]
+                if p.as_char() == '#' && g.delimiter() == proc_macro2::Delimiter::Bracket =>
+            {
+                2
+            }
+            [.., TokenTree::Ident(ident), TokenTree::Group(g)]
+                if ident == "pub" && g.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+            {
+                2
+            }
+            [.., TokenTree::Ident(ident), TokenTree::Literal(_)] if ident == "extern" => 2,
+            [.., TokenTree::Ident(ident)]
+                if ["pub", "async", "unsafe", "const", "extern"]
+                    .iter()
+                    .any(|keyword| ident == keyword) =>
+            {
+                1
+            }
+            _ => return start,
+        };
+    }
+}
+
+// add `#[ignore]` on the test functions in the `proptest!` block of the ignored module, the
+// attribute is put before the attributes and visibility of the function, which are kept on the
+// test functions generated by `proptest!`
+fn ignore_proptest_macro(mut item_macro: ItemMacro, ignore_msg: &String) -> ItemMacro {
+    let is_proptest = item_macro
+        .mac
+        .path
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "proptest");
+    if is_proptest {
+        let mut tokens: Vec<TokenTree> = vec![];
+        for token in std::mem::take(&mut item_macro.mac.tokens) {
+            if matches!(&token, TokenTree::Ident(ident) if ident == "fn") {
+                let start = fn_item_start(&tokens);
+                tokens.splice(start..start, quote! { #[ignore = #ignore_msg] });
+            }
+            tokens.push(token);
+        }
+        item_macro.mac.tokens = tokens.into_iter().collect();
+    }
+    item_macro
+}
+
 pub(crate) fn mod_macro(
     attr: TokenStream,
     input: ItemMod,
@@ -179,76 +450,45 @@ pub(crate) fn mod_macro(
     } = input;
     if let Some(content) = content {
         let content = content.1;
-        let attr_str = attr.to_string().replace(' ', "");
-        let (all_var_exist, ignore_msg) = check_condition(attr_str);
-        let has_test = has_test_cfg(&attrs);
+        // the functions in the module keep their own test attributes, so `allow_non_test` is
+        // only taken out of the condition
+        let (attr_str, reason, _) = split_condition_options(&attr_to_string(attr));
+        let (all_var_exist, ignore_msg) = check_condition(attr_str.clone());
+        let ignore_msg = reason.unwrap_or(ignore_msg);
+        let explain_note = explain_note(&ident, &attr_str, &ignore_msg);
+        // the `#[cfg(test)]` of the module is kept, and only added when it is absent
+        let cfg_test = if has_test_cfg(&attrs) {
+            quote! {}
+        } else {
+            quote! { #[cfg(test)] }
+        };
 
-        if all_var_exist && has_test {
-            quote! {
-                #(#attrs)*
-                #[cfg(test)]
-                #vis #mod_token #ident {
-                    #(#content)*
-                }
-            }
-            .into()
-        } else if all_var_exist {
+        if all_var_exist {
             quote! {
                 #(#attrs)*
-                #[cfg(test)]
+                #cfg_test
                 #vis #mod_token #ident {
                     #(#content)*
                 }
             }
             .into()
-        } else if has_test {
-            let fn_names: Vec<Ident> = content
-                .into_iter()
-                .filter_map(|i| match i {
-                    Item::Fn(ItemFn { sig, .. }) => {
-                        #[cfg(not(feature = "ign-msg"))]
-                        let ident = sig.ident;
-                        #[cfg(feature = "ign-msg")]
-                        let ident = rewrite_fn_ident_with_msg(sig.ident, &ignore_msg);
-                        Some(ident)
-                    }
-                    _ => None,
-                })
-                .collect();
-            quote! {
-                #(#attrs)*
-                #vis #mod_token #ident {
-                    #(
-                        #[test]
-                        #[ignore = #ignore_msg ]
-                        fn #fn_names () {}
-                    )*
-                }
-            }
-            .into()
         } else {
-            let fn_names: Vec<Ident> = content
+            let content: Vec<Item> = content
                 .into_iter()
-                .filter_map(|i| match i {
-                    Item::Fn(ItemFn { sig, .. }) => {
-                        #[cfg(not(feature = "ign-msg"))]
-                        let ident = sig.ident;
-                        #[cfg(feature = "ign-msg")]
-                        let ident = rewrite_fn_ident_with_msg(sig.ident, &ignore_msg);
-                        Some(ident)
+                .map(|i| match i {
+                    Item::Fn(item_fn) => Item::Fn(ignore_test_fn(item_fn, &ignore_msg)),
+                    Item::Macro(item_macro) => {
+                        Item::Macro(ignore_proptest_macro(item_macro, &ignore_msg))
                     }
-                    _ => None,
+                    _ => i,
                 })
                 .collect();
             quote! {
+                #explain_note
                 #(#attrs)*
-                #[cfg(test)]
+                #cfg_test
                 #vis #mod_token #ident {
-                    #(
-                        #[test]
-                        #[ignore = #ignore_msg ]
-                        fn #fn_names () {}
-                    )*
+                    #(#content)*
                 }
             }
             .into()
@@ -266,12 +506,57 @@ pub(crate) fn lock_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
         block,
     } = input;
     let Block { stmts, .. } = *block;
+    let locked_body = locked_body(attr, &stmts);
+
+    check_before_attrs(&attrs);
+
+    if has_test_attr(&attrs) {
+        quote! {
+            #(#attrs)*
+            #vis #sig {
+                #locked_body
+            }
+        }
+        .into()
+    } else {
+        quote! {
+            #(#attrs)*
+            #[test]
+            #vis #sig {
+                #locked_body
+            }
+        }
+        .into()
+    }
+}
+
+/// The statements run after the file lock of the `lock` attribute is acquired, and it is shared
+/// by `#[test_with::lock]` and `#[test_with::runtime_lock]`.
+pub(crate) fn locked_body(attr: TokenStream, stmts: &[Stmt]) -> proc_macro2::TokenStream {
     let attr_str = attr.to_string().replace(' ', "");
-    let lock_attrs: Vec<&str> = attr_str.split(',').collect();
+    let mut poll_ms: u64 = 50;
+    let mut flock = false;
+    let mut lock_attrs: Vec<&str> = Vec::new();
+    for item in attr_str.split(',') {
+        if let Some(ms) = item.strip_prefix("poll_ms=") {
+            poll_ms = match ms.parse::<u64>() {
+                Ok(ms) if ms > 0 => ms,
+                _ => abort_call_site!("`poll_ms` should be a positive number of milliseconds"),
+            };
+        } else if let Some(backend) = item.strip_prefix("backend=") {
+            flock = match backend {
+                "flock" => true,
+                "create_new" => false,
+                _ => abort_call_site!("`backend` should be `create_new` or `flock`"),
+            };
+        } else {
+            lock_attrs.push(item);
+        }
+    }
     let (lock_name, wait_time) = match (lock_attrs.first(), lock_attrs.get(1)) {
         (Some(name), None) => (name, 60),
         (Some(name), Some(sec)) => {
-            if let Ok(wait_time) = sec.parse::<usize>() {
+            if let Ok(wait_time) = sec.parse::<u64>() {
                 (name, wait_time)
             } else {
                 abort_call_site!("`The second parameter of #[test_with::lock]` should be a number for waiting time");
@@ -281,109 +566,1790 @@ pub(crate) fn lock_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
             abort_call_site!("`#[test_with::lock]` need a name for the file lock");
         }
     };
-    let lock_file = std::env::temp_dir().join(lock_name).display().to_string();
+    let lock_file = std::env::temp_dir()
+        .join(expand_env_vars(lock_name))
+        .display()
+        .to_string();
 
-    check_before_attrs(&attrs);
-
-    if has_test_attr(&attrs) {
-        quote! {
-            #(#attrs)*
-            #vis #sig {
-                let mut _test_with_lock = None;
-                for i in 0..#wait_time {
-                    if let Ok(file) = std::fs::File::create_new(#lock_file) {
-                        _test_with_lock = Some(file);
-                        break;
-                    }
-                    std::thread::sleep(std::time::Duration::from_secs(1));
+    // try to lock with the backend, and the lock is released when `_test_with_lock` is dropped
+    // for the `flock` backend, or when the marker file is removed for the `create_new` backend
+    let (open_lock, try_lock, unlock) = if flock {
+        (
+            quote! {
+                // the advisory lock is taken with `flock` on unix and `LockFileEx` on Windows,
+                // which are declared here because the test crate may not depend on `libc`
+                fn _test_with_try_flock(file: &std::fs::File) -> bool {
+                    #[cfg(unix)]
+                    let locked = {
+                        unsafe extern "C" {
+                            fn flock(fd: i32, operation: i32) -> i32;
+                        }
+                        // LOCK_EX | LOCK_NB
+                        unsafe { flock(std::os::unix::io::AsRawFd::as_raw_fd(file), 2 | 4) == 0 }
+                    };
+                    #[cfg(windows)]
+                    let locked = {
+                        #[allow(dead_code)]
+                        #[repr(C)]
+                        struct Overlapped {
+                            internal: usize,
+                            internal_high: usize,
+                            offset: u32,
+                            offset_high: u32,
+                            event: *mut std::ffi::c_void,
+                        }
+                        unsafe extern "system" {
+                            fn LockFileEx(
+                                file: *mut std::ffi::c_void,
+                                flags: u32,
+                                reserved: u32,
+                                bytes_low: u32,
+                                bytes_high: u32,
+                                overlapped: *mut Overlapped,
+                            ) -> i32;
+                        }
+                        let mut overlapped = Overlapped {
+                            internal: 0,
+                            internal_high: 0,
+                            offset: 0,
+                            offset_high: 0,
+                            event: std::ptr::null_mut(),
+                        };
+                        // LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY
+                        unsafe {
+                            LockFileEx(
+                                std::os::windows::io::AsRawHandle::as_raw_handle(file),
+                                2 | 1,
+                                0,
+                                u32::MAX,
+                                u32::MAX,
+                                &mut overlapped,
+                            ) != 0
+                        }
+                    };
+                    #[cfg(not(any(unix, windows)))]
+                    let locked: bool =
+                        compile_error!("`backend=flock` only works on unix and Windows");
+                    locked
                 }
-                if _test_with_lock.is_none() {
-                    panic!("Fail to acquire the lock for the testcase")
+                let lock_file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(false)
+                    .open(#lock_file)
+                    .expect("Fail to open the lock file for the testcase");
+            },
+            quote! {
+                if _test_with_try_flock(&lock_file) {
+                    _test_with_lock = Some(lock_file);
+                    break;
+                }
+            },
+            quote! {},
+        )
+    } else {
+        (
+            quote! {},
+            quote! {
+                if let Ok(file) = std::fs::File::create_new(#lock_file) {
+                    _test_with_lock = Some(file);
+                    break;
                 }
-                #(#stmts)*
+            },
+            quote! {
                 if std::fs::remove_file(#lock_file).is_err() {
                     panic!("Fail to unlock the testcase")
                 }
+            },
+        )
+    };
+
+    // poll the lock with exponential backoff capped at 1 second, and the total waiting time is
+    // still bounded by the waiting seconds
+    quote! {
+        #open_lock
+        let mut _test_with_lock = None;
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(#wait_time);
+        let mut poll = std::time::Duration::from_millis(#poll_ms);
+        loop {
+            #try_lock
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                break;
             }
+            std::thread::sleep(poll.min(deadline - now));
+            poll = (poll * 2).min(std::time::Duration::from_secs(1));
         }
-        .into()
+        if _test_with_lock.is_none() {
+            panic!("Fail to acquire the lock for the testcase")
+        }
+        #(#stmts)*
+        #unlock
+    }
+}
+
+pub(crate) fn throttle_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let Block { stmts, .. } = *block;
+    let limit = match attr.to_string().replace(' ', "").parse::<usize>() {
+        Ok(limit) if limit > 0 => limit,
+        _ => abort_call_site!(
+            "`#[test_with::throttle]` needs a positive number of the concurrent test cases"
+        ),
+    };
+
+    check_before_attrs(&attrs);
+    let test_attr = if has_test_attr(&attrs) {
+        quote! {}
     } else {
-        quote! {
-            #(#attrs)*
-            #[test]
-            #vis #sig {
-                let mut _test_with_lock = None;
-                for i in 0..#wait_time {
-                    if let Ok(file) = std::fs::File::create_new(#lock_file) {
-                        _test_with_lock = Some(file);
-                        break;
-                    }
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                }
-                if _test_with_lock.is_none() {
-                    panic!("Fail to acquire the lock for the testcase")
+        quote! { #[test] }
+    };
+
+    // the running test cases are counted by the limit in the test process, and the slot is released
+    // when the guard is dropped, even the test case panics
+    quote! {
+        #(#attrs)*
+        #test_attr
+        #vis #sig {
+            struct _TestWithThrottleSlot;
+            impl Drop for _TestWithThrottleSlot {
+                fn drop(&mut self) {
+                    let (running, released) = crate::_test_with_throttle(#limit);
+                    *running.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) -= 1;
+                    released.notify_all();
                 }
-                #(#stmts)*
-                if std::fs::remove_file(#lock_file).is_err() {
-                    panic!("Fail to unlock the testcase")
+            }
+            let _test_with_slot = {
+                let (running, released) = crate::_test_with_throttle(#limit);
+                let mut running = released
+                    .wait_while(
+                        running.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+                        |running| *running >= #limit,
+                    )
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                *running += 1;
+                _TestWithThrottleSlot
+            };
+            #(#stmts)*
+        }
+    }
+    .into()
+}
+
+/// The number of the running test cases of `#[test_with::throttle]` keyed by the limit, which is
+/// generated once at the crate root by `test_with::throttles!()`.
+pub(crate) fn throttles_tokens() -> proc_macro2::TokenStream {
+    quote! {
+        #[allow(dead_code)]
+        #[doc(hidden)]
+        pub(crate) fn _test_with_throttle(
+            limit: usize,
+        ) -> &'static (std::sync::Mutex<usize>, std::sync::Condvar) {
+            static RUNNING: std::sync::OnceLock<
+                std::sync::Mutex<
+                    std::collections::HashMap<
+                        usize,
+                        &'static (std::sync::Mutex<usize>, std::sync::Condvar),
+                    >,
+                >,
+            > = std::sync::OnceLock::new();
+            let mut running = RUNNING
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            running.entry(limit).or_insert_with(|| {
+                Box::leak(Box::new((
+                    std::sync::Mutex::new(0),
+                    std::sync::Condvar::new(),
+                )))
+            })
+        }
+    }
+}
+
+pub(crate) fn serial_group_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let Block { stmts, .. } = *block;
+    let group = attr_to_string(attr).trim_matches('"').to_string();
+    if group.is_empty() || group.contains(',') {
+        abort_call_site!(
+            "`#[test_with::serial_group]` needs one group name, ex: `serial_group(DB)`"
+        );
+    }
+
+    check_before_attrs(&attrs);
+    let test_attr = if has_test_attr(&attrs) {
+        quote! {}
+    } else {
+        quote! { #[test] }
+    };
+
+    // the guard is held until the test case ends, and the poisoned mutex of a panicked test case
+    // is still usable for the others
+    quote! {
+        #(#attrs)*
+        #test_attr
+        #vis #sig {
+            let _test_with_serial_guard = crate::_test_with_serial_group(#group)
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            #(#stmts)*
+        }
+    }
+    .into()
+}
+
+/// The registry of the mutexes of `#[test_with::serial_group]` keyed by the group name, which is
+/// generated once at the crate root by `test_with::serial_groups!()`.
+pub(crate) fn serial_groups_tokens() -> proc_macro2::TokenStream {
+    quote! {
+        #[allow(dead_code)]
+        #[doc(hidden)]
+        pub(crate) fn _test_with_serial_group(name: &str) -> &'static std::sync::Mutex<()> {
+            static GROUPS: std::sync::OnceLock<
+                std::sync::Mutex<std::collections::HashMap<String, &'static std::sync::Mutex<()>>>,
+            > = std::sync::OnceLock::new();
+            let mut groups = GROUPS
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            groups
+                .entry(name.to_string())
+                .or_insert_with(|| Box::leak(Box::new(std::sync::Mutex::new(()))))
+        }
+    }
+}
+
+pub(crate) fn with_env_macro(attr: TokenStream, input: ItemFn) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let Block { stmts, .. } = *block;
+    let mut names = vec![];
+    let mut values = vec![];
+    for (name, value) in env_attr_items(attr.into()) {
+        match value {
+            Some(value) if !name.is_empty() => {
+                names.push(name);
+                values.push(value.trim_matches('"').to_string());
+            }
+            _ => abort_call_site!(
+                "`#[test_with::with_env]` needs the variables with values, ex: `LOG=debug, TZ=UTC`"
+            ),
+        }
+    }
+    if names.is_empty() {
+        abort_call_site!(
+            "`#[test_with::with_env]` needs the variables with values, ex: `LOG=debug, TZ=UTC`"
+        );
+    }
+
+    check_before_attrs(&attrs);
+    let test_attr = if has_test_attr(&attrs) {
+        quote! {}
+    } else {
+        quote! { #[test] }
+    };
+
+    // the previous values are kept in the guard and restored when the guard is dropped, even the
+    // test case panics, and `set_var`, `remove_var` are unsafe since Rust 2024 edition
+    quote! {
+        #(#attrs)*
+        #test_attr
+        #vis #sig {
+            struct _TestWithEnvGuard(Vec<(&'static str, Option<std::ffi::OsString>)>);
+            impl Drop for _TestWithEnvGuard {
+                fn drop(&mut self) {
+                    for (name, value) in self.0.drain(..).rev() {
+                        #[allow(unused_unsafe)]
+                        unsafe {
+                            match value {
+                                Some(value) => std::env::set_var(name, value),
+                                None => std::env::remove_var(name),
+                            }
+                        }
+                    }
                 }
             }
+            let _test_with_env = _TestWithEnvGuard(vec![#((#names, std::env::var_os(#names))),*]);
+            #[allow(unused_unsafe)]
+            unsafe {
+                #(std::env::set_var(#names, #values);)*
+            }
+            #(#stmts)*
         }
-        .into()
     }
+    .into()
+}
+
+/// Expand `${VAR}` in the name with the environment variables when compiling, and the unset
+/// variables expand to empty.
+pub(crate) fn expand_env_vars(name: &str) -> String {
+    let re = unsafe { Regex::new(r"\$\{(\w+)\}").unwrap_unchecked() };
+    re.replace_all(name, |caps: &regex::Captures| {
+        std::env::var(&caps[1]).unwrap_or_default()
+    })
+    .to_string()
 }
 
 /// Sanitize the attribute string to remove any leading or trailing whitespace
 /// and split the string into an iterator of individual environment variable names.
 pub fn sanitize_env_vars_attr(attr_str: &str) -> impl Iterator<Item = &str> {
-    attr_str.split(',').map(str::trim)
+    // the commas in `group(..)` and `in [..]` do not split the items
+    let mut depth = 0usize;
+    attr_str
+        .split(move |c| {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+            c == ',' && depth == 0
+        })
+        .map(str::trim)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::sanitize_env_vars_attr;
-
-    #[test]
-    fn sanitize_single_env_var() {
-        //* Given
-        let env_var = "FOO";
+/// Split the attribute string into the items separated by commas.  The whitespace around each
+/// item, including the newlines and tabs of a multiline attribute, is trimmed, and the empty items,
+/// ex: the one after a trailing comma, are dropped.  An empty list, ex: `env()`, is rejected.
+pub(crate) fn split_attr_list(attr_str: &str) -> Vec<&str> {
+    let items: Vec<&str> = attr_str
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+    if items.is_empty() {
+        abort_call_site!("at least one item is required in the condition");
+    }
+    items
+}
 
-        let attr_str = env_var.to_string();
+/// Split the aliases of a variable, ex: `DATABASE_URL | DB_URL`, the variable is set when any of
+/// the aliases is set.
+pub(crate) fn env_var_aliases(name: &str) -> Vec<&str> {
+    name.split('|').map(str::trim).collect()
+}
 
-        //* When
-        let result = sanitize_env_vars_attr(&attr_str).collect::<Vec<_>>();
+/// Take out the variables of `group(..)`, ex: `group(AWS_KEY, AWS_SECRET)`, which are required to
+/// be all set or all unset.
+pub(crate) fn env_var_group(item: &str) -> Option<Vec<&str>> {
+    let members = item
+        .strip_prefix("group")?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    Some(split_attr_list(members))
+}
 
-        //* Then
-        assert_eq!(result, vec!["FOO"]);
+/// Check the grouped variables, the group is set when all of them are set, and unset when none of
+/// them is set.  A partially set group is a misconfiguration instead of a missing variable, so it
+/// is returned as the error message to fail the test rather than ignore it.
+pub(crate) fn env_var_group_exists(members: &[&str]) -> Result<bool, String> {
+    let unset: Vec<&str> = members
+        .iter()
+        .copied()
+        .filter(|name| !env_var_exists(name))
+        .collect();
+    match unset.len() {
+        0 => Ok(true),
+        n if n == members.len() => Ok(false),
+        _ => Err(format!(
+            "the variables of group({}) should be all set or all unset, but {} not set",
+            members.join(", "),
+            unset.join(", ")
+        )),
     }
+}
 
-    #[test]
-    fn sanitize_multiple_env_vars() {
-        //* Given
-        let env_var1 = "FOO";
-        let env_var2 = "BAR";
-        let env_var3 = "BAZ";
-
-        let attr_str = format!("\t{},\n\t{},\n\t{}", env_var1, env_var2, env_var3);
+/// Check the environment variable is set, the name with a trailing `*` is a prefix, ex: `AWS_*`,
+/// and it is set when any variable with the prefix is set.
+pub(crate) fn env_var_exists(name: &str) -> bool {
+    env_var_aliases(name)
+        .into_iter()
+        .any(|alias| match alias.strip_suffix('*') {
+            Some(prefix) => {
+                std::env::vars_os().any(|(key, _)| key.to_string_lossy().starts_with(prefix))
+            }
+            None => std::env::var(alias).is_ok(),
+        })
+}
 
-        //* When
-        let result = sanitize_env_vars_attr(&attr_str).collect::<Vec<_>>();
+/// Check the environment variable is set with a non-empty value, `VAR=` is set but empty, which
+/// often means the variable is exported but not configured.
+pub(crate) fn env_var_nonempty(name: &str) -> bool {
+    env_var_aliases(name)
+        .into_iter()
+        .any(|alias| match alias.strip_suffix('*') {
+            Some(prefix) => std::env::vars_os()
+                .any(|(key, value)| key.to_string_lossy().starts_with(prefix) && !value.is_empty()),
+            None => std::env::var_os(alias).is_some_and(|value| !value.is_empty()),
+        })
+}
 
-        //* Then
-        assert_eq!(result, vec!["FOO", "BAR", "BAZ"]);
+/// Split the allowed values of a variable, ex: `ENVIRONMENT in [staging, prod]`, which is joined
+/// as `ENVIRONMENTin[staging,prod]` in the attribute string, the variable is required to be one of
+/// the values.
+pub(crate) fn split_env_value_set(item: &str) -> (&str, Option<Vec<String>>) {
+    let Some((head, values)) = item.strip_suffix(']').and_then(|item| item.split_once('[')) else {
+        return (item, None);
+    };
+    let name = match head.trim_end().strip_suffix("in") {
+        Some(name) if !name.trim().is_empty() => name.trim(),
+        _ => abort_call_site!("the allowed values should be like `ENVIRONMENT in [staging, prod]`"),
+    };
+    if name.contains('*') || env_var_group(name).is_some() {
+        abort_call_site!(
+            "the allowed values can not be used with the prefix or the group, ex: `{}`",
+            name
+        );
     }
+    let values: Vec<String> = split_attr_list(values)
+        .into_iter()
+        .map(|value| value.trim_matches('"').to_string())
+        .collect();
+    (name, Some(values))
+}
 
-    #[test]
-    fn sanitize_env_vars_with_whitespace() {
-        //* Given
-        let env_var = "FOO BAR";
+/// The value of the variable, the first set alias is used when the variable has aliases.
+pub(crate) fn env_var_value(name: &str) -> String {
+    env_var_aliases(name)
+        .into_iter()
+        .find_map(std::env::var_os)
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
 
-        let attr_str = env_var.to_string();
+/// Take out the `nonempty` option from the attribute string of env conditions, with the option,
+/// the variables are required to have non-empty values.
+pub(crate) fn split_nonempty_option(attr_str: &str) -> (String, bool) {
+    let mut nonempty = false;
+    let items: Vec<&str> = sanitize_env_vars_attr(attr_str)
+        .filter(|item| {
+            let is_option = *item == "nonempty";
+            nonempty |= is_option;
+            !is_option
+        })
+        .collect();
+    (items.join(","), nonempty)
+}
 
-        //* When
+/// The ignore message of env conditions when only one variable not found, the message of a prefix
+/// states which prefix had no matches, and the message of aliases lists all the tried names.
+pub(crate) fn missing_env_var_msg(name: &str) -> String {
+    if let Some(members) = env_var_group(name) {
+        return format!(
+            "because none of grouped variables {} found",
+            members.join(", ")
+        );
+    }
+    let aliases = env_var_aliases(name);
+    if aliases.len() > 1 {
+        return format!("because none of variables {} found", aliases.join(", "));
+    }
+    match name.strip_suffix('*') {
+        Some(prefix) => format!("because no variable with prefix {} found", prefix),
+        None => format!("because variable {} not found", name),
+    }
+}
+
+/// Take out the `at_least=K` option from the attribute string of env conditions, and return the
+/// variable names with the number of variables required to be present.  If the option is absent,
+/// all the variables are required.
+pub(crate) fn split_at_least_option(attr_str: &str) -> (Vec<&str>, Option<usize>) {
+    let mut var_names = vec![];
+    let mut at_least = None;
+    for item in sanitize_env_vars_attr(attr_str) {
+        match item
+            .strip_prefix("at_least")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+        {
+            Some(k) => at_least = Some(k.trim().parse::<usize>().ok()),
+            None => var_names.push(item),
+        }
+    }
+    match at_least {
+        Some(Some(k)) if k > 0 && k <= var_names.len() => (var_names, Some(k)),
+        Some(_) => abort_call_site!(
+            "`at_least` should be a number between 1 and the number of variables ({})",
+            var_names.len()
+        ),
+        None => (var_names, None),
+    }
+}
+
+/// Take out the `retries=N` option from the attribute string, and return the remaining items with
+/// the number of attempts.  If the option is absent, the target will be probed once.
+pub(crate) fn split_retries_option(attr_str: &str) -> (Vec<&str>, usize) {
+    let mut items = vec![];
+    let mut retries = 1;
+    for item in split_attr_list(attr_str) {
+        if let Some(n) = item.strip_prefix("retries=") {
+            retries = match n.parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => abort_call_site!("`retries` should be a positive number of attempts"),
+            };
+        } else {
+            items.push(item);
+        }
+    }
+    (items, retries)
+}
+
+/// The IP family forced by the `family=v4|v6` option of the tcp and icmp conditions
+#[derive(Clone, Copy)]
+pub(crate) enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    pub(crate) fn matches(&self, addr: &std::net::IpAddr) -> bool {
+        match self {
+            IpFamily::V4 => addr.is_ipv4(),
+            IpFamily::V6 => addr.is_ipv6(),
+        }
+    }
+
+    /// The value of the `family` option, which is also a part of the key of the probe cache
+    #[cfg(feature = "runtime")]
+    pub(crate) fn option(&self) -> &'static str {
+        match self {
+            IpFamily::V4 => "v4",
+            IpFamily::V6 => "v6",
+        }
+    }
+}
+
+impl std::fmt::Display for IpFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpFamily::V4 => write!(f, "IPv4"),
+            IpFamily::V6 => write!(f, "IPv6"),
+        }
+    }
+}
+
+/// Take out the `family=v4|v6` option, the family is not restricted if the option is absent.
+pub(crate) fn split_family_option(items: Vec<&str>) -> (Vec<&str>, Option<IpFamily>) {
+    let mut remaining = vec![];
+    let mut family = None;
+    for item in items {
+        if let Some(f) = item.strip_prefix("family=") {
+            family = match f {
+                "v4" => Some(IpFamily::V4),
+                "v6" => Some(IpFamily::V6),
+                _ => abort_call_site!("`family` should be `v4` or `v6`"),
+            };
+        } else {
+            remaining.push(item);
+        }
+    }
+    (remaining, family)
+}
+
+/// Expand the port range of the tcp condition, ex: `127.0.0.1:8000-8010`, to the sockets of every
+/// port in the range, and the socket with a single port is kept as it is.
+pub(crate) fn expand_port_range(socket: &str) -> Vec<String> {
+    let Some((host, ports)) = socket.rsplit_once(':') else {
+        return vec![socket.to_string()];
+    };
+    let Some((start, end)) = ports.split_once('-') else {
+        return vec![socket.to_string()];
+    };
+    match (start.parse::<u16>(), end.parse::<u16>()) {
+        (Ok(start), Ok(end)) if start <= end => (start..=end)
+            .map(|port| format!("{}:{}", host, port))
+            .collect(),
+        _ => abort_call_site!(
+            "the port range of `{}` should be like `8000-8010` with the start not greater than the end",
+            socket
+        ),
+    }
+}
+
+/// Probe up to `retries` times with a small backoff between attempts, the probe is considered
+/// successful if any attempt succeeds.
+pub(crate) fn retry_probe<F: FnMut() -> bool>(retries: usize, mut probe: F) -> bool {
+    for attempt in 0..retries {
+        if probe() {
+            return true;
+        }
+        if attempt + 1 < retries {
+            std::thread::sleep(std::time::Duration::from_millis(100 * (attempt as u64 + 1)));
+        }
+    }
+    false
+}
+
+/// The TLS options of the http and https conditions
+#[cfg(feature = "http")]
+#[derive(Default)]
+pub(crate) struct TlsOptions {
+    /// accept the invalid certificates, ex: self-signed or expired certificates
+    pub(crate) danger_accept_invalid_certs: bool,
+    /// the PEM file of the custom root CA
+    pub(crate) root_cert: Option<String>,
+}
+
+/// Take out the `danger_accept_invalid_certs` and `root_cert="ca.pem"` options of the http and
+/// https conditions.
+#[cfg(feature = "http")]
+pub(crate) fn split_tls_options(items: Vec<&str>) -> (Vec<&str>, TlsOptions) {
+    let mut remaining = vec![];
+    let mut tls = TlsOptions::default();
+    for item in items {
+        if item == "danger_accept_invalid_certs" {
+            tls.danger_accept_invalid_certs = true;
+        } else if let Some(path) = item.strip_prefix("root_cert=") {
+            let path = path.trim_matches('"');
+            if path.is_empty() {
+                abort_call_site!("`root_cert` should be the path of a PEM file");
+            }
+            tls.root_cert = Some(path.to_string());
+        } else {
+            remaining.push(item);
+        }
+    }
+    (remaining, tls)
+}
+
+/// Take out the `max_ms=N` option of the http and https conditions, which is the latency budget of
+/// the response, and the response time is not limited if the option is absent.
+#[cfg(feature = "http")]
+pub(crate) fn split_max_ms_option(items: Vec<&str>) -> (Vec<&str>, Option<u64>) {
+    let mut remaining = vec![];
+    let mut max_ms = None;
+    for item in items {
+        if let Some(ms) = item.strip_prefix("max_ms=") {
+            max_ms = match ms.parse::<u64>() {
+                Ok(ms) if ms > 0 => Some(ms),
+                _ => abort_call_site!("`max_ms` should be a positive number of milliseconds"),
+            };
+        } else {
+            remaining.push(item);
+        }
+    }
+    (remaining, max_ms)
+}
+
+/// Probe like `retry_probe`, and the attempt only succeeds when the response time is within the
+/// latency budget.  If the probe fails, the response time of the last responded attempt is
+/// returned, or `None` if no attempt responded.
+#[cfg(feature = "http")]
+pub(crate) fn timed_retry_probe<F: FnMut() -> bool>(
+    retries: usize,
+    max_ms: Option<u64>,
+    mut probe: F,
+) -> Result<(), Option<u128>> {
+    let mut response_ms = None;
+    let in_time = retry_probe(retries, || {
+        let start = std::time::Instant::now();
+        if !probe() {
+            return false;
+        }
+        let elapsed = start.elapsed().as_millis();
+        response_ms = Some(elapsed);
+        match max_ms {
+            Some(max_ms) => elapsed <= max_ms as u128,
+            None => true,
+        }
+    });
+    if in_time {
+        Ok(())
+    } else {
+        Err(response_ms)
+    }
+}
+
+/// Probe the links of the http and https conditions, and return the links not responding and the
+/// links responding slower than the latency budget.  The link can be the alternatives joined with
+/// `||`, ex: `mirror1.example.com||mirror2.example.com`, which is fine when any of them responds.
+#[cfg(feature = "http")]
+pub(crate) fn probe_http_links(
+    scheme: &str,
+    links: &[&str],
+    retries: usize,
+    max_ms: Option<u64>,
+    client: &reqwest::blocking::Client,
+) -> (Vec<String>, Vec<String>) {
+    let mut missing_links = vec![];
+    let mut slow_links = vec![];
+    for link in links.iter() {
+        let mut failures = vec![];
+        let mut slow = false;
+        for alternative in link.split("||") {
+            let url = format!("{}://{}", scheme, alternative);
+            match timed_retry_probe(retries, max_ms, || client.head(&url).send().is_ok()) {
+                Ok(()) => {
+                    failures.clear();
+                    break;
+                }
+                Err(Some(response_ms)) => {
+                    slow = true;
+                    failures.push(format!(
+                        "{} responded in {}ms (> {}ms)",
+                        url,
+                        response_ms,
+                        max_ms.unwrap_or_default()
+                    ));
+                }
+                Err(None) => failures.push(url),
+            }
+        }
+        match failures.len() {
+            0 => (),
+            _ if slow => slow_links.push(failures.join(" || ")),
+            _ => missing_links.push(failures.join(" || ")),
+        }
+    }
+    (missing_links, slow_links)
+}
+
+/// The ignore message of the http and https conditions for the links not responding and the links
+/// responding slower than the latency budget.
+#[cfg(feature = "http")]
+pub(crate) fn http_ignore_msg(missing_links: &[String], slow_links: &[String]) -> String {
+    match (missing_links.len(), slow_links.len()) {
+        (0, 1) => format!("because {}", slow_links[0]),
+        (0, _) => format!(
+            "because following links responded slowly: \n{}\n",
+            slow_links.join("\n")
+        ),
+        (1, 0) => format!("because {} not response", missing_links[0]),
+        _ => format!(
+            "because following links not response: \n{}\n",
+            missing_links
+                .iter()
+                .chain(slow_links.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
+
+/// Build the client of the http and https conditions with the TLS options
+#[cfg(feature = "http")]
+pub(crate) fn http_client(tls: &TlsOptions) -> reqwest::blocking::Client {
+    let mut builder = reqwest::blocking::Client::builder();
+    if tls.danger_accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some(path) = &tls.root_cert {
+        let cert = match std::fs::read(path).map(|pem| reqwest::Certificate::from_pem(&pem)) {
+            Ok(Ok(cert)) => cert,
+            _ => abort_call_site!("`{}` is not a readable PEM certificate", path),
+        };
+        builder = builder.add_root_certificate(cert);
+    }
+    match builder.build() {
+        Ok(client) => client,
+        Err(e) => abort_call_site!("fail to build the http client: {}", e),
+    }
+}
+
+/// The generated check of the runtime http and https conditions, the links responding slower than
+/// `max_ms` are reported with the response time.
+#[cfg(all(feature = "runtime", feature = "http"))]
+pub(crate) fn http_check_tokens(
+    scheme: &str,
+    links: &[&str],
+    retries: usize,
+    cache_ttl: Option<u64>,
+    max_ms: Option<u64>,
+    tls: &TlsOptions,
+    ident: &Ident,
+) -> proc_macro2::TokenStream {
+    let client = http_client_tokens(tls);
+    // the alternatives joined with `||` are fine when any of them responds
+    let alternatives: Vec<Vec<String>> = links
+        .iter()
+        .map(|link| {
+            link.split("||")
+                .map(|alternative| format!("{}://{}", scheme, alternative))
+                .collect()
+        })
+        .collect();
+    let urls: Vec<String> = alternatives.iter().map(|urls| urls.join(" || ")).collect();
+    let probes: Vec<proc_macro2::TokenStream> = alternatives
+        .iter()
+        .map(|urls| {
+            let probes = urls
+                .iter()
+                .map(|url| http_probe_tokens(url, retries, cache_ttl, max_ms, tls));
+            quote! { (#(#probes)||*) }
+        })
+        .collect();
+    let (slow_links, track_response, report_failure) = match max_ms {
+        Some(max_ms) => (
+            quote! { let mut slow_links = vec![]; },
+            quote! { let mut response_ms: Option<u128> = None; },
+            quote! {
+                match response_ms {
+                    Some(ms) => slow_links.push(format!("{} responded in {}ms (> {}ms)", url, ms, #max_ms)),
+                    None => missing_links.push(url.to_string()),
+                }
+            },
+        ),
+        None => (
+            quote! { let slow_links: Vec<String> = vec![]; },
+            quote! {},
+            quote! { missing_links.push(url.to_string()); },
+        ),
+    };
+    quote! {
+        let mut missing_links: Vec<String> = vec![];
+        #slow_links
+        let client = #client;
+        #(
+            {
+                let url = #urls;
+                #track_response
+                if !#probes {
+                    #report_failure
+                }
+            }
+        )*
+        match (missing_links.len(), slow_links.len()) {
+            (0, 0) => {
+                #ident();
+                Ok(())
+            },
+            (0, 1) => Err(_ignore_reason(format_args!("because {}", slow_links[0]))),
+            (0, _) => Err(_ignore_reason(format_args!(
+                "because following links responded slowly: \n{}\n",
+                slow_links.join(", ")
+            ))),
+            (1, 0) => Err(_ignore_reason(format_args!(
+                "because {} not response",
+                missing_links[0]
+            ))),
+            _ => Err(_ignore_reason(format_args!(
+                "because following links not response: \n{}\n",
+                missing_links.iter().chain(slow_links.iter()).cloned().collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+}
+
+// the generated probe of one url, the response time is kept in `response_ms` for the message of
+// the latency budget
+#[cfg(all(feature = "runtime", feature = "http"))]
+fn http_probe_tokens(
+    url: &str,
+    retries: usize,
+    cache_ttl: Option<u64>,
+    max_ms: Option<u64>,
+    tls: &TlsOptions,
+) -> proc_macro2::TokenStream {
+    let probe = match max_ms {
+        Some(max_ms) => quote! {
+            {
+                let start = std::time::Instant::now();
+                client.head(#url).send().is_ok() && {
+                    let elapsed = start.elapsed().as_millis();
+                    response_ms = Some(elapsed);
+                    elapsed <= #max_ms as u128
+                }
+            }
+        },
+        None => quote! { client.head(#url).send().is_ok() },
+    };
+    // the result depends on the latency budget and the certificate checks, so they are a part of
+    // the key, ex: a pass without the certificate checks does not satisfy the strict one
+    let mut params = vec![];
+    if let Some(max_ms) = max_ms {
+        params.push(format!("max_ms={}", max_ms));
+    }
+    if tls.danger_accept_invalid_certs {
+        params.push("danger_accept_invalid_certs".to_string());
+    }
+    if let Some(root_cert) = &tls.root_cert {
+        params.push(format!("root_cert={}", root_cert));
+    }
+    let key = if params.is_empty() {
+        url.to_string()
+    } else {
+        format!("{}?{}", url, params.join("&"))
+    };
+    cached_probe_tokens(cache_ttl, key, retry_probe_tokens(retries, probe))
+}
+
+/// The generated code to build the client of the runtime http and https conditions, the root
+/// certificate is read when the example running.
+#[cfg(all(feature = "runtime", feature = "http"))]
+fn http_client_tokens(tls: &TlsOptions) -> proc_macro2::TokenStream {
+    let danger_accept_invalid_certs = tls.danger_accept_invalid_certs;
+    let add_root_cert = tls.root_cert.as_ref().map(|path| {
+        quote! {
+            let pem = std::fs::read(#path).expect("root certificate should be readable");
+            let cert = libtest_with::reqwest::Certificate::from_pem(&pem)
+                .expect("root certificate should be PEM encoded");
+            let builder = builder.add_root_certificate(cert);
+        }
+    });
+    quote! {
+        {
+            let builder = libtest_with::reqwest::blocking::Client::builder()
+                .danger_accept_invalid_certs(#danger_accept_invalid_certs);
+            #add_root_cert
+            builder.build().expect("http client should be built")
+        }
+    }
+}
+
+/// The generated code of `retry_probe` for runtime macros, the probe expression should be a
+/// `bool` expression.
+#[cfg(feature = "runtime")]
+pub(crate) fn retry_probe_tokens(
+    retries: usize,
+    probe: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let mut probe_success = false;
+            for attempt in 0..#retries {
+                if #probe {
+                    probe_success = true;
+                    break;
+                }
+                if attempt + 1 < #retries {
+                    std::thread::sleep(std::time::Duration::from_millis(100 * (attempt as u64 + 1)));
+                }
+            }
+            probe_success
+        }
+    }
+}
+
+/// Take out the `cache=SECS` option from the items of the runtime network conditions, and return
+/// the remaining items with the time to live of the cached probe result.
+#[cfg(feature = "runtime")]
+pub(crate) fn split_cache_option(items: Vec<&str>) -> (Vec<&str>, Option<u64>) {
+    let mut remaining = vec![];
+    let mut cache_ttl = None;
+    for item in items {
+        if let Some(ttl) = item.strip_prefix("cache=") {
+            cache_ttl = match ttl.parse::<u64>() {
+                Ok(ttl) if ttl > 0 => Some(ttl),
+                _ => abort_call_site!("`cache` should be a positive number of seconds"),
+            };
+        } else {
+            remaining.push(item);
+        }
+    }
+    (remaining, cache_ttl)
+}
+
+/// The generated code to reuse the probe result of the same target within the time to live, the
+/// cache is provided by `test_with::runner!` and shared in the test binary.
+#[cfg(feature = "runtime")]
+pub(crate) fn cached_probe_tokens(
+    cache_ttl: Option<u64>,
+    key: String,
+    probe: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match cache_ttl {
+        Some(ttl) => quote! { crate::_test_with_probe_cache(#key, #ttl, || #probe) },
+        None => probe,
+    }
+}
+
+/// Render the `#[test_with::runtime_*]` attributes of the test case, which are printed with the
+/// ignore reason when `TEST_WITH_EXPLAIN=1`.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_conditions(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .map(|seg| seg.ident.to_string().starts_with("runtime_"))
+                .unwrap_or(false)
+        })
+        .map(|attr| {
+            let path = attr
+                .path()
+                .segments
+                .iter()
+                .map(|seg| seg.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            match &attr.meta {
+                Meta::List(list) => {
+                    format!("#[{}({})]", path, tokens_to_string(list.tokens.clone()))
+                }
+                _ => format!("#[{}]", path),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Collect the tags of `#[test_with::tag(slow, db)]` on the runtime test, which are used to
+/// select the tests with `--tag` of the runner.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_tags(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| {
+            attr.path()
+                .segments
+                .first()
+                .is_some_and(|seg| seg.ident == "test_with")
+                && attr
+                    .path()
+                    .segments
+                    .last()
+                    .is_some_and(|seg| seg.ident == "tag")
+        })
+        .flat_map(|attr| match &attr.meta {
+            Meta::List(list) => split_attr_list(&tokens_to_string(list.tokens.clone()))
+                .into_iter()
+                .map(|tag| tag.trim_matches('"').to_string())
+                .collect::<Vec<_>>(),
+            _ => abort_call_site!("the tags are required, ex: `#[test_with::tag(slow, db)]`"),
+        })
+        .collect()
+}
+
+/// Check the runtime test case is marked with `#[test_with::assert_ignored]`
+#[cfg(feature = "runtime")]
+pub(crate) fn has_assert_ignored_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .first()
+            .is_some_and(|seg| seg.ident == "test_with")
+            && attr
+                .path()
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "assert_ignored")
+    })
+}
+
+/// The generated reporter of `#[test_with::module]`, each runtime test is called through it.  When
+/// the `TEST_WITH_JSON` environment variable is set to a file path, one JSON object per test will
+/// be appended to the file, and the human readable summary will still print out as usual.
+#[cfg(feature = "runtime")]
+pub(crate) fn runtime_reporter_tokens() -> proc_macro2::TokenStream {
+    quote! {
+        // the reason of the ignored test case is carried by the message of `Failed` with the
+        // `RUNTIME_IGNORE_PREFIX`, which is picked up by the runner
+        #[allow(dead_code)]
+        fn _ignore_reason(reason: std::fmt::Arguments) -> libtest_with::Failed {
+            format!("{}{}", libtest_with::RUNTIME_IGNORE_PREFIX, reason).into()
+        }
+
+        fn _strip_ignore_prefix(msg: &str) -> Option<&str> {
+            msg.strip_prefix(libtest_with::RUNTIME_IGNORE_PREFIX)
+        }
+
+        // the test case with `#[test_with::assert_ignored]` passes when it is ignored, and fails
+        // when it runs, such that the gating of the test case can be checked on CI
+        fn _assert_outcome(
+            assert_ignored: bool,
+            outcome: Result<(), libtest_with::Failed>,
+        ) -> Result<(), libtest_with::Failed> {
+            if !assert_ignored {
+                return outcome;
+            }
+            match outcome {
+                Err(failed) if failed.message().and_then(_strip_ignore_prefix).is_some() => Ok(()),
+                Ok(()) => Err("the test case is expected to be ignored, but it ran and passed".into()),
+                Err(failed) => Err(format!(
+                    "the test case is expected to be ignored, but it ran and failed: {}",
+                    failed.message().unwrap_or_default()
+                )
+                .into()),
+            }
+        }
+
+        fn _report_check(
+            module: &str,
+            name: &str,
+            condition: &str,
+            check: fn() -> Result<(), libtest_with::Failed>,
+        ) -> Result<(), libtest_with::Failed> {
+            let start = std::time::Instant::now();
+            let outcome = std::panic::catch_unwind(check);
+            let duration = start.elapsed();
+            if std::env::var("TEST_WITH_EXPLAIN").map(|v| v == "1").unwrap_or(false) {
+                if let Ok(Err(failed)) = &outcome {
+                    if let Some(reason) = failed.message().and_then(_strip_ignore_prefix) {
+                        eprintln!(
+                            "test-with: {}::{} is ignored with the condition `{}` {}",
+                            module, name, condition, reason
+                        );
+                    }
+                }
+            }
+            if let Ok(path) = std::env::var("TEST_WITH_JSON") {
+                let (result, reason) = match &outcome {
+                    Ok(Ok(())) => ("ok", String::new()),
+                    Ok(Err(failed)) => match failed.message() {
+                        Some(msg) => match _strip_ignore_prefix(msg) {
+                            Some(reason) => ("ignored", reason.to_string()),
+                            None => ("failed", msg.to_string()),
+                        },
+                        None => ("failed", String::new()),
+                    },
+                    Err(payload) => (
+                        "failed",
+                        payload
+                            .downcast_ref::<&str>()
+                            .map(|s| s.to_string())
+                            .or_else(|| payload.downcast_ref::<String>().cloned())
+                            .unwrap_or_default(),
+                    ),
+                };
+                let escape = |s: &str| {
+                    s.chars().fold(String::new(), |mut escaped, c| {
+                        match c {
+                            '"' => escaped.push_str("\\\""),
+                            '\\' => escaped.push_str("\\\\"),
+                            '\n' => escaped.push_str("\\n"),
+                            c if c.is_control() => {
+                                escaped.push_str(&format!("\\u{:04x}", c as u32))
+                            }
+                            c => escaped.push(c),
+                        }
+                        escaped
+                    })
+                };
+                let line = format!(
+                    "{{\"module\":\"{}\",\"name\":\"{}\",\"result\":\"{}\",\"reason\":\"{}\",\"duration_ms\":{}}}\n",
+                    escape(module),
+                    escape(name),
+                    result,
+                    escape(&reason),
+                    duration.as_millis()
+                );
+                if let Ok(mut file) = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                {
+                    use std::io::Write;
+                    let _ = file.write_all(line.as_bytes());
+                }
+            }
+            match outcome {
+                Ok(result) => result,
+                Err(payload) => std::panic::resume_unwind(payload),
+            }
+        }
+    }
+}
+
+/// The `before_each` and `after_each` hooks of the module, which run around the body of every test
+/// case, so they only run when the conditions of the test case are met, and a panic of the hooks
+/// is reported as the failure of the test case.  `after_each` runs in a drop guard, so it still
+/// runs when the test case or `before_each` panics.
+#[cfg(feature = "runtime")]
+pub(crate) fn each_hooks_tokens(
+    before_each: Option<&Ident>,
+    after_each: Option<&Ident>,
+) -> proc_macro2::TokenStream {
+    let before_each = before_each.map(|hook| quote! { #hook(); });
+    let after_each = after_each.map(|hook| quote! { #hook(); });
+    quote! {
+        struct _EachHooks;
+
+        impl Drop for _EachHooks {
+            fn drop(&mut self) {
+                #after_each
+            }
+        }
+
+        fn _each_hooks() -> _EachHooks {
+            let hooks = _EachHooks;
+            #before_each
+            hooks
+        }
+    }
+}
+
+/// Run the hooks of the module around the body of the test case, see [each_hooks_tokens].
+#[cfg(feature = "runtime")]
+pub(crate) fn with_each_hooks(mut item_fn: ItemFn) -> ItemFn {
+    item_fn
+        .block
+        .stmts
+        .insert(0, parse_quote! { let _each_hooks = _each_hooks(); });
+    item_fn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        env_var_exists, env_var_group, env_var_group_exists, env_var_nonempty, env_var_value,
+        expand_env_vars, expand_port_range, has_test_attr, has_test_cfg, ignore_proptest_macro,
+        ignore_test_fn, missing_env_var_msg, retry_probe, sanitize_env_vars_attr,
+        split_allow_non_test_option, split_at_least_option, split_attr_list,
+        split_condition_options, split_env_value_set, split_nonempty_option, split_reason_option,
+        split_retries_option, tokens_to_string,
+    };
+    use quote::ToTokens;
+    use syn::{parse_quote, ItemFn};
+
+    #[test]
+    fn sanitize_single_env_var() {
+        //* Given
+        let env_var = "FOO";
+
+        let attr_str = env_var.to_string();
+
+        //* When
+        let result = sanitize_env_vars_attr(&attr_str).collect::<Vec<_>>();
+
+        //* Then
+        assert_eq!(result, vec!["FOO"]);
+    }
+
+    #[test]
+    fn sanitize_multiple_env_vars() {
+        //* Given
+        let env_var1 = "FOO";
+        let env_var2 = "BAR";
+        let env_var3 = "BAZ";
+
+        let attr_str = format!("\t{},\n\t{},\n\t{}", env_var1, env_var2, env_var3);
+
+        //* When
+        let result = sanitize_env_vars_attr(&attr_str).collect::<Vec<_>>();
+
+        //* Then
+        assert_eq!(result, vec!["FOO", "BAR", "BAZ"]);
+    }
+
+    #[test]
+    fn split_multiline_attr_list() {
+        //* Given
+        let attr_str = "\t\"/tmp\",\n\t127.0.0.1:80,\r\n\tretries=2,\n";
+
+        //* When
+        let result = split_attr_list(attr_str);
+
+        //* Then
+        assert_eq!(result, vec!["\"/tmp\"", "127.0.0.1:80", "retries=2"]);
+    }
+
+    #[test]
+    fn sanitize_env_vars_with_whitespace() {
+        //* Given
+        let env_var = "FOO BAR";
+
+        let attr_str = env_var.to_string();
+
+        //* When
         let result = sanitize_env_vars_attr(&attr_str).collect::<Vec<_>>();
 
         //* Then
         assert_eq!(result, vec!["FOO BAR"]);
     }
+
+    #[test]
+    fn env_attr_keeps_spaces_in_values() {
+        //* Given
+        let attr = quote::quote!(PWD, GREETING = hello  world, at_least = 1);
+
+        //* When
+        let items = super::env_attr_items(attr);
+
+        //* Then
+        assert_eq!(
+            items,
+            vec![
+                ("PWD".to_string(), None),
+                ("GREETING".to_string(), Some("hello world".to_string())),
+                ("at_least".to_string(), Some("1".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn attr_keeps_spaces_in_quotes() {
+        //* Given
+        let attr = quote::quote!(/ etc / hostname, "examples/fixtures/with space.txt");
+
+        //* When
+        let attr_str = tokens_to_string(attr);
+
+        //* Then
+        assert_eq!(
+            attr_str,
+            "/etc/hostname,\"examples/fixtures/with space.txt\""
+        );
+    }
+
+    #[test]
+    fn split_with_at_least_option() {
+        //* Given
+        let attr_str = "DB_URL, API_KEY, API_SECRET, at_least = 2";
+
+        //* When
+        let (var_names, at_least) = split_at_least_option(attr_str);
+
+        //* Then
+        assert_eq!(var_names, vec!["DB_URL", "API_KEY", "API_SECRET"]);
+        assert_eq!(at_least, Some(2));
+    }
+
+    #[test]
+    fn split_without_at_least_option() {
+        //* Given
+        let attr_str = "DB_URL,API_KEY";
+
+        //* When
+        let (var_names, at_least) = split_at_least_option(attr_str);
+
+        //* Then
+        assert_eq!(var_names, vec!["DB_URL", "API_KEY"]);
+        assert_eq!(at_least, None);
+    }
+
+    #[test]
+    fn env_var_with_prefix() {
+        //* Given
+        let names = ["CARGO_*", "NOT_EXIST_PREFIX_*"];
+
+        //* When
+        let exists: Vec<_> = names.iter().map(|n| env_var_exists(n)).collect();
+
+        //* Then
+        assert_eq!(exists, vec![true, false]);
+        assert_eq!(
+            missing_env_var_msg(names[1]),
+            "because no variable with prefix NOT_EXIST_PREFIX_ found"
+        );
+    }
+
+    #[test]
+    fn env_var_with_aliases() {
+        //* Given
+        let names = ["NOT_EXIST_URL | CARGO", "NOT_EXIST_URL|NOT_EXIST_DB_URL"];
+
+        //* When
+        let exists: Vec<_> = names.iter().map(|n| env_var_exists(n)).collect();
+
+        //* Then
+        assert_eq!(exists, vec![true, false]);
+        assert_eq!(
+            missing_env_var_msg(names[1]),
+            "because none of variables NOT_EXIST_URL, NOT_EXIST_DB_URL found"
+        );
+    }
+
+    #[test]
+    fn env_var_in_group() {
+        //* Given
+        let attr_str = "group(CARGO, NOT_EXIST_SECRET), group(NOT_EXIST_KEY, NOT_EXIST_SECRET)";
+
+        //* When
+        let groups: Vec<_> = sanitize_env_vars_attr(attr_str)
+            .map(|item| env_var_group(item).expect("group"))
+            .collect();
+
+        //* Then
+        assert_eq!(groups[0], vec!["CARGO", "NOT_EXIST_SECRET"]);
+        assert_eq!(
+            env_var_group_exists(&groups[0]),
+            Err("the variables of group(CARGO, NOT_EXIST_SECRET) should be all set or all unset, but NOT_EXIST_SECRET not set".to_string())
+        );
+        assert_eq!(env_var_group_exists(&groups[1]), Ok(false));
+    }
+
+    #[test]
+    fn expand_socket_with_port_range() {
+        //* Given
+        let sockets = ["127.0.0.1:8000-8002", "[::1]:80-81", "127.0.0.1:8000"];
+
+        //* When
+        let expanded: Vec<_> = sockets.iter().map(|s| expand_port_range(s)).collect();
+
+        //* Then
+        assert_eq!(
+            expanded,
+            vec![
+                vec!["127.0.0.1:8000", "127.0.0.1:8001", "127.0.0.1:8002"],
+                vec!["[::1]:80", "[::1]:81"],
+                vec!["127.0.0.1:8000"],
+            ]
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn split_with_max_ms_option() {
+        //* Given
+        let items = vec!["api.example.com", "max_ms=500"];
+
+        //* When
+        let (links, max_ms) = super::split_max_ms_option(items);
+
+        //* Then
+        assert_eq!(links, vec!["api.example.com"]);
+        assert_eq!(max_ms, Some(500));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn slow_link_ignore_msg() {
+        //* Given
+        let slow_links = vec!["http://api.example.com responded in 900ms (> 500ms)".to_string()];
+
+        //* When
+        let ignore_msg = super::http_ignore_msg(&[], &slow_links);
+
+        //* Then
+        assert_eq!(
+            ignore_msg,
+            "because http://api.example.com responded in 900ms (> 500ms)"
+        );
+    }
+
+    #[test]
+    fn split_without_retries_option() {
+        //* Given
+        let attr_str = "httpbin.org,www.rust-lang.org";
+
+        //* When
+        let (items, retries) = split_retries_option(attr_str);
+
+        //* Then
+        assert_eq!(items, vec!["httpbin.org", "www.rust-lang.org"]);
+        assert_eq!(retries, 1);
+    }
+
+    #[test]
+    fn split_with_retries_option() {
+        //* Given
+        let attr_str = "8.8.8.8:53,retries=3";
+
+        //* When
+        let (items, retries) = split_retries_option(attr_str);
+
+        //* Then
+        assert_eq!(items, vec!["8.8.8.8:53"]);
+        assert_eq!(retries, 3);
+    }
+
+    #[test]
+    fn retry_probe_until_success() {
+        //* Given
+        let mut attempts = 0;
+
+        //* When
+        let success = retry_probe(3, || {
+            attempts += 1;
+            attempts == 2
+        });
+
+        //* Then
+        assert!(success);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn retry_probe_all_failed() {
+        //* Given
+        let mut attempts = 0;
+
+        //* When
+        let success = retry_probe(2, || {
+            attempts += 1;
+            false
+        });
+
+        //* Then
+        assert!(!success);
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn ignore_test_fn_keeps_body() {
+        //* Given
+        let item_fn: ItemFn = parse_quote! {
+            #[test]
+            fn test_gated() {
+                let value: u32 = 1;
+                assert_eq!(value, 1);
+            }
+        };
+
+        //* When
+        let ignored = ignore_test_fn(item_fn.clone(), &"because gated".to_string());
+
+        //* Then
+        assert_eq!(
+            ignored.block.to_token_stream().to_string(),
+            item_fn.block.to_token_stream().to_string()
+        );
+        assert_eq!(ignored.attrs.len(), 2);
+        assert!(ignored.attrs[1].path().is_ident("ignore"));
+    }
+
+    #[test]
+    fn ignore_tests_in_proptest_block() {
+        //* Given
+        let item_macro: syn::ItemMacro = parse_quote! {
+            proptest! {
+                #[test]
+                fn test_add(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+            }
+        };
+
+        //* When
+        let ignored = ignore_proptest_macro(item_macro, &"because gated".to_string());
+
+        //* Then
+        assert_eq!(
+            ignored.mac.tokens.to_string(),
+            quote::quote! {
+                #[ignore = "because gated"]
+                #[test]
+                fn test_add(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn ignore_pub_tests_in_proptest_block() {
+        //* Given
+        let item_macro: syn::ItemMacro = parse_quote! {
+            proptest! {
+                #![proptest_config(ProptestConfig::with_cases(8))]
+                #[test]
+                pub fn test_add(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+
+                #[test]
+                pub(crate) fn test_sub(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+            }
+        };
+
+        //* When
+        let ignored = ignore_proptest_macro(item_macro, &"because gated".to_string());
+
+        //* Then
+        assert_eq!(
+            ignored.mac.tokens.to_string(),
+            quote::quote! {
+                #![proptest_config(ProptestConfig::with_cases(8))]
+                #[ignore = "because gated"]
+                #[test]
+                pub fn test_add(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+
+                #[ignore = "because gated"]
+                #[test]
+                pub(crate) fn test_sub(a in 0..10u32) {
+                    prop_assert!(a < 10);
+                }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn detect_test_attrs_of_other_frameworks() {
+        //* Given
+        let rstest_fn: ItemFn = parse_quote! {
+            #[rstest::rstest]
+            #[case(0)]
+            fn test_case(#[case] input: u32) {}
+        };
+        let test_case_fn: ItemFn = parse_quote! {
+            #[test_case(0)]
+            fn test_case(input: u32) {}
+        };
+        let plain_fn: ItemFn = parse_quote! {
+            #[inline]
+            fn helper() {}
+        };
+
+        //* When
+        let detected: Vec<_> = [rstest_fn, test_case_fn, plain_fn]
+            .iter()
+            .map(|item_fn| has_test_attr(&item_fn.attrs))
+            .collect();
+
+        //* Then
+        assert_eq!(detected, vec![true, true, false]);
+    }
+
+    #[test]
+    fn env_var_with_nonempty_option() {
+        //* Given
+        std::env::set_var("TEST_WITH_EMPTY_VAR", "");
+        std::env::set_var("TEST_WITH_FILLED_VAR", "value");
+
+        //* When
+        let (attr_str, nonempty) = split_nonempty_option("A, nonempty, group(B, C)");
+        let values: Vec<bool> = [
+            "TEST_WITH_EMPTY_VAR",
+            "TEST_WITH_FILLED_VAR",
+            "TEST_WITH_EMPTY_VAR | TEST_WITH_FILLED_VAR",
+        ]
+        .iter()
+        .map(|name| env_var_nonempty(name))
+        .collect();
+
+        //* Then
+        assert_eq!((attr_str.as_str(), nonempty), ("A,group(B, C)", true));
+        assert!(env_var_exists("TEST_WITH_EMPTY_VAR"));
+        assert_eq!(values, vec![false, true, true]);
+    }
+
+    #[test]
+    fn env_var_with_value_set() {
+        //* Given
+        std::env::set_var("TEST_WITH_STAGE", "staging");
+        let attr_str = "TEST_WITH_STAGEin[staging,\"prod\"],PWD";
+
+        //* When
+        let items: Vec<_> = sanitize_env_vars_attr(attr_str)
+            .map(split_env_value_set)
+            .collect();
+
+        //* Then
+        assert_eq!(
+            items,
+            vec![
+                (
+                    "TEST_WITH_STAGE",
+                    Some(vec!["staging".to_string(), "prod".to_string()])
+                ),
+                ("PWD", None),
+            ]
+        );
+        assert_eq!(env_var_value("NOT_SAYING | TEST_WITH_STAGE"), "staging");
+    }
+
+    #[test]
+    fn expand_env_vars_in_name() {
+        //* Given
+        std::env::set_var("TEST_WITH_LOCK_JOB", "42");
+        std::env::remove_var("TEST_WITH_LOCK_UNSET");
+
+        //* When
+        let expanded = expand_env_vars("DB_${TEST_WITH_LOCK_JOB}");
+        let unset = expand_env_vars("DB_${TEST_WITH_LOCK_UNSET}");
+        let plain = expand_env_vars("DB");
+
+        //* Then
+        assert_eq!(expanded, "DB_42");
+        assert_eq!(unset, "DB_");
+        assert_eq!(plain, "DB");
+    }
+
+    #[test]
+    fn split_with_allow_non_test_option() {
+        //* Given
+        let attr_str = "PWD,allow_non_test";
+
+        //* When
+        let (remaining, allow_non_test) = split_allow_non_test_option(attr_str);
+
+        //* Then
+        assert_eq!(remaining, "PWD");
+        assert!(allow_non_test);
+    }
+
+    #[test]
+    fn split_with_reason_option() {
+        //* Given
+        let attr_str = r#"db:5432,reason="needs the staging DB, \"tunnel\"",retries=3"#;
+
+        //* When
+        let (remaining, reason) = split_reason_option(attr_str);
+
+        //* Then
+        assert_eq!(remaining, "db:5432,retries=3");
+        assert_eq!(reason.as_deref(), Some(r#"needs the staging DB, "tunnel""#));
+        assert_eq!(split_reason_option("PWD"), ("PWD".to_string(), None));
+    }
+
+    #[test]
+    fn split_condition_options_of_module() {
+        //* Given
+        let attr_str = r#"PWD,allow_non_test,reason="needs the shell""#;
+
+        //* When
+        let (remaining, reason, allow_non_test) = split_condition_options(attr_str);
+
+        //* Then
+        assert_eq!(remaining, "PWD");
+        assert_eq!(reason.as_deref(), Some("needs the shell"));
+        assert!(allow_non_test);
+    }
+
+    #[test]
+    fn split_without_allow_non_test_option() {
+        //* Given
+        let attr_str = "PWD,HOME";
+
+        //* When
+        let (remaining, allow_non_test) = split_allow_non_test_option(attr_str);
+
+        //* Then
+        assert_eq!(remaining, "PWD,HOME");
+        assert!(!allow_non_test);
+    }
+
+    #[test]
+    fn detect_test_cfg() {
+        //* Given
+        let test_mod: syn::ItemMod = parse_quote! {
+            #[cfg(test)]
+            mod tests {}
+        };
+        let nested_test_mod: syn::ItemMod = parse_quote! {
+            #[allow(dead_code)]
+            #[cfg(all(unix, test))]
+            mod tests {}
+        };
+        let not_test_mod: syn::ItemMod = parse_quote! {
+            #[cfg(not(test))]
+            mod tests {}
+        };
+        let plain_mod: syn::ItemMod = parse_quote! {
+            #[cfg(unix)]
+            mod tests {}
+        };
+
+        //* When
+        let detected: Vec<bool> = [test_mod, nested_test_mod, not_test_mod, plain_mod]
+            .iter()
+            .map(|m| has_test_cfg(&m.attrs))
+            .collect();
+
+        //* Then
+        assert_eq!(detected, vec![true, true, false, false]);
+    }
 }